@@ -0,0 +1,261 @@
+//! # CSV list of players
+//!
+//! One row per player, after a header row with the names of the columns:
+//! `starting_rank_number`, `sex`, `title`, `name`, `fide_rating`, `fide_federation`,
+//! `fide_number`, `birth_date`, `points`, `rank`, then `round 1`, `round 2`... Values
+//! use the JSON format (see [`openswisspairings_lib::json::Player`]), and each round is
+//! written `<opponent> <color> <result>`, e.g. `12 w 1`, without the opponent for byes,
+//! e.g. `- F`, and only with the result when there is no color, e.g. `H`.
+//!
+//! When reading, columns may be in any order and only `starting_rank_number` is
+//! required. The header, the teams and the extension records aren't part of the list.
+use std::{collections::BTreeMap, str::FromStr};
+
+use openswisspairings_lib::json::{
+    Constraints, Extensions, Header, Player, Round, Tournament, VERSION,
+};
+
+/// The columns of the player data, before the rounds.
+const COLUMNS: [&str; 10] = [
+    "starting_rank_number",
+    "sex",
+    "title",
+    "name",
+    "fide_rating",
+    "fide_federation",
+    "fide_number",
+    "birth_date",
+    "points",
+    "rank",
+];
+
+/// The prefix of the columns of the rounds.
+const ROUND: &str = "round ";
+
+/// Quote a cell if it contains a separator, a quote or a line break.
+fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Split a line into cells, quoted cells may contain separators.
+fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if cell.is_empty() => quoted = true,
+            (',', false) => cells.push(std::mem::take(&mut cell)),
+            (c, _) => cell.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("Expected a closing quote".to_string());
+    }
+    cells.push(cell);
+    Ok(cells)
+}
+
+/// Write an optional value as a cell.
+fn text<T: ToString>(value: Option<&T>) -> String {
+    value.map(ToString::to_string).unwrap_or_default()
+}
+
+/// Write a round as a cell.
+fn write_round(round: &Round) -> String {
+    [
+        round.id.map(|id| id.to_string()),
+        round.color.clone(),
+        round.result.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>()
+    .join(" ")
+}
+
+/// Read a round from a cell, see [`write_round`].
+fn read_round(cell: &str) -> Result<Round, String> {
+    let parts: Vec<&str> = cell.split_whitespace().collect();
+    let (id, color, result) = match parts.as_slice() {
+        [id, color, result] => (Some(*id), Some(*color), *result),
+        [color, result] => (None, Some(*color), *result),
+        [result] => (None, None, *result),
+        _ => return Err(format!("Expected a round, found {cell}")),
+    };
+
+    Ok(Round {
+        id: id
+            .map(str::parse::<u16>)
+            .transpose()
+            .map_err(|_| format!("Expected an opponent, found {cell}"))?,
+        color: color.map(ToString::to_string),
+        result: Some(result.to_string()),
+    })
+}
+
+/// Write the players of a tournament as a CSV list.
+pub fn write(tournament: &Tournament) -> String {
+    let rounds = tournament
+        .players
+        .iter()
+        .map(|player| player.rounds.len())
+        .max()
+        .unwrap_or_default();
+
+    let header = COLUMNS
+        .iter()
+        .map(ToString::to_string)
+        .chain((1..=rounds).map(|round| format!("{ROUND}{round}")))
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let rows = tournament.players.iter().map(|player| {
+        let cells = [
+            player.starting_rank_number.to_string(),
+            text(player.sex.as_ref()),
+            text(player.title.as_ref()),
+            text(player.name.as_ref()),
+            text(player.fide_rating.as_ref()),
+            text(player.fide_federation.as_ref()),
+            text(player.fide_number.as_ref()),
+            text(player.birth_date.as_ref()),
+            text(player.points.as_ref()),
+            text(player.rank.as_ref()),
+        ];
+        let rounds = (0..rounds).map(|round| {
+            player
+                .rounds
+                .get(round)
+                .and_then(Option::as_ref)
+                .map(write_round)
+                .unwrap_or_default()
+        });
+
+        cells
+            .into_iter()
+            .chain(rounds)
+            .map(|cell| escape(&cell))
+            .collect::<Vec<String>>()
+            .join(",")
+    });
+
+    std::iter::once(header)
+        .chain(rows)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Read a value of a row, empty cells are [`None`].
+fn value<T: FromStr>(cell: Option<&String>, column: &str) -> Result<Option<T>, String> {
+    cell.filter(|cell| !cell.trim().is_empty())
+        .map(|cell| {
+            cell.trim()
+                .parse::<T>()
+                .map_err(|_| format!("Invalid value {cell} in column {column}"))
+        })
+        .transpose()
+}
+
+/// Read a row of the list.
+fn read_player(header: &[String], row: &[String]) -> Result<Player, String> {
+    let cell = |column: &str| {
+        header
+            .iter()
+            .position(|title| title == column)
+            .and_then(|index| row.get(index))
+    };
+
+    let mut rounds = vec![];
+    for (title, cell) in header.iter().zip(row) {
+        let Some(round) = title.strip_prefix(ROUND) else {
+            continue;
+        };
+        let index = round
+            .parse::<usize>()
+            .ok()
+            .and_then(|round| round.checked_sub(1))
+            .ok_or_else(|| format!("Expected a round number, found {title}"))?;
+        if rounds.len() <= index {
+            rounds.resize(index + 1, None);
+        }
+        if let Some(slot) = rounds.get_mut(index) {
+            *slot = Some(cell.trim())
+                .filter(|cell| !cell.is_empty())
+                .map(read_round)
+                .transpose()?;
+        }
+    }
+
+    Ok(Player {
+        starting_rank_number: value(
+            cell("starting_rank_number"),
+            "starting_rank_number",
+        )?
+        .ok_or_else(|| "Expected a starting rank number".to_string())?,
+        sex: value(cell("sex"), "sex")?,
+        title: value(cell("title"), "title")?,
+        name: value(cell("name"), "name")?,
+        fide_rating: value(cell("fide_rating"), "fide_rating")?,
+        fide_federation: value(cell("fide_federation"), "fide_federation")?,
+        fide_number: value(cell("fide_number"), "fide_number")?,
+        birth_date: value(cell("birth_date"), "birth_date")?,
+        points: value(cell("points"), "points")?,
+        rank: value(cell("rank"), "rank")?,
+        rounds,
+        metadata: BTreeMap::new(),
+    })
+}
+
+/// Read a CSV list of players.
+///
+/// Returns the line number and the problem if the list is invalid.
+pub fn read(contents: &str) -> Result<Tournament, (usize, String)> {
+    let mut lines = contents
+        .lines()
+        .zip(1..)
+        .filter(|(line, _)| !line.trim().is_empty());
+
+    let header = lines
+        .next()
+        .map(|(line, _)| split(line))
+        .transpose()
+        .map_err(|error| (1, error))?
+        .unwrap_or_default();
+    if let Some(title) = header
+        .iter()
+        .find(|title| !COLUMNS.contains(&title.as_str()) && !title.starts_with(ROUND))
+    {
+        return Err((1, format!("Unknown column {title}")));
+    }
+
+    let players = lines
+        .map(|(line, number)| {
+            split(line)
+                .and_then(|row| read_player(&header, &row))
+                .map_err(|error| (number, error))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Tournament {
+        version: VERSION,
+        header: Header::default(),
+        scoring: None,
+        players,
+        teams: vec![],
+        constraints: Constraints::default(),
+        extensions: Extensions::default(),
+    })
+}
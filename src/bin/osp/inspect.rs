@@ -0,0 +1,175 @@
+//! The `inspect` command: the header, the players and the crosstable.
+use openswisspairings_lib::{
+    trf::{player::Section, FormatVersion},
+    Situation,
+};
+
+use crate::{
+    name,
+    table::{render, Align},
+};
+
+/// Format a parsed field, or an empty string if it is empty or incorrectly formatted.
+fn field<T: ToString, E>(field: Result<&Option<T>, E>) -> String {
+    field
+        .ok()
+        .and_then(Option::as_ref)
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
+/// Format the points of a player with one decimal, as in the standings.
+fn points(player: &Section) -> String {
+    player
+        .points()
+        .ok()
+        .copied()
+        .flatten()
+        .map(|points| format!("{points:.1}"))
+        .unwrap_or_default()
+}
+
+/// Write the header of the tournament, one record per line.
+fn header(situation: &Situation) -> String {
+    let tournament = situation.tournament();
+    let date = |date: Result<&Option<_>, _>| {
+        date.ok()
+            .and_then(Option::as_ref)
+            .map(String::from)
+            .unwrap_or_default()
+    };
+    let format = match situation.format_version() {
+        FormatVersion::Trf16 => "TRF16",
+        FormatVersion::Trf2025 => "TRF-2025",
+    };
+
+    [
+        (
+            "Tournament",
+            tournament.name().unwrap_or_default().to_string(),
+        ),
+        ("City", tournament.city().unwrap_or_default().to_string()),
+        (
+            "Federation",
+            tournament.federation().unwrap_or_default().to_string(),
+        ),
+        ("Start", date(tournament.start_date())),
+        ("End", date(tournament.end_date())),
+        (
+            "Chief arbiter",
+            tournament.chief_arbiter().unwrap_or_default().to_string(),
+        ),
+        ("Players", situation.players().len().to_string()),
+        (
+            "Rounds",
+            situation
+                .number_of_rounds()
+                .map(|rounds| rounds.to_string())
+                .unwrap_or_default(),
+        ),
+        ("Format", format.to_string()),
+    ]
+    .into_iter()
+    .filter(|(_, value)| !value.is_empty())
+    .map(|(title, value)| format!("{:<15}{value}", format!("{title}:")))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+/// Write the table of the players.
+fn players(players: &[Section]) -> String {
+    let rows: Vec<Vec<String>> = players
+        .iter()
+        .map(|player| {
+            vec![
+                player
+                    .starting_rank_number()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                player
+                    .title()
+                    .ok()
+                    .and_then(Option::as_ref)
+                    .map(String::from)
+                    .unwrap_or_default(),
+                name(player),
+                player.fide_federation().unwrap_or_default().to_string(),
+                field(player.fide_rating()),
+                field(player.fide_number()),
+                points(player),
+                field(player.rank()),
+            ]
+        })
+        .collect();
+
+    render(
+        &[
+            ("No", Align::Right),
+            ("Title", Align::Left),
+            ("Name", Align::Left),
+            ("Fed", Align::Left),
+            ("Rating", Align::Right),
+            ("FIDE ID", Align::Right),
+            ("Pts", Align::Right),
+            ("Rank", Align::Right),
+        ],
+        &rows,
+    )
+}
+
+/// Write the crosstable: the opponent, colour and result of each player in each round.
+fn crosstable(players: &[Section]) -> String {
+    let rounds = players
+        .iter()
+        .map(|player| player.rounds().len())
+        .max()
+        .unwrap_or_default();
+
+    let rows: Vec<Vec<String>> = players
+        .iter()
+        .map(|player| {
+            let cells = (0..rounds).map(|round| {
+                player
+                    .rounds()
+                    .get(round)
+                    .and_then(Option::as_ref)
+                    .map(|section| String::from(section).trim().to_string())
+                    .unwrap_or_default()
+            });
+
+            [
+                player
+                    .starting_rank_number()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                name(player),
+                points(player),
+            ]
+            .into_iter()
+            .chain(cells)
+            .collect()
+        })
+        .collect();
+
+    let titles: Vec<String> = (1..=rounds).map(|round| format!("R{round}")).collect();
+    let columns: Vec<(&str, Align)> = [
+        ("No", Align::Right),
+        ("Name", Align::Left),
+        ("Pts", Align::Right),
+    ]
+    .into_iter()
+    .chain(titles.iter().map(|title| (title.as_str(), Align::Right)))
+    .collect();
+
+    render(&columns, &rows)
+}
+
+/// Write the header, the players and the crosstable of a situation.
+pub fn inspect(situation: &Situation) -> String {
+    [
+        header(situation),
+        players(situation.players()),
+        crosstable(situation.players()),
+    ]
+    .join("\n\n")
+}
@@ -0,0 +1,362 @@
+//! # `osp`
+//!
+//! A command-line tool to inspect, validate and convert TRF files, compute the standings
+//! and pair the next round of a tournament.
+//!
+//! Build it with the `cli` feature: `cargo install openswisspairings_lib --features cli`.
+//!
+//! ```text
+//! osp inspect <file>
+//! osp validate [--strict] [--json] <file>
+//! osp convert [--to <trf|json|csv>] <input> <output>
+//! osp standings [--tiebreaks <BH,SB,...>] <file>
+//! osp pair [--round <n>] [--system <dutch|burstein|dubov|lim>] <file>
+//! ```
+//!
+//! Files are read and written according to their extension: `.json` for the JSON format
+//! (see [`openswisspairings_lib::json`]), `.csv` for a list of players with their rounds
+//! (see [`csv`]) and TRF otherwise. `-` reads from the standard input, or writes to the
+//! standard output.
+//!
+//! The exit code is 0 on success, 1 if `validate` found errors and 2 if the command
+//! failed.
+#![deny(
+    missing_docs,
+    clippy::missing_docs_in_private_items,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::indexing_slicing
+)]
+
+mod csv;
+mod inspect;
+mod pair;
+mod standings;
+mod table;
+mod validate;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use openswisspairings_lib::{
+    json::{JsonError, Tournament},
+    pairing::PairingError,
+    trf::{diagnostic::Diagnostics, player::Section, ParseMode},
+    Situation,
+};
+use thiserror::Error;
+
+/// The help message.
+const USAGE: &str = "\
+Usage: osp <command> [options] <file>
+
+Commands:
+  inspect <file>                      Show the header, the players and the crosstable
+  validate [--strict] [--json] <file> Report the problems found in a file
+  convert [--to <format>] <in> <out>  Convert between TRF, JSON and CSV
+  standings [--tiebreaks <list>] <file>
+                                      Rank the players, e.g. --tiebreaks BH-C1,BH,SB
+  pair [--round <n>] [--system <name>] <file>
+                                      Pair a round, with the dutch (default),
+                                      burstein, dubov or lim system
+  help                                Show this message
+
+Files ending with .json or .csv are read and written in that format, other files as
+TRF. Use - for the standard input or output.";
+
+/// An error of the command-line tool.
+#[derive(Error, Debug)]
+pub enum CliError {
+    /// Error when the arguments are invalid.
+    #[error("{0}\n\n{USAGE}")]
+    UsageError(String),
+
+    /// Error when a file can't be read or written.
+    #[error("Couldn't access {0}: {1}")]
+    IoError(String, io::Error),
+
+    /// Error when a JSON file is invalid.
+    #[error("Invalid JSON in {0}: {1}")]
+    InvalidJsonError(String, serde_json::Error),
+
+    /// Error when a CSV file is invalid, stores the line number.
+    #[error("Invalid CSV in {0}, line {1}: {2}")]
+    InvalidCsvError(String, usize, String),
+
+    /// Error when a tournament can't be converted to or from the JSON format.
+    #[error(transparent)]
+    ConversionError(#[from] JsonError),
+
+    /// Error when a round can't be paired.
+    #[error(transparent)]
+    PairingError(#[from] PairingError),
+}
+
+/// A file format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Tournament Report File.
+    Trf,
+
+    /// The JSON format of [`openswisspairings_lib::json`].
+    Json,
+
+    /// A list of players, see [`csv`].
+    Csv,
+}
+
+impl Format {
+    /// Get the format of a file from its extension, TRF by default.
+    fn of(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|i| i.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Trf,
+        }
+    }
+}
+
+impl TryFrom<&str> for Format {
+    type Error = CliError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "trf" => Ok(Self::Trf),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => Err(CliError::UsageError(format!("Unknown format {other}"))),
+        }
+    }
+}
+
+/// The arguments of a command: options with a value, flags and positional arguments.
+#[derive(Debug, Default)]
+pub struct Arguments {
+    /// Options with a value, e.g. `--round 4`.
+    options: BTreeMap<String, String>,
+
+    /// Flags, e.g. `--strict`.
+    flags: BTreeSet<String>,
+
+    /// Positional arguments, e.g. the file.
+    positional: Vec<String>,
+}
+
+impl Arguments {
+    /// Read the arguments of a command.
+    ///
+    /// `options` are the names of the options which take a value, `flags` the names of
+    /// the flags, both without the leading `--`.
+    fn parse(
+        arguments: impl IntoIterator<Item = String>,
+        options: &[&str],
+        flags: &[&str],
+    ) -> Result<Self, CliError> {
+        let mut result = Self::default();
+        let mut arguments = arguments.into_iter();
+
+        while let Some(argument) = arguments.next() {
+            let Some(name) = argument.strip_prefix("--") else {
+                result.positional.push(argument);
+                continue;
+            };
+            let (name, value) =
+                name.split_once('=').map_or((name, None), |(name, value)| {
+                    (name, Some(value.to_string()))
+                });
+
+            if flags.contains(&name) {
+                result.flags.insert(name.to_string());
+            } else if options.contains(&name) {
+                let value = value.or_else(|| arguments.next()).ok_or_else(|| {
+                    CliError::UsageError(format!("Expected a value for --{name}"))
+                })?;
+                result.options.insert(name.to_string(), value);
+            } else {
+                return Err(CliError::UsageError(format!("Unknown option --{name}")));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Get the value of an option.
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Whether a flag is set.
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    /// Get the positional arguments, failing if there isn't exactly `count` of them.
+    fn positional(&self, count: usize) -> Result<&[String], CliError> {
+        if self.positional.len() == count {
+            Ok(&self.positional)
+        } else {
+            Err(CliError::UsageError(format!(
+                "Expected {count} file(s), found {}",
+                self.positional.len()
+            )))
+        }
+    }
+
+    /// Get the only positional argument.
+    fn file(&self) -> Result<&str, CliError> {
+        Ok(self.positional(1)?.first().map_or("", String::as_str))
+    }
+}
+
+/// Read a file, or the standard input if `path` is `-`.
+fn read(path: &str) -> Result<String, CliError> {
+    let error = |error| CliError::IoError(path.to_string(), error);
+
+    if path == "-" {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).map_err(error)?;
+        Ok(contents)
+    } else {
+        fs::read_to_string(path).map_err(error)
+    }
+}
+
+/// Write a file, or the standard output if `path` is `-`.
+fn write(path: &str, contents: &str) -> Result<(), CliError> {
+    let error = |error| CliError::IoError(path.to_string(), error);
+
+    if path == "-" {
+        // A closed output, e.g. when piped to `head`, isn't an error
+        match writeln!(io::stdout(), "{contents}") {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            result => result.map_err(error),
+        }
+    } else {
+        fs::write(path, contents).map_err(error)
+    }
+}
+
+/// Read a situation from a file in the given format.
+///
+/// Diagnostics are only found in TRF files. In [`ParseMode::Strict`], a TRF file with
+/// diagnostics can't be read and only the diagnostics are returned.
+fn load(
+    path: &str,
+    format: Format,
+    mode: ParseMode,
+) -> Result<Result<(Situation, Diagnostics), Diagnostics>, CliError> {
+    let contents = read(path)?;
+
+    let tournament = match format {
+        Format::Trf => return Ok(Situation::read_with(&contents, mode)),
+        Format::Json => serde_json::from_str::<Tournament>(&contents)
+            .map_err(|error| CliError::InvalidJsonError(path.to_string(), error))?,
+        Format::Csv => csv::read(&contents).map_err(|(line, error)| {
+            CliError::InvalidCsvError(path.to_string(), line, error)
+        })?,
+    };
+
+    Ok(Ok((
+        Situation::try_from(&tournament)?,
+        Diagnostics::default(),
+    )))
+}
+
+/// Read a situation leniently, in the format given by the extension of the file.
+fn open(path: &str) -> Result<Situation, CliError> {
+    load(path, Format::of(path), ParseMode::Lenient)?
+        .map(|(situation, _)| situation)
+        .map_err(|_| CliError::UsageError(format!("Couldn't read {path}")))
+}
+
+/// Get the name of a player, or an empty string.
+fn name(player: &Section) -> String {
+    player
+        .name()
+        .ok()
+        .and_then(Option::as_ref)
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+/// Convert a file to another format.
+fn convert(arguments: &Arguments) -> Result<(), CliError> {
+    let [input, output] = arguments.positional(2)? else {
+        return Ok(());
+    };
+    let format = arguments
+        .option("to")
+        .map_or_else(|| Ok(Format::of(output)), Format::try_from)?;
+
+    let situation = open(input)?;
+    let contents = match format {
+        Format::Trf => String::from(&situation),
+        Format::Json => {
+            let tournament = Tournament::try_from(&situation)?;
+            serde_json::to_string_pretty(&tournament)
+                .map_err(|error| CliError::InvalidJsonError(output.clone(), error))?
+        }
+        Format::Csv => csv::write(&Tournament::try_from(&situation)?),
+    };
+
+    write(output, &contents)
+}
+
+/// Run a command, returns whether it succeeded.
+fn run(mut arguments: impl Iterator<Item = String>) -> Result<bool, CliError> {
+    let command = arguments.next().unwrap_or_default();
+
+    match command.as_str() {
+        "inspect" => {
+            let arguments = Arguments::parse(arguments, &[], &[])?;
+            write("-", &inspect::inspect(&open(arguments.file()?)?))?;
+        }
+        "validate" => {
+            let arguments = Arguments::parse(arguments, &[], &["strict", "json"])?;
+            return validate::validate(&arguments);
+        }
+        "convert" => convert(&Arguments::parse(arguments, &["to"], &[])?)?,
+        "standings" => {
+            let arguments = Arguments::parse(arguments, &["tiebreaks"], &[])?;
+            let situation = open(arguments.file()?)?;
+            write(
+                "-",
+                &standings::standings(&situation, arguments.option("tiebreaks"))?,
+            )?;
+        }
+        "pair" => {
+            let arguments = Arguments::parse(arguments, &["round", "system"], &[])?;
+            let situation = open(arguments.file()?)?;
+            write(
+                "-",
+                &pair::pair(
+                    &situation,
+                    arguments.option("round"),
+                    arguments.option("system"),
+                )?,
+            )?;
+        }
+        "help" | "--help" | "-h" => write("-", USAGE)?,
+        "" => return Err(CliError::UsageError("Expected a command".to_string())),
+        other => return Err(CliError::UsageError(format!("Unknown command {other}"))),
+    }
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(error) => {
+            eprintln!("osp: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The `pair` command: the pairings of a round.
+use openswisspairings_lib::{pairing::System, Situation};
+
+use crate::{
+    name,
+    table::{render, Align},
+    CliError,
+};
+
+/// Write the pairings of a round.
+///
+/// `round` defaults to the round after the last round found in the file, `system` to the
+/// FIDE Dutch system.
+pub fn pair(
+    situation: &Situation,
+    round: Option<&str>,
+    system: Option<&str>,
+) -> Result<String, CliError> {
+    let system = match system.map(str::to_lowercase).as_deref() {
+        None | Some("dutch") => System::Dutch,
+        Some("burstein") => System::Burstein,
+        Some("dubov") => System::Dubov,
+        Some("lim") => System::Lim,
+        Some(other) => {
+            return Err(CliError::UsageError(format!(
+                "Unknown pairing system {other}"
+            )))
+        }
+    };
+    let round = match round {
+        Some(round) => round
+            .parse::<u16>()
+            .map_err(|_| CliError::UsageError(format!("Invalid round {round}")))?,
+        None => situation
+            .players()
+            .iter()
+            .map(|player| player.rounds().len())
+            .max()
+            .and_then(|rounds| u16::try_from(rounds).ok())
+            .unwrap_or_default()
+            .saturating_add(1),
+    };
+    let pairings = system.pair(situation, round)?;
+
+    let player = |id: u16| {
+        let section = situation
+            .players()
+            .iter()
+            .find(|player| player.starting_rank_number().is_ok_and(|i| *i == id));
+        [id.to_string(), section.map(name).unwrap_or_default()]
+    };
+
+    let rows: Vec<Vec<String>> = pairings
+        .games()
+        .iter()
+        .zip(1..)
+        .map(|(game, board): (_, u16)| {
+            std::iter::once(board.to_string())
+                .chain(player(game.white()))
+                .chain(player(game.black()))
+                .collect()
+        })
+        .collect();
+
+    let table = render(
+        &[
+            ("Board", Align::Right),
+            ("No", Align::Right),
+            ("White", Align::Left),
+            ("No", Align::Right),
+            ("Black", Align::Left),
+        ],
+        &rows,
+    );
+    let bye = pairings.bye().map(|id| {
+        let [id, name] = player(id);
+        format!("\nPairing-allocated bye: {id} {name}")
+    });
+
+    let approximate = pairings.approximate().then_some(
+        "\nWarning: the search was cut short, the pairings may not follow every rule",
+    );
+
+    Ok(format!(
+        "Round {round}\n\n{table}{}{}",
+        bye.unwrap_or_default(),
+        approximate.unwrap_or_default()
+    ))
+}
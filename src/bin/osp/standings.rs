@@ -0,0 +1,74 @@
+//! The `standings` command: the players ranked by points and tie-breaks.
+use openswisspairings_lib::{standings::Standings, tiebreak::Tiebreak, Situation};
+
+use crate::{
+    name,
+    table::{render, Align},
+    CliError,
+};
+
+/// Read a list of tie-breaks separated by commas, e.g. `BH-C1,BH,SB`.
+fn tiebreaks(list: &str) -> impl Iterator<Item = Result<Tiebreak, String>> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|acronym| !acronym.is_empty())
+        .map(|acronym| Tiebreak::try_from(acronym).map_err(|error| error.to_string()))
+}
+
+/// Write the standings of a situation.
+///
+/// The tie-breaks are read from `list` if it is given, otherwise from the tie-breaks
+/// record of the file (DIN 202), where unknown tie-breaks are skipped.
+pub fn standings(situation: &Situation, list: Option<&str>) -> Result<String, CliError> {
+    let tiebreaks: Vec<Tiebreak> = match list {
+        Some(list) => tiebreaks(list)
+            .collect::<Result<_, _>>()
+            .map_err(CliError::UsageError)?,
+        None => situation
+            .tournament()
+            .tiebreaks()
+            .map(|list| tiebreaks(list).filter_map(Result::ok).collect())
+            .unwrap_or_default(),
+    };
+    let standings = Standings::new(situation, &tiebreaks);
+
+    let rows: Vec<Vec<String>> = standings
+        .rows()
+        .iter()
+        .map(|row| {
+            let place = if row.shared() {
+                format!("{}-{}", row.place(), row.last_place())
+            } else {
+                row.place().to_string()
+            };
+            let player = situation.players().iter().find(|player| {
+                player
+                    .starting_rank_number()
+                    .is_ok_and(|id| *id == row.player())
+            });
+
+            [
+                place,
+                row.player().to_string(),
+                player.map(name).unwrap_or_default(),
+                format!("{:.1}", row.points()),
+            ]
+            .into_iter()
+            .chain(row.tiebreaks().iter().map(ToString::to_string))
+            .collect()
+        })
+        .collect();
+
+    let titles: Vec<String> = tiebreaks.iter().map(ToString::to_string).collect();
+    let columns: Vec<(&str, Align)> = [
+        ("Place", Align::Right),
+        ("No", Align::Right),
+        ("Name", Align::Left),
+        ("Pts", Align::Right),
+    ]
+    .into_iter()
+    .chain(titles.iter().map(|title| (title.as_str(), Align::Right)))
+    .collect();
+
+    Ok(render(&columns, &rows))
+}
@@ -0,0 +1,61 @@
+//! Plain text tables.
+
+/// The alignment of a column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Align {
+    /// Text, aligned to the left.
+    Left,
+
+    /// Numbers, aligned to the right.
+    Right,
+}
+
+/// Write a table with a header row, columns are separated by two spaces.
+///
+/// `columns` are the title and the alignment of each column. Trailing spaces are
+/// removed.
+pub fn render(columns: &[(&str, Align)], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, (title, _))| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|cell| cell.chars().count())
+                .chain([title.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        cells
+            .zip(columns.iter().zip(&widths))
+            .map(|(cell, ((_, align), width))| match align {
+                Align::Left => format!("{cell:<width$}"),
+                Align::Right => format!("{cell:>width$}"),
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<String>>()
+        .join("  ");
+
+    [
+        line(&mut columns.iter().map(|(title, _)| *title)),
+        separator,
+    ]
+    .into_iter()
+    .chain(
+        rows.iter()
+            .map(|row| line(&mut row.iter().map(String::as_str))),
+    )
+    .collect::<Vec<String>>()
+    .join("\n")
+}
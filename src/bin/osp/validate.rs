@@ -0,0 +1,176 @@
+//! The `validate` command: the problems found in a file.
+use std::fmt::{self, Display, Formatter};
+
+use openswisspairings_lib::trf::{
+    diagnostic::{Diagnostic, Severity},
+    scoring::PointsMismatch,
+    validation::Inconsistency,
+    ParseMode,
+};
+use serde::Serialize;
+
+use crate::{load, write, Arguments, CliError, Format};
+
+/// A problem found in a file, as it is reported.
+#[derive(Debug, Serialize)]
+struct Problem {
+    /// `"error"` or `"warning"`.
+    severity: String,
+
+    /// Line number, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+
+    /// Data identification number of the line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    din: Option<String>,
+
+    /// First and last column of the line, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    columns: Option<(usize, usize)>,
+
+    /// The round, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    round: Option<u16>,
+
+    /// The starting rank number of the player.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    player: Option<u16>,
+
+    /// The problem.
+    message: String,
+}
+
+impl Problem {
+    /// Create a problem which isn't located in a line of the file.
+    fn new(severity: Severity, round: Option<u16>, player: u16, message: String) -> Self {
+        Self {
+            severity: severity.to_string(),
+            line: None,
+            din: None,
+            columns: None,
+            round,
+            player: Some(player),
+            message,
+        }
+    }
+
+    /// Whether this is an error.
+    fn is_error(&self) -> bool {
+        self.severity == Severity::Error.to_string()
+    }
+}
+
+impl From<&Diagnostic> for Problem {
+    fn from(value: &Diagnostic) -> Self {
+        let columns = value.columns();
+
+        Self {
+            severity: value.severity().to_string(),
+            line: value.line(),
+            din: value.din().map(ToString::to_string),
+            columns: (!columns.is_empty()).then(|| (columns.start + 1, columns.end)),
+            round: None,
+            player: None,
+            message: value.error().to_string(),
+        }
+    }
+}
+
+impl From<&Inconsistency> for Problem {
+    fn from(value: &Inconsistency) -> Self {
+        Self::new(
+            Severity::Error,
+            Some(value.round()),
+            value.player(),
+            value.kind().to_string(),
+        )
+    }
+}
+
+impl From<&PointsMismatch> for Problem {
+    fn from(value: &PointsMismatch) -> Self {
+        let found = value
+            .found()
+            .map_or_else(|| "an invalid value".to_string(), |found| found.to_string());
+
+        Self::new(
+            Severity::Warning,
+            None,
+            value.player(),
+            format!("Expected {} points, found {found}", value.computed()),
+        )
+    }
+}
+
+impl Display for Problem {
+    /// Write the problem like a [`Diagnostic`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut location = vec![];
+        if let Some(line) = self.line {
+            location.push(self.din.as_ref().map_or_else(
+                || format!("line {line}"),
+                |din| format!("line {line} ({din})"),
+            ));
+        }
+        if let Some((first, last)) = self.columns {
+            location.push(format!("columns {first}-{last}"));
+        }
+        if let Some(round) = self.round {
+            location.push(format!("round {round}"));
+        }
+        if let Some(player) = self.player {
+            location.push(format!("player {player}"));
+        }
+
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Report the problems found in a file: the diagnostics of the parser, the
+/// inconsistencies between the rounds of the players and the points which don't match
+/// the rounds.
+///
+/// Returns whether the file is free of errors.
+pub fn validate(arguments: &Arguments) -> Result<bool, CliError> {
+    let path = arguments.file()?;
+    let mode = if arguments.flag("strict") {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    };
+
+    let (situation, diagnostics) = match load(path, Format::of(path), mode)? {
+        Ok((situation, diagnostics)) => (Some(situation), diagnostics),
+        Err(diagnostics) => (None, diagnostics),
+    };
+
+    let mut problems: Vec<Problem> =
+        diagnostics.all().iter().map(Problem::from).collect();
+    if let Some(situation) = situation {
+        problems.extend(situation.validate().iter().map(Problem::from));
+        problems.extend(situation.verify_points().iter().map(Problem::from));
+    }
+
+    let errors = problems.iter().filter(|problem| problem.is_error()).count();
+    let output = if arguments.flag("json") {
+        serde_json::to_string_pretty(&problems)
+            .map_err(|error| CliError::InvalidJsonError("-".to_string(), error))?
+    } else {
+        problems
+            .iter()
+            .map(ToString::to_string)
+            .chain([format!(
+                "{errors} error(s), {} warning(s)",
+                problems.len() - errors
+            )])
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    write("-", &output)?;
+
+    Ok(errors == 0)
+}
@@ -0,0 +1,565 @@
+//! # Editing
+//!
+//! This module contains the methods to edit a [`Situation`] the way a tournament manager
+//! does: add late entries, withdraw players, and record or correct the result of a game
+//! for both players at once. The data of a player (rating, title, federation...) can be
+//! edited with the setter methods of [`Section`], see [`Situation::player_mut`].
+//!
+//! Before round 1, the players can be ordered by rating, title and name, and their
+//! starting rank numbers allocated again, see [`Situation::renumber_players`].
+//!
+//! The scoring system can be changed, e.g. to 3 / 1 / 0 for football-style events, see
+//! [`Situation::set_scoring_system`].
+//!
+//! Edited records are written back in the canonical TRF format, other records are
+//! written back verbatim (see [`String::from`]).
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::{
+//! #     trf::player::{
+//! #         fields::Title,
+//! #         round::{RegularRoundResult, Result as RoundResult},
+//! #         Section,
+//! #     },
+//! #     Situation,
+//! # };
+//! let file_contents = "\
+//! 012 Test Open
+//! 142 3
+//! 001    1      Alpha, Anna                       2000                             1.0          2 w 1
+//! 001    2      Bravo, Ben                        1900                             0.0          1 b 0";
+//!
+//! let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+//!
+//! // A late entry in round 2 gets a zero-point bye in round 1:
+//! let id = situation.add_player(Section::new(0), 2).unwrap();
+//! assert_eq!(id, 3);
+//!
+//! situation
+//!     .set_result(2, 3, 1, RoundResult::Regular(RegularRoundResult::Draw))
+//!     .unwrap();
+//! situation.withdraw_player(2, 2).unwrap();
+//! situation.player_mut(1).unwrap().set_title(Some(Title::FIDEMaster));
+//!
+//! assert!(situation.validate().is_empty());
+//! assert_eq!(situation.players()[0].points().unwrap(), &Some(1.5));
+//! assert_eq!(
+//!     String::from(&situation.players()[2]),
+//!     "001    3                                                                         0.5            - Z     1 w ="
+//! );
+//! ```
+use std::{cmp::Ordering, collections::HashMap};
+
+use thiserror::Error;
+
+use crate::{
+    trf::{
+        player::{
+            fields::Title,
+            round::{ByeRoundResult, Color, PlayerRoundSection, Result as RoundResult},
+            Section,
+        },
+        record::{Record, RecordKind},
+        scoring::ScoringSystem,
+        tournament::TournamentData,
+    },
+    Situation,
+};
+
+/// An error when editing a [`Situation`].
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// Error when no player has the given starting rank number.
+    #[error("Expected the starting rank number of a player, found {0}")]
+    UnknownPlayerError(u16),
+
+    /// Error when the round is invalid (rounds start at 1).
+    #[error("Expected a valid round number, found {0}")]
+    InvalidRoundError(u16),
+
+    /// Error when a player is paired with themselves.
+    #[error("Player {0} can't be paired with themselves")]
+    SelfPairingError(u16),
+
+    /// Error when the result of a game is a bye.
+    #[error("Expected the result of a game, found a bye")]
+    ByeResultError(),
+
+    /// Error when the number of rounds of the tournament is needed but unknown, see
+    /// [`Situation::number_of_rounds`].
+    #[error("The number of rounds of the tournament is unknown")]
+    UnknownNumberOfRoundsError(),
+}
+
+/// A criterion used to order the players by, see [`Situation::renumber_players`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StartingRankCriterion {
+    /// FIDE rating, highest first. Unrated players come last.
+    Rating,
+
+    /// Title, in the order of [`Title`] (GM, IM, WGM, FM, WIM, CM, WFM, WCM). Untitled
+    /// players come last.
+    Title,
+
+    /// Name, in alphabetical order of the last name then the first name, ignoring case.
+    Name,
+}
+
+impl StartingRankCriterion {
+    /// The order required by the FIDE rules before round 1: rating, then title, then
+    /// name.
+    pub const FIDE: [Self; 3] = [Self::Rating, Self::Title, Self::Name];
+
+    /// Compare two players, the player who comes first is [`Ordering::Less`].
+    ///
+    /// Fields which are empty or incorrectly formatted come last.
+    fn compare(self, a: &Section, b: &Section) -> Ordering {
+        /// Compare two optional values, [`None`] comes last.
+        fn last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (a, b) => b.is_none().cmp(&a.is_none()).reverse(),
+            }
+        }
+
+        match self {
+            Self::Rating => {
+                let rating = |player: &Section| {
+                    player
+                        .fide_rating()
+                        .ok()
+                        .copied()
+                        .flatten()
+                        .filter(|rating| *rating > 0)
+                        .map(std::cmp::Reverse)
+                };
+                last(rating(a), rating(b))
+            }
+            Self::Title => {
+                let title = |player: &Section| {
+                    player.title().ok()?.as_ref().map(|title| match title {
+                        Title::Grandmaster => 0,
+                        Title::InternationalMaster => 1,
+                        Title::WomanGrandmaster => 2,
+                        Title::FIDEMaster => 3,
+                        Title::WomanInternationalMaster => 4,
+                        Title::CandidateMaster => 5,
+                        Title::WomanFIDEMaster => 6,
+                        Title::WomanCandidateMaster => 7,
+                    })
+                };
+                last(title(a), title(b))
+            }
+            Self::Name => {
+                let name = |player: &Section| {
+                    player.name().ok()?.as_ref().map(|name| {
+                        (
+                            name.last_name().to_lowercase(),
+                            name.first_name().to_lowercase(),
+                        )
+                    })
+                };
+                last(name(a), name(b))
+            }
+        }
+    }
+}
+
+/// The round section of a player who is absent from a round.
+const fn zero_point_bye() -> PlayerRoundSection {
+    PlayerRoundSection::new(
+        None,
+        Some(Color::None),
+        Some(RoundResult::Bye(ByeRoundResult::ZeroPointBye)),
+    )
+}
+
+impl Situation {
+    /// Get a mutable player by starting rank number.
+    ///
+    /// Use the setter methods of [`Section`] to edit the data of the player.
+    pub fn player_mut(&mut self, id: u16) -> Option<&mut Section> {
+        self.players
+            .iter_mut()
+            .find(|player| player.starting_rank_number().is_ok_and(|i| *i == id))
+    }
+
+    /// Get the mutable tournament header data.
+    pub const fn tournament_mut(&mut self) -> &mut TournamentData {
+        &mut self.tournament
+    }
+
+    /// Set the scoring system.
+    ///
+    /// The point-value records (DIN BBW, BBD, BBL, BBZ, BBF and BBU) are written with the
+    /// situation. The points of the players aren't changed, see
+    /// [`Situation::verify_points`].
+    ///
+    /// ```
+    /// # use openswisspairings_lib::{trf::scoring::ScoringSystem, Situation};
+    /// let file_contents = "\
+    /// 012 Football League
+    /// 001    1      Smith, John                       2100                             1.5          2 w 1     3 b =
+    /// 001    2      Doe, Jane                         2000                             0.5          1 b 0     3 w =
+    /// 001    3      Roe, Richard                      1900                             1.0          2 b =     1 w =";
+    ///
+    /// let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+    /// assert!(situation.verify_points().is_empty());
+    ///
+    /// situation.set_scoring_system(ScoringSystem::new(3.0, 1.0, 0.0));
+    /// assert_eq!(situation.verify_points().len(), 3);
+    /// assert_eq!(situation.verify_points()[0].computed(), 4.0);
+    ///
+    /// let written = String::from(&situation);
+    /// assert!(written.starts_with("012 Football League\nBBW 3.0\nBBD 1.0\nBBL 0.0\n"));
+    /// ```
+    pub fn set_scoring_system(&mut self, scoring_system: ScoringSystem) {
+        self.scoring_system = scoring_system;
+
+        for din in ScoringSystem::RECORDS {
+            self.insert_header_record(RecordKind::Scoring(din.to_string()));
+        }
+    }
+
+    /// Add a header record before the players, unless the situation already has it.
+    pub(crate) fn insert_header_record(&mut self, kind: RecordKind) {
+        if self.records.iter().any(|record| record.kind == kind) {
+            return;
+        }
+
+        let position = self
+            .records
+            .iter()
+            .position(|record| matches!(record.kind, RecordKind::Player(_)))
+            .unwrap_or(self.records.len());
+        self.records.insert(position, Record { kind, source: None });
+    }
+
+    /// Compute the points of a player again from their rounds, see
+    /// [`crate::trf::scoring::ScoringSystem::score`].
+    fn update_points(&mut self, id: u16) {
+        let scoring_system = &self.scoring_system;
+
+        if let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|player| player.starting_rank_number().is_ok_and(|i| *i == id))
+        {
+            let points = scoring_system.score(player);
+            player.set_points(Some(points));
+        }
+    }
+
+    /// Add a player entering the tournament in `round` (rounds start at 1).
+    ///
+    /// The player is given the next starting rank number, and a zero-point bye for each
+    /// round they missed. The number of players of the header (DIN 062) is updated if it
+    /// is present. Returns the starting rank number of the player.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EditError::InvalidRoundError`] if `round` is 0.
+    pub fn add_player(
+        &mut self,
+        mut player: Section,
+        round: u16,
+    ) -> Result<u16, EditError> {
+        if round == 0 {
+            return Err(EditError::InvalidRoundError(round));
+        }
+
+        let id = self
+            .players
+            .iter()
+            .filter_map(|player| player.starting_rank_number().ok().copied())
+            .max()
+            .unwrap_or_default()
+            + 1;
+        player.set_starting_rank_number(id);
+        for missed in 1..round {
+            player.set_round(missed, Some(zero_point_bye()));
+        }
+        player.set_points(Some(self.scoring_system.score(&player)));
+
+        if let Ok(Some(number_of_players)) = self.tournament.number_of_players() {
+            let number_of_players = number_of_players + 1;
+            self.tournament
+                .set_number_of_players(Some(number_of_players));
+        }
+
+        // The player is written after the last player.
+        let index = self.players.len();
+        let position = self
+            .records
+            .iter()
+            .rposition(|record| {
+                matches!(
+                    record.kind,
+                    RecordKind::Player(_) | RecordKind::Metadata(..)
+                )
+            })
+            .map_or(self.records.len(), |position| position + 1);
+        let metadata = player
+            .metadata()
+            .keys()
+            .map(|key| RecordKind::Metadata(index, key.clone()));
+        let records: Vec<Record> = std::iter::once(RecordKind::Player(index))
+            .chain(metadata)
+            .map(|kind| Record { kind, source: None })
+            .collect();
+
+        self.records.splice(position..position, records);
+        self.players.push(player);
+
+        Ok(id)
+    }
+
+    /// Withdraw a player from `round` and the following rounds (rounds start at 1).
+    ///
+    /// The player is given a zero-point bye in each of those rounds which isn't filled
+    /// yet, so they aren't paired (see [`crate::pairing`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player doesn't exist, if `round` is 0 or if the number of
+    /// rounds of the tournament is unknown.
+    pub fn withdraw_player(&mut self, id: u16, round: u16) -> Result<(), EditError> {
+        if round == 0 {
+            return Err(EditError::InvalidRoundError(round));
+        }
+        let rounds = self
+            .number_of_rounds()
+            .ok_or(EditError::UnknownNumberOfRoundsError())?;
+        let player = self
+            .player_mut(id)
+            .ok_or(EditError::UnknownPlayerError(id))?;
+
+        for round in round..=rounds {
+            let filled = player
+                .rounds()
+                .get(usize::from(round - 1))
+                .and_then(Option::as_ref)
+                .is_some_and(|section| {
+                    section.id().is_ok_and(Option::is_some)
+                        || section.result().is_ok_and(Option::is_some)
+                });
+
+            if !filled {
+                player.set_round(round, Some(zero_point_bye()));
+            }
+        }
+        self.update_points(id);
+
+        Ok(())
+    }
+
+    /// Record or correct the result of a game, for both players at once.
+    ///
+    /// `result` is the result of the player with white, the result of the player with
+    /// black is the opposite one (see [`RoundResult::opponent_result`]). If either player
+    /// was paired with someone else in this round, the round of that previous opponent is
+    /// cleared. The points of the players are computed again with the scoring system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a player doesn't exist, if both players are the same, if
+    /// `round` is 0 or if `result` is a bye. The situation isn't changed in that case.
+    pub fn set_result(
+        &mut self,
+        round: u16,
+        white: u16,
+        black: u16,
+        result: RoundResult,
+    ) -> Result<(), EditError> {
+        if round == 0 {
+            return Err(EditError::InvalidRoundError(round));
+        }
+        if white == black {
+            return Err(EditError::SelfPairingError(white));
+        }
+        let opponent_result = result
+            .opponent_result()
+            .ok_or(EditError::ByeResultError())?;
+        for id in [white, black] {
+            self.player_mut(id)
+                .ok_or(EditError::UnknownPlayerError(id))?;
+        }
+
+        // Clear the round of the previous opponents.
+        let index = usize::from(round - 1);
+        let previous: Vec<u16> = [white, black]
+            .into_iter()
+            .filter_map(|id| {
+                let player = self.players.iter().find(|player| {
+                    player.starting_rank_number().is_ok_and(|i| *i == id)
+                })?;
+                let section = player.rounds().get(index)?.as_ref()?;
+                section.id().ok().copied().flatten()
+            })
+            .filter(|opponent| *opponent != white && *opponent != black)
+            .collect();
+        for opponent in previous {
+            if let Some(player) = self.player_mut(opponent) {
+                player.set_round(round, None);
+            }
+            self.update_points(opponent);
+        }
+
+        for (id, opponent, color, result) in [
+            (white, black, Color::White, result),
+            (black, white, Color::Black, opponent_result),
+        ] {
+            if let Some(player) = self.player_mut(id) {
+                let section =
+                    PlayerRoundSection::new(Some(opponent), Some(color), Some(result));
+                player.set_round(round, Some(section));
+            }
+            self.update_points(id);
+        }
+
+        Ok(())
+    }
+
+    /// Order the players by `criteria` and allocate their starting rank numbers again,
+    /// from 1.
+    ///
+    /// Players who are equal for every criterion keep their current order. Every
+    /// reference to a player is changed consistently: the opponents in the round
+    /// sections, the team members, the absent players (DIN 240), the forbidden pairs
+    /// (DIN 260 and XXP) and the accelerations (XXA). The player ranges of the
+    /// accelerations of TRF-2025 (DIN 250) aren't changed. Player sections are written
+    /// in the new order.
+    ///
+    /// Returns the previous and the new starting rank number of each player whose
+    /// starting rank number was valid.
+    ///
+    /// ```
+    /// # use openswisspairings_lib::{edit::StartingRankCriterion, Situation};
+    /// let file_contents = "\
+    /// 001    1      Charlie, Cleo                     1800                             1.0          2 w 1
+    /// 001    2      Alpha, Anna                       2000                             0.0          1 b 0
+    /// 001    3 m IM Bravo, Ben                        2000                             0.0";
+    ///
+    /// let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+    /// let renumbered = situation.renumber_players(&StartingRankCriterion::FIDE);
+    ///
+    /// assert_eq!(renumbered, [(3, 1), (2, 2), (1, 3)]);
+    /// assert!(situation.validate().is_empty());
+    /// assert_eq!(
+    ///     String::from(&situation),
+    ///     "\
+    /// 001    1 m IM Bravo, Ben                        2000                             0.0\n\
+    /// 001    2      Alpha, Anna                       2000                             0.0          3 b 0\n\
+    /// 001    3      Charlie, Cleo                     1800                             1.0          2 w 1"
+    /// );
+    /// ```
+    pub fn renumber_players(
+        &mut self,
+        criteria: &[StartingRankCriterion],
+    ) -> Vec<(u16, u16)> {
+        let compare = |a: &Section, b: &Section| {
+            criteria
+                .iter()
+                .map(|criterion| criterion.compare(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+
+        // `order[new]` is the previous index of each player.
+        let mut order: Vec<usize> = (0..self.players.len()).collect();
+        order.sort_by(|a, b| match (self.players.get(*a), self.players.get(*b)) {
+            (Some(a), Some(b)) => compare(a, b),
+            _ => Ordering::Equal,
+        });
+        let mut players: Vec<Option<Section>> = std::mem::take(&mut self.players)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.players = order
+            .iter()
+            .filter_map(|index| players.get_mut(*index)?.take())
+            .collect();
+
+        let mut renumbered: Vec<(u16, u16)> = vec![];
+        for (id, player) in (1..).zip(&mut self.players) {
+            if let Ok(previous) = player.starting_rank_number() {
+                renumbered.push((*previous, id));
+            }
+            player.set_starting_rank_number(id);
+        }
+
+        let ids: HashMap<u16, u16> = renumbered.iter().copied().collect();
+        let renumber = |id: u16| ids.get(&id).copied().unwrap_or(id);
+        for player in &mut self.players {
+            player.renumber_opponents(renumber);
+        }
+        for team in &mut self.teams {
+            team.renumber(renumber);
+        }
+        for absent_players in &mut self.absent_players {
+            absent_players.renumber(renumber);
+        }
+        for forbidden_pairs in &mut self.forbidden_pairs {
+            forbidden_pairs.renumber(renumber);
+        }
+        self.extensions.renumber(renumber);
+
+        self.reorder_player_records(&order);
+
+        renumbered
+    }
+
+    /// Write the player sections in their new order, along with their metadata records.
+    ///
+    /// `order[new]` is the previous index of each player. The original line of each
+    /// record is kept, so players whose data didn't change are written back verbatim.
+    fn reorder_player_records(&mut self, order: &[usize]) {
+        let mut sources: HashMap<(usize, Option<String>), Option<String>> =
+            HashMap::new();
+        for record in &mut self.records {
+            match &record.kind {
+                RecordKind::Player(index) => {
+                    sources.insert((*index, None), record.source.take());
+                }
+                RecordKind::Metadata(index, key) => {
+                    sources.insert((*index, Some(key.clone())), record.source.take());
+                }
+                _ => {}
+            }
+        }
+
+        let mut slot = 0;
+        let records = std::mem::take(&mut self.records);
+        for record in records {
+            match record.kind {
+                RecordKind::Metadata(..) => {}
+                RecordKind::Player(_) => {
+                    let previous = order.get(slot).copied().unwrap_or(slot);
+                    let mut source = |key: Option<&String>| {
+                        sources.remove(&(previous, key.cloned())).flatten()
+                    };
+
+                    self.records.push(Record {
+                        kind: RecordKind::Player(slot),
+                        source: source(None),
+                    });
+                    let keys: Vec<String> = self
+                        .players
+                        .get(slot)
+                        .map(|player| player.metadata().keys().cloned().collect())
+                        .unwrap_or_default();
+                    for key in keys {
+                        self.records.push(Record {
+                            source: source(Some(&key)),
+                            kind: RecordKind::Metadata(slot, key),
+                        });
+                    }
+                    slot += 1;
+                }
+                _ => self.records.push(record),
+            }
+        }
+    }
+}
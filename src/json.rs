@@ -0,0 +1,1093 @@
+//! # JSON interchange format
+//!
+//! A stable, versioned JSON representation of a tournament: the header, the scoring
+//! system, the players with their rounds and metadata, the teams, the pairing constraints
+//! of TRF-2025 and the `JaVaFo` extension records. It is meant for programs which
+//! shouldn't have to produce TRF files, e.g. a web interface, and is easy to diff.
+//!
+//! This module is only available with the `serde` feature. The format is described by a
+//! JSON Schema, see [`SCHEMA`].
+//!
+//! Fields use the codes of the TRF format:
+//! - sex: `"m"` or `"w"`
+//! - title: `"GM"`, `"IM"`, `"WGM"`, `"FM"`, `"WIM"`, `"CM"`, `"WFM"` or `"WCM"`
+//! - color: `"w"`, `"b"` or `"-"`
+//! - result: `"+"`, `"-"`, `"W"`, `"D"`, `"L"`, `"1"`, `"="`, `"0"`, `"H"`, `"F"`,
+//!   `"U"` or `"Z"`, see [`crate::trf::player::round::Result`]
+//! - dates: `YYYY/MM/DD`, or `YY/MM/DD` for the dates of the rounds
+//!
+//! Empty fields are left out. The layout of the TRF file (order of the records, unknown
+//! records) isn't part of the format.
+//!
+//! ## Versioning
+//!
+//! Every document contains the version of the format it was written in, see
+//! [`VERSION`]. The version is increased whenever the format changes, documents of
+//! another version are rejected.
+//!
+//! ## Usage
+//!
+//! Use [`Tournament::try_from`] to convert a [`Situation`], and [`Situation::try_from`]
+//! to convert it back:
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{json::Tournament, Situation};
+//!
+//! let file_contents =
+//!     fs::read_to_string("lichess_swiss_2024.10.13_hId1KZmF_rapid.trf").unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! let tournament = Tournament::try_from(&situation).unwrap();
+//! let json = serde_json::to_string_pretty(&tournament).unwrap();
+//!
+//! let read: Tournament = serde_json::from_str(&json).unwrap();
+//! let situation = Situation::try_from(&read).unwrap();
+//! assert_eq!(Tournament::try_from(&situation).unwrap(), tournament);
+//! ```
+//!
+//! A tournament can be created without writing any TRF:
+//!
+//! ```
+//! # use openswisspairings_lib::{json::Tournament, Situation};
+//! let json = r#"{
+//!     "version": 1,
+//!     "header": { "name": "Club Championship", "number_of_rounds": 5 },
+//!     "players": [
+//!         { "starting_rank_number": 1, "name": "Carlsen, Magnus", "fide_rating": 2830 },
+//!         { "starting_rank_number": 2, "name": "Caruana, Fabiano", "fide_rating": 2795 }
+//!     ]
+//! }"#;
+//!
+//! let tournament: Tournament = serde_json::from_str(json).unwrap();
+//! let situation = Situation::try_from(&tournament).unwrap();
+//!
+//! assert_eq!(situation.players().len(), 2);
+//! assert_eq!(situation.number_of_rounds(), Some(5));
+//! ```
+//!
+//! The pairing constraints of TRF-2025 are kept:
+//!
+//! ```
+//! # use openswisspairings_lib::{json::Tournament, Situation};
+//! let file_contents = "\
+//! 012 Test Open
+//! 240 H   3    2    4
+//! 250  1.0   1   2    1    2
+//! 260    1    3
+//! 001    1      Alpha, Anna                       2000                             0.0
+//! 001    2      Bravo, Ben                        1900                             0.0
+//! 001    3      Charlie, Cleo                     1800                             0.0
+//! 001    4      Delta, Dan                        1700                             0.0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let tournament = Tournament::try_from(&situation).unwrap();
+//! assert_eq!(tournament.constraints.absent_players[0].players, [2, 4]);
+//! assert_eq!(tournament.constraints.forbidden_pairs, [vec![1, 3]]);
+//!
+//! let situation = Situation::try_from(&tournament).unwrap();
+//! assert_eq!(String::from(&situation).replace('\r', "\n"), file_contents);
+//! ```
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    trf::{
+        constraint::{self, AbsentPlayers, ForbiddenPairs},
+        extension::{self, Configuration, PlayerAcceleration, ScoreCode, ScoreValues},
+        player::{
+            fields::{Date, Name, Sex, Title},
+            round::{self, ByeRoundResult, Color, PlayerRoundSection},
+            Section,
+        },
+        record::{Record, RecordKind},
+        scoring::ScoringSystem,
+        team::TeamData,
+        tournament::TournamentData,
+        ParseMode, TRFError,
+    },
+    Situation,
+};
+
+/// The version of the format written by this library.
+pub const VERSION: u16 = 1;
+
+/// The JSON Schema of the format, version [`VERSION`].
+pub const SCHEMA: &str = include_str!("../schema/tournament.schema.json");
+
+/// An error when converting a tournament from or to the JSON format.
+#[derive(Error, Debug, Clone)]
+pub enum JsonError {
+    /// Error when the document was written in another version of the format.
+    #[error("Expected version {VERSION} of the format, found version {0}")]
+    UnsupportedVersionError(u16),
+
+    /// Error when a field is incorrectly formatted, stores the path of the field.
+    #[error("Invalid field {0}: {1}")]
+    InvalidFieldError(String, TRFError),
+
+    /// Error when a required field is empty, stores the path of the field.
+    #[error("Expected a value in field {0}")]
+    MissingFieldError(String),
+}
+
+/// Get a field of the situation, or an error with its path if it is incorrectly
+/// formatted.
+fn valid<'a, T>(
+    field: Result<&'a T, &TRFError>,
+    path: impl FnOnce() -> String,
+) -> Result<&'a T, JsonError> {
+    field.map_err(|error| JsonError::InvalidFieldError(path(), error.clone()))
+}
+
+/// Read a field of the document, or an error with its path if it is incorrectly
+/// formatted.
+fn parse<'a, T: TryFrom<&'a str, Error = TRFError>>(
+    field: Option<&'a str>,
+    path: impl FnOnce() -> String,
+) -> Result<Option<T>, JsonError> {
+    field
+        .map(T::try_from)
+        .transpose()
+        .map_err(|error| JsonError::InvalidFieldError(path(), error))
+}
+
+/// A tournament.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tournament {
+    /// The version of the format, see [`VERSION`].
+    pub version: u16,
+
+    /// The tournament header.
+    #[serde(default)]
+    pub header: Header,
+
+    /// The scoring system, the standard 1 / ½ / 0 scoring system if it is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<Scoring>,
+
+    /// The players.
+    #[serde(default)]
+    pub players: Vec<Player>,
+
+    /// The teams.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<Team>,
+
+    /// The pairing constraints of TRF-2025.
+    #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,
+
+    /// The `JaVaFo` extension records.
+    #[serde(default, skip_serializing_if = "Extensions::is_empty")]
+    pub extensions: Extensions,
+}
+
+impl TryFrom<&Situation> for Tournament {
+    type Error = JsonError;
+
+    /// Convert a situation to the JSON format.
+    ///
+    /// Fails if a field of the situation is incorrectly formatted.
+    fn try_from(value: &Situation) -> Result<Self, Self::Error> {
+        let scoring_system = value.scoring_system();
+        let scoring = ScoringSystem::RECORDS
+            .iter()
+            .any(|din| scoring_system.write_record(din).is_some())
+            .then(|| Scoring::from(scoring_system));
+
+        Ok(Self {
+            version: VERSION,
+            header: Header::try_from(value.tournament())?,
+            scoring,
+            players: value
+                .players()
+                .iter()
+                .enumerate()
+                .map(|(index, player)| Player::new(player, &format!("players[{index}]")))
+                .collect::<Result<_, _>>()?,
+            teams: value
+                .teams()
+                .iter()
+                .enumerate()
+                .map(|(index, team)| Team::new(team, &format!("teams[{index}]")))
+                .collect::<Result<_, _>>()?,
+            constraints: Constraints::try_from(value)?,
+            extensions: Extensions::try_from(value.extensions())?,
+        })
+    }
+}
+
+impl TryFrom<&Tournament> for Situation {
+    type Error = JsonError;
+
+    /// Convert a tournament in the JSON format to a situation.
+    ///
+    /// Fails if the version isn't [`VERSION`] or if a field is incorrectly formatted.
+    fn try_from(value: &Tournament) -> Result<Self, Self::Error> {
+        if value.version != VERSION {
+            return Err(JsonError::UnsupportedVersionError(value.version));
+        }
+
+        let players = value
+            .players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| player.section(&format!("players[{index}]")))
+            .collect::<Result<_, _>>()?;
+        let teams = value.teams.iter().map(Team::team_data).collect();
+        let mut situation = Self::new(value.header.tournament_data()?, players, teams);
+
+        if let Some(scoring) = &value.scoring {
+            situation.set_scoring_system(scoring.into());
+        }
+
+        situation.absent_players = value.constraints.absent_players()?;
+        situation.accelerations = value.constraints.accelerations();
+        situation.forbidden_pairs = value.constraints.forbidden_pairs();
+
+        // Situation::new only writes the records of empty extensions, and no constraint
+        let extensions = value.extensions.extensions()?;
+        let is_extension =
+            |record: &Record| matches!(record.kind, RecordKind::Extension(..));
+        let position = situation
+            .records
+            .iter()
+            .position(is_extension)
+            .unwrap_or_default();
+        situation.records.retain(|record| !is_extension(record));
+        let constraints = (0..situation.absent_players.len())
+            .map(RecordKind::AbsentPlayers)
+            .chain((0..situation.accelerations.len()).map(RecordKind::Acceleration))
+            .chain((0..situation.forbidden_pairs.len()).map(RecordKind::ForbiddenPairs));
+        let records = constraints
+            .chain(
+                extensions
+                    .records()
+                    .into_iter()
+                    .map(|(din, index)| RecordKind::Extension(din.to_string(), index)),
+            )
+            .map(|kind| Record { kind, source: None });
+        situation.records.splice(position..position, records);
+        situation.extensions = extensions;
+
+        Ok(situation)
+    }
+}
+
+/// The tournament header, see [`TournamentData`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Header {
+    /// Tournament name (DIN 012).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// City (DIN 022).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+
+    /// Federation (DIN 032).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub federation: Option<String>,
+
+    /// Date of start (DIN 042), `YYYY/MM/DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+
+    /// Date of end (DIN 052), `YYYY/MM/DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+
+    /// Number of players (DIN 062).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_players: Option<u16>,
+
+    /// Number of rated players (DIN 072).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_rated_players: Option<u16>,
+
+    /// Number of teams (DIN 082).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_teams: Option<u16>,
+
+    /// Type of tournament (DIN 092).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tournament_type: Option<String>,
+
+    /// Chief arbiter (DIN 102).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chief_arbiter: Option<String>,
+
+    /// Deputy chief arbiters (DIN 112).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deputy_chief_arbiters: Vec<String>,
+
+    /// Allotted time per moves/game (DIN 122).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_control: Option<String>,
+
+    /// Dates of the rounds (DIN 132), `YY/MM/DD`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub round_dates: Vec<Option<String>>,
+
+    /// Number of rounds planned (DIN 142).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_rounds: Option<u16>,
+
+    /// Pairing system (DIN 162).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pairing_system: Option<String>,
+
+    /// Method used to allocate the starting ranks (DIN 172).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_rank_method: Option<String>,
+
+    /// Tie-breaks, in order (DIN 202).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tiebreaks: Option<String>,
+
+    /// Source of the file (DIN XRS).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl Header {
+    /// Convert the header to the tournament header data of a situation.
+    fn tournament_data(&self) -> Result<TournamentData, JsonError> {
+        let date = |field: &Option<String>, name: &str| {
+            parse::<Date>(field.as_deref(), || format!("header.{name}"))
+        };
+
+        let mut tournament = TournamentData::default();
+        tournament.set_name(self.name.clone());
+        tournament.set_city(self.city.clone());
+        tournament.set_federation(self.federation.clone());
+        tournament.set_start_date(date(&self.start_date, "start_date")?);
+        tournament.set_end_date(date(&self.end_date, "end_date")?);
+        tournament.set_number_of_players(self.number_of_players);
+        tournament.set_number_of_rated_players(self.number_of_rated_players);
+        tournament.set_number_of_teams(self.number_of_teams);
+        tournament.set_tournament_type(self.tournament_type.clone());
+        tournament.set_chief_arbiter(self.chief_arbiter.clone());
+        tournament.set_deputy_chief_arbiters(self.deputy_chief_arbiters.clone());
+        tournament.set_time_control(self.time_control.clone());
+        tournament.set_round_dates(
+            self.round_dates
+                .iter()
+                .enumerate()
+                .map(|(index, round_date)| {
+                    date(round_date, &format!("round_dates[{index}]"))
+                })
+                .collect::<Result<_, _>>()?,
+        );
+        tournament.set_number_of_rounds(self.number_of_rounds);
+        tournament.set_pairing_system(self.pairing_system.clone());
+        tournament.set_starting_rank_method(self.starting_rank_method.clone());
+        tournament.set_tiebreaks(self.tiebreaks.clone());
+        tournament.set_source(self.source.clone());
+
+        Ok(tournament)
+    }
+}
+
+impl TryFrom<&TournamentData> for Header {
+    type Error = JsonError;
+
+    fn try_from(value: &TournamentData) -> Result<Self, Self::Error> {
+        let text = |field: Option<&str>| field.map(ToString::to_string);
+        let path = |name: &str| format!("header.{name}");
+        let date = |field, name| {
+            valid(field, || path(name))
+                .map(|date: &Option<Date>| date.as_ref().map(Into::into))
+        };
+
+        Ok(Self {
+            name: text(value.name()),
+            city: text(value.city()),
+            federation: text(value.federation()),
+            start_date: date(value.start_date(), "start_date")?,
+            end_date: date(value.end_date(), "end_date")?,
+            number_of_players: *valid(value.number_of_players(), || {
+                path("number_of_players")
+            })?,
+            number_of_rated_players: *valid(value.number_of_rated_players(), || {
+                path("number_of_rated_players")
+            })?,
+            number_of_teams: *valid(value.number_of_teams(), || path("number_of_teams"))?,
+            tournament_type: text(value.tournament_type()),
+            chief_arbiter: text(value.chief_arbiter()),
+            deputy_chief_arbiters: value.deputy_chief_arbiters().to_vec(),
+            time_control: text(value.time_control()),
+            round_dates: value
+                .round_dates()
+                .iter()
+                .enumerate()
+                .map(|(index, round_date)| {
+                    let round_date = valid(round_date.as_ref(), || {
+                        format!("header.round_dates[{index}]")
+                    })?;
+                    Ok(round_date.as_ref().map(|date| {
+                        format!(
+                            "{:02}/{:02}/{:02}",
+                            date.year(),
+                            date.month(),
+                            date.day()
+                        )
+                    }))
+                })
+                .collect::<Result<_, _>>()?,
+            number_of_rounds: *valid(value.number_of_rounds(), || {
+                path("number_of_rounds")
+            })?,
+            pairing_system: text(value.pairing_system()),
+            starting_rank_method: text(value.starting_rank_method()),
+            tiebreaks: text(value.tiebreaks()),
+            source: text(value.source()),
+        })
+    }
+}
+
+/// The points given for each round result, see [`ScoringSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scoring {
+    /// Points for a win (DIN BBW).
+    pub win: f32,
+
+    /// Points for a draw (DIN BBD).
+    pub draw: f32,
+
+    /// Points for a loss (DIN BBL).
+    pub loss: f32,
+
+    /// Points for a zero-point bye (DIN BBZ).
+    pub zero_point_bye: f32,
+
+    /// Points for a forfeit loss (DIN BBF).
+    pub forfeit_loss: f32,
+
+    /// Points for a pairing-allocated bye (DIN BBU).
+    pub pairing_allocated_bye: f32,
+}
+
+impl From<&ScoringSystem> for Scoring {
+    fn from(value: &ScoringSystem) -> Self {
+        Self {
+            win: value.win(),
+            draw: value.draw(),
+            loss: value.loss(),
+            zero_point_bye: value.zero_point_bye(),
+            forfeit_loss: value.forfeit_loss(),
+            pairing_allocated_bye: value.pairing_allocated_bye(),
+        }
+    }
+}
+
+impl From<&Scoring> for ScoringSystem {
+    fn from(value: &Scoring) -> Self {
+        let mut scoring_system = Self::new(value.win, value.draw, value.loss);
+        scoring_system.set_zero_point_bye(value.zero_point_bye);
+        scoring_system.set_forfeit_loss(value.forfeit_loss);
+        scoring_system.set_pairing_allocated_bye(value.pairing_allocated_bye);
+        scoring_system
+    }
+}
+
+/// A player, see [`Section`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Player {
+    /// Starting rank number.
+    pub starting_rank_number: u16,
+
+    /// Sex, `"m"` or `"w"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sex: Option<String>,
+
+    /// FIDE title, e.g. `"GM"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Name, `"Lastname, Firstname"` or a username.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// FIDE rating.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fide_rating: Option<u16>,
+
+    /// FIDE federation, e.g. `"FRA"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fide_federation: Option<String>,
+
+    /// FIDE number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fide_number: Option<u64>,
+
+    /// Birth date, `YYYY/MM/DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<String>,
+
+    /// Points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points: Option<f32>,
+
+    /// Rank.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u16>,
+
+    /// Rounds, starting with round 1. Rounds which weren't paired are `null`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rounds: Vec<Option<Round>>,
+
+    /// Metadata, e.g. `GROUP` (DIN DAT).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Player {
+    /// Convert a player section, `path` is the path of the player in the document.
+    fn new(section: &Section, path: &str) -> Result<Self, JsonError> {
+        let field = |name: &str| format!("{path}.{name}");
+
+        Ok(Self {
+            starting_rank_number: *valid(section.starting_rank_number(), || {
+                field("starting_rank_number")
+            })?,
+            sex: valid(section.sex(), || field("sex"))?
+                .as_ref()
+                .map(Into::into),
+            title: valid(section.title(), || field("title"))?
+                .as_ref()
+                .map(Into::into),
+            name: valid(section.name(), || field("name"))?
+                .as_ref()
+                .map(Into::into),
+            fide_rating: *valid(section.fide_rating(), || field("fide_rating"))?,
+            fide_federation: section.fide_federation().map(ToString::to_string),
+            fide_number: *valid(section.fide_number(), || field("fide_number"))?,
+            birth_date: valid(section.birth_date(), || field("birth_date"))?
+                .as_ref()
+                .map(Into::into),
+            points: *valid(section.points(), || field("points"))?,
+            rank: *valid(section.rank(), || field("rank"))?,
+            rounds: section
+                .rounds()
+                .iter()
+                .enumerate()
+                .map(|(index, round)| {
+                    round
+                        .as_ref()
+                        .map(|round| {
+                            Round::new(round, &format!("{path}.rounds[{index}]"))
+                        })
+                        .transpose()
+                })
+                .collect::<Result<_, _>>()?,
+            metadata: section.metadata().clone(),
+        })
+    }
+
+    /// Convert the player to a player section, `path` is the path of the player in the
+    /// document.
+    fn section(&self, path: &str) -> Result<Section, JsonError> {
+        let field = |name: &str| format!("{path}.{name}");
+
+        let mut section = Section::new(self.starting_rank_number);
+        section.set_sex(parse::<Sex>(self.sex.as_deref(), || field("sex"))?);
+        section.set_title(parse::<Title>(self.title.as_deref(), || field("title"))?);
+        section.set_name(
+            self.name
+                .as_deref()
+                .map(|name| Name::read(name, ParseMode::Lenient))
+                .transpose()
+                .map_err(|error| JsonError::InvalidFieldError(field("name"), error))?,
+        );
+        section.set_fide_rating(self.fide_rating);
+        section.set_fide_federation(self.fide_federation.clone());
+        section.set_fide_number(self.fide_number);
+        section.set_birth_date(parse::<Date>(self.birth_date.as_deref(), || {
+            field("birth_date")
+        })?);
+        section.set_points(self.points);
+        section.set_rank(self.rank);
+
+        for (round, index) in self.rounds.iter().zip(1..) {
+            let round = round
+                .as_ref()
+                .map(|round| round.section(&format!("{path}.rounds[{}]", index - 1)))
+                .transpose()?;
+            section.set_round(index, round);
+        }
+        for (key, value) in &self.metadata {
+            section.set_metadata(key, value);
+        }
+
+        Ok(section)
+    }
+}
+
+/// A round of a player, see [`PlayerRoundSection`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Round {
+    /// Starting rank number of the opponent, empty for byes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u16>,
+
+    /// Color, `"w"`, `"b"` or `"-"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// Result, e.g. `"1"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+}
+
+impl Round {
+    /// Convert a round section, `path` is the path of the round in the document.
+    fn new(section: &PlayerRoundSection, path: &str) -> Result<Self, JsonError> {
+        let field = |name: &str| format!("{path}.{name}");
+
+        Ok(Self {
+            id: *valid(section.id(), || field("id"))?,
+            color: valid(section.color(), || field("color"))?.map(Into::into),
+            result: valid(section.result(), || field("result"))?.map(Into::into),
+        })
+    }
+
+    /// Convert the round to a round section, `path` is the path of the round in the
+    /// document.
+    fn section(&self, path: &str) -> Result<PlayerRoundSection, JsonError> {
+        let field = |name: &str| format!("{path}.{name}");
+
+        Ok(PlayerRoundSection::new(
+            self.id,
+            parse::<Color>(self.color.as_deref(), || field("color"))?,
+            parse::<round::Result>(self.result.as_deref(), || field("result"))?,
+        ))
+    }
+}
+
+/// A team, see [`TeamData`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Team {
+    /// Team name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Starting rank numbers of the members.
+    #[serde(default)]
+    pub members: Vec<u16>,
+}
+
+impl Team {
+    /// Convert a team section, `path` is the path of the team in the document.
+    fn new(team: &TeamData, path: &str) -> Result<Self, JsonError> {
+        Ok(Self {
+            name: team.name().map(ToString::to_string),
+            members: team
+                .members()
+                .iter()
+                .enumerate()
+                .map(|(index, member)| {
+                    valid(member.as_ref(), || format!("{path}.members[{index}]")).copied()
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Convert the team to a team section.
+    fn team_data(&self) -> TeamData {
+        TeamData::new(self.name.clone(), &self.members)
+    }
+}
+
+/// The pairing constraints of TRF-2025, see [`constraint`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Constraints {
+    /// Players who announced their absence from a round (DIN 240).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub absent_players: Vec<Absence>,
+
+    /// Points added to the score of some players for pairing purposes (DIN 250).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accelerations: Vec<RangeAcceleration>,
+
+    /// Forbidden pairs (DIN 260), the starting rank numbers of each group of players.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_pairs: Vec<Vec<u16>>,
+}
+
+impl Constraints {
+    /// Whether there is no constraint.
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Convert the absent players to the records of a situation.
+    fn absent_players(&self) -> Result<Vec<AbsentPlayers>, JsonError> {
+        self.absent_players
+            .iter()
+            .enumerate()
+            .map(|(index, absence)| {
+                let path = format!("constraints.absent_players[{index}].bye");
+                match round::Result::try_from(absence.bye.as_str()) {
+                    Ok(round::Result::Bye(
+                        bye @ (ByeRoundResult::HalfPointBye
+                        | ByeRoundResult::FullPointBye
+                        | ByeRoundResult::ZeroPointBye),
+                    )) => Ok(AbsentPlayers::new(bye, absence.round, &absence.players)),
+                    Ok(_) => Err(JsonError::InvalidFieldError(
+                        path,
+                        TRFError::InvalidRoundResultError(absence.bye.clone()),
+                    )),
+                    Err(error) => Err(JsonError::InvalidFieldError(path, error)),
+                }
+            })
+            .collect()
+    }
+
+    /// Convert the accelerations to the records of a situation.
+    fn accelerations(&self) -> Vec<constraint::Acceleration> {
+        self.accelerations
+            .iter()
+            .map(|acceleration| {
+                constraint::Acceleration::with_bounds(
+                    acceleration.points,
+                    (acceleration.first_round, acceleration.last_round),
+                    (acceleration.first_player, acceleration.last_player),
+                )
+            })
+            .collect()
+    }
+
+    /// Convert the forbidden pairs to the records of a situation.
+    fn forbidden_pairs(&self) -> Vec<ForbiddenPairs> {
+        self.forbidden_pairs
+            .iter()
+            .map(|players| ForbiddenPairs::new(players))
+            .collect()
+    }
+}
+
+impl TryFrom<&Situation> for Constraints {
+    type Error = JsonError;
+
+    fn try_from(value: &Situation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            absent_players: value
+                .absent_players()
+                .iter()
+                .enumerate()
+                .map(|(index, absent_players)| {
+                    Absence::new(
+                        absent_players,
+                        &format!("constraints.absent_players[{index}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            accelerations: value
+                .accelerations()
+                .iter()
+                .enumerate()
+                .map(|(index, acceleration)| {
+                    RangeAcceleration::new(
+                        acceleration,
+                        &format!("constraints.accelerations[{index}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            forbidden_pairs: value
+                .forbidden_pairs()
+                .iter()
+                .enumerate()
+                .map(|(index, forbidden_pairs)| {
+                    players(
+                        forbidden_pairs.players(),
+                        &format!("constraints.forbidden_pairs[{index}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Get the starting rank numbers of a record, or an error with the path of the record if
+/// one is incorrectly formatted.
+fn players(players: &[Result<u16, TRFError>], path: &str) -> Result<Vec<u16>, JsonError> {
+    players
+        .iter()
+        .map(|player| valid(player.as_ref(), || path.to_string()).copied())
+        .collect()
+}
+
+/// Players who announced their absence from a round (DIN 240), see [`AbsentPlayers`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Absence {
+    /// The bye given to the players, `"H"`, `"F"` or `"Z"`.
+    pub bye: String,
+
+    /// The round, starting at 1.
+    pub round: u16,
+
+    /// Starting rank numbers of the players.
+    pub players: Vec<u16>,
+}
+
+impl Absence {
+    /// Convert an absent players record, `path` is the path of the record in the
+    /// document.
+    fn new(absent_players: &AbsentPlayers, path: &str) -> Result<Self, JsonError> {
+        let required =
+            |field: &str| JsonError::MissingFieldError(format!("{path}.{field}"));
+
+        Ok(Self {
+            bye: valid(absent_players.bye(), || format!("{path}.bye"))?
+                .map(Into::into)
+                .ok_or_else(|| required("bye"))?,
+            round: valid(absent_players.round(), || format!("{path}.round"))?
+                .ok_or_else(|| required("round"))?,
+            players: players(absent_players.players(), &format!("{path}.players"))?,
+        })
+    }
+}
+
+/// Points added to the score of a range of players for a range of rounds (DIN 250), see
+/// [`constraint::Acceleration`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RangeAcceleration {
+    /// The points added to the score.
+    pub points: f32,
+
+    /// The first round, left out if the range is open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_round: Option<u16>,
+
+    /// The last round, left out if the range is open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_round: Option<u16>,
+
+    /// Starting rank number of the first player, left out if the range is open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_player: Option<u16>,
+
+    /// Starting rank number of the last player, left out if the range is open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_player: Option<u16>,
+}
+
+impl RangeAcceleration {
+    /// Convert an acceleration record, `path` is the path of the record in the document.
+    fn new(
+        acceleration: &constraint::Acceleration,
+        path: &str,
+    ) -> Result<Self, JsonError> {
+        let field = |name: &str| format!("{path}.{name}");
+
+        Ok(Self {
+            points: valid(acceleration.points(), || field("points"))?
+                .ok_or_else(|| JsonError::MissingFieldError(field("points")))?,
+            first_round: *valid(acceleration.first_round(), || field("first_round"))?,
+            last_round: *valid(acceleration.last_round(), || field("last_round"))?,
+            first_player: *valid(acceleration.first_player(), || field("first_player"))?,
+            last_player: *valid(acceleration.last_player(), || field("last_player"))?,
+        })
+    }
+}
+
+/// The `JaVaFo` extension records, see [`extension::Extensions`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Extensions {
+    /// Number of rounds (XXR).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_rounds: Option<u16>,
+
+    /// Configuration of the pairing engine (XXC).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<EngineConfiguration>,
+
+    /// Accelerations of the players (XXA).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accelerations: Vec<Acceleration>,
+
+    /// Forbidden pairs (XXP), the starting rank numbers of each group of players.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_pairs: Vec<Vec<u16>>,
+
+    /// Custom score values (XXS), in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub score_values: Vec<ScoreValue>,
+}
+
+impl Extensions {
+    /// Whether there is no extension record.
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Convert the extension records to the extension records of a situation.
+    fn extensions(&self) -> Result<extension::Extensions, JsonError> {
+        let mut extensions = extension::Extensions::default();
+
+        if let Some(number_of_rounds) = self.number_of_rounds {
+            extensions.set_number_of_rounds(number_of_rounds);
+        }
+        if let Some(configuration) = &self.configuration {
+            let initial_color =
+                parse::<Color>(configuration.initial_color.as_deref(), || {
+                    "extensions.configuration.initial_color".to_string()
+                })?;
+            extensions
+                .set_configuration(Configuration::new(initial_color, configuration.rank));
+        }
+        for acceleration in &self.accelerations {
+            extensions.add_acceleration(PlayerAcceleration::new(
+                acceleration.player,
+                &acceleration.points,
+            ));
+        }
+        for players in &self.forbidden_pairs {
+            extensions.add_forbidden_pairs(ForbiddenPairs::new(players));
+        }
+        if !self.score_values.is_empty() {
+            let values: Vec<(ScoreCode, f32)> = self
+                .score_values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    ScoreCode::try_from(value.code.as_str())
+                        .map(|code| (code, value.points))
+                        .map_err(|error| {
+                            let path = format!("extensions.score_values[{index}].code");
+                            JsonError::InvalidFieldError(path, error)
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            extensions.set_score_values(ScoreValues::new(&values));
+        }
+
+        Ok(extensions)
+    }
+}
+
+impl TryFrom<&extension::Extensions> for Extensions {
+    type Error = JsonError;
+
+    fn try_from(value: &extension::Extensions) -> Result<Self, Self::Error> {
+        let path = |name: &str| format!("extensions.{name}");
+
+        Ok(Self {
+            number_of_rounds: *valid(value.number_of_rounds(), || {
+                path("number_of_rounds")
+            })?,
+            configuration: valid(value.configuration(), || path("configuration"))?.map(
+                |configuration| EngineConfiguration {
+                    initial_color: configuration.initial_color().map(Into::into),
+                    rank: configuration.rank(),
+                },
+            ),
+            accelerations: value
+                .accelerations()
+                .iter()
+                .enumerate()
+                .map(|(index, acceleration)| {
+                    Acceleration::new(
+                        acceleration,
+                        &format!("extensions.accelerations[{index}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            forbidden_pairs: value
+                .forbidden_pairs()
+                .iter()
+                .enumerate()
+                .map(|(index, forbidden_pairs)| {
+                    players(
+                        forbidden_pairs.players(),
+                        &format!("extensions.forbidden_pairs[{index}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            score_values: valid(value.score_values(), || path("score_values"))?
+                .as_ref()
+                .map(|score_values| {
+                    score_values
+                        .values()
+                        .iter()
+                        .map(|(code, points)| ScoreValue {
+                            code: code.to_string(),
+                            points: *points,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// The configuration of the pairing engine (XXC), see [`Configuration`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineConfiguration {
+    /// Color of the first player in round 1, `"w"` or `"b"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_color: Option<String>,
+
+    /// Whether the players are sorted by rating before pairing.
+    #[serde(default)]
+    pub rank: bool,
+}
+
+/// The accelerations of a player (XXA), see [`PlayerAcceleration`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Acceleration {
+    /// Starting rank number of the player.
+    pub player: u16,
+
+    /// Points added to the score of the player in each round, starting with round 1.
+    ///
+    /// Empty slots of the file are written as 0.
+    pub points: Vec<f32>,
+}
+
+impl Acceleration {
+    /// Convert the accelerations of a player, `path` is the path of the record in the
+    /// document.
+    fn new(acceleration: &PlayerAcceleration, path: &str) -> Result<Self, JsonError> {
+        Ok(Self {
+            player: valid(acceleration.player(), || format!("{path}.player"))?
+                .ok_or_else(|| JsonError::MissingFieldError(format!("{path}.player")))?,
+            points: acceleration
+                .points()
+                .iter()
+                .enumerate()
+                .map(|(index, points)| {
+                    valid(points.as_ref(), || format!("{path}.points[{index}]"))
+                        .map(|points| points.unwrap_or_default())
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A custom score value (XXS), see [`ScoreValues`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoreValue {
+    /// The score code, e.g. `"WW"`, see [`ScoreCode`].
+    pub code: String,
+
+    /// The points.
+    pub points: f32,
+}
@@ -5,7 +5,17 @@
 //! ## Usage
 //!
 //! 1. Read a TRF file.
-//! 2. TODO
+//! 2. Pair the next round, see [`pairing`].
+//!
+//! Tie-breaks can be computed from the rounds of each player, see [`tiebreak`], and used
+//! to rank the players, see [`standings`].
+//!
+//! A situation can be edited: late entries, withdrawals and results, see [`edit`].
+//!
+//! Round-robin tournaments can be scheduled and checked with the Berger tables, see
+//! [`roundrobin`].
+//!
+//! Random tournaments can be generated to test pairing engines, see [`rtg`].
 //!
 //! ### Reading TRF files
 //!
@@ -25,6 +35,41 @@
 //! // Display a debug view of the Situation:
 //! println!("{:#?}", situation);
 //! ```
+//!
+//! Incorrectly formatted data doesn't stop the parser, use [`Situation::read`] to get the
+//! problems found along with their position in the file.
+//!
+//! ### Writing TRF files
+//!
+//! Use [`String::from`] to write a [`Situation`] back to the TRF format:
+//!
+//! ```
+//! # use std::fs;
+//! # use openswisspairings_lib::Situation;
+//! # let situation = Situation::try_from(fs::read_to_string("test_file.trf").unwrap()).unwrap();
+//! let file_contents = String::from(&situation);
+//! ```
+//!
+//! ### Serialization
+//!
+//! With the `serde` feature, a [`Situation`] and everything it contains implement
+//! `Serialize` and `Deserialize`. Fields are written as their value, `null` if they are
+//! empty, or `{"error": "..."}` if they were incorrectly formatted in the file.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! # use std::fs;
+//! # use openswisspairings_lib::Situation;
+//! # let situation = Situation::try_from(fs::read_to_string("test_file.trf").unwrap()).unwrap();
+//! let json = serde_json::to_string(&situation).unwrap();
+//! let read: Situation = serde_json::from_str(&json).unwrap();
+//!
+//! assert_eq!(String::from(&read), String::from(&situation));
+//! # }
+//! ```
+//!
+//! This representation follows the data model of the library. For a stable, versioned
+//! format with a JSON Schema, see the `json` module (also requires the `serde` feature).
 #![deny(
     missing_docs,
     clippy::missing_docs_in_private_items,
@@ -37,26 +82,145 @@
 #![feature(iter_next_chunk)]
 
 use std::{
-    error::Error,
+    ops::Range,
     str::{Chars, Split},
 };
 
-use trf::{player::Section, TRFError};
+use trf::{
+    constraint::{AbsentPlayers, Acceleration, ForbiddenPairs},
+    diagnostic::{Diagnostic, Diagnostics, Severity},
+    extension::Extensions,
+    metadata::PlayerMetadata,
+    player::Section,
+    record::{Record, RecordKind},
+    scoring::{PointsMismatch, ScoringSystem},
+    team::TeamData,
+    tournament::TournamentData,
+    validation::{self, Inconsistency},
+    FormatVersion, ParseMode, TRFError,
+};
 
+pub mod edit;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod pairing;
+pub mod roundrobin;
+pub mod rtg;
+pub mod standings;
+pub mod tiebreak;
 pub mod trf;
 
+/// The errors found in the fields of a line, with the columns of each field.
+type FieldErrors = Vec<(Range<usize>, TRFError)>;
+
+/// A line separator: `\r`, `\n` or `\r\n`.
+///
+/// This is an alias so that the `serde` derive doesn't borrow the separator from the
+/// deserializer, which would require a `'static` input.
+type LineSeparator = &'static str;
+
 /// A situation represents a single tournament. It is the root object.
 ///
 /// You can load a situation from a TRF file with [`Situation::try_from`]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Situation {
     /// A vector containing all tournament players.
     players: Vec<Section>,
-    // tournament: TournamentData,
-    // teams: Vec<TeamData>,
+
+    /// The tournament header data.
+    tournament: TournamentData,
+
+    /// A vector containing all tournament teams.
+    teams: Vec<TeamData>,
+
+    /// The points given for each round result.
+    scoring_system: ScoringSystem,
+
+    /// Players who announced their absence from a round.
+    absent_players: Vec<AbsentPlayers>,
+
+    /// Points added to the score of some players for pairing purposes.
+    accelerations: Vec<Acceleration>,
+
+    /// Players who must not be paired with each other.
+    forbidden_pairs: Vec<ForbiddenPairs>,
+
+    /// The `JaVaFo` extension records.
+    extensions: Extensions,
+
+    /// The lines of the TRF file, in order.
+    ///
+    /// Used to write the situation back in the same layout it was read.
+    records: Vec<Record>,
+
+    /// The line separator used when writing the situation.
+    ///
+    /// FIDE uses `\r`, however many files use `\n` instead.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::trf::serialization::line_separator")
+    )]
+    line_separator: LineSeparator,
+
+    /// The mode the situation was read in, used to read the original lines again when
+    /// writing.
+    mode: ParseMode,
 }
 
 impl Situation {
+    /// Create a situation.
+    ///
+    /// The situation uses the standard scoring system. It is written with the header
+    /// records first, then the players and the teams, using the FIDE `\r` line separator.
+    #[must_use]
+    pub fn new(
+        tournament: TournamentData,
+        players: Vec<Section>,
+        teams: Vec<TeamData>,
+    ) -> Self {
+        let header = tournament
+            .records()
+            .into_iter()
+            .map(|(din, index)| RecordKind::Tournament(din.to_string(), index));
+        let scoring = ScoringSystem::RECORDS
+            .into_iter()
+            .map(|din| RecordKind::Scoring(din.to_string()));
+        let extensions = Extensions::default();
+        let extension = extensions
+            .records()
+            .into_iter()
+            .map(|(din, index)| RecordKind::Extension(din.to_string(), index));
+
+        let records = header
+            .chain(scoring)
+            .chain(extension)
+            .chain(players.iter().enumerate().flat_map(|(index, player)| {
+                let metadata = player
+                    .metadata()
+                    .keys()
+                    .map(move |key| RecordKind::Metadata(index, key.clone()));
+                std::iter::once(RecordKind::Player(index)).chain(metadata)
+            }))
+            .chain((0..teams.len()).map(RecordKind::Team))
+            .map(|kind| Record { kind, source: None })
+            .collect();
+
+        Self {
+            players,
+            tournament,
+            teams,
+            scoring_system: ScoringSystem::default(),
+            absent_players: vec![],
+            accelerations: vec![],
+            forbidden_pairs: vec![],
+            extensions: Extensions::default(),
+            records,
+            line_separator: "\r",
+            mode: ParseMode::default(),
+        }
+    }
+
     /// Get a list of all tournament players.
     ///
     /// See [`field@Situation::players`].
@@ -64,67 +228,636 @@ impl Situation {
     pub fn players(&self) -> &[Section] {
         &self.players
     }
-}
 
-impl TryFrom<String> for Situation {
-    type Error = Box<dyn Error>;
+    /// Get a mutable list of all tournament players.
+    pub(crate) fn players_mut(&mut self) -> &mut [Section] {
+        &mut self.players
+    }
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let split_char: char = if value.contains('\r') {
-            '\r'
+    /// Get the tournament header data.
+    ///
+    /// See [`field@Situation::tournament`].
+    #[must_use]
+    pub const fn tournament(&self) -> &TournamentData {
+        &self.tournament
+    }
+
+    /// Get a list of all tournament teams.
+    ///
+    /// See [`field@Situation::teams`].
+    #[must_use]
+    pub fn teams(&self) -> &[TeamData] {
+        &self.teams
+    }
+
+    /// Get the scoring system.
+    ///
+    /// See [`field@Situation::scoring_system`] and [`Situation::set_scoring_system`].
+    #[must_use]
+    pub const fn scoring_system(&self) -> &ScoringSystem {
+        &self.scoring_system
+    }
+
+    /// Get the players who announced their absence from a round (TRF-2025).
+    ///
+    /// See [`field@Situation::absent_players`].
+    #[must_use]
+    pub fn absent_players(&self) -> &[AbsentPlayers] {
+        &self.absent_players
+    }
+
+    /// Get the accelerations (TRF-2025).
+    ///
+    /// See [`field@Situation::accelerations`].
+    #[must_use]
+    pub fn accelerations(&self) -> &[Acceleration] {
+        &self.accelerations
+    }
+
+    /// Get the forbidden pairs (TRF-2025).
+    ///
+    /// See [`field@Situation::forbidden_pairs`].
+    #[must_use]
+    pub fn forbidden_pairs(&self) -> &[ForbiddenPairs] {
+        &self.forbidden_pairs
+    }
+
+    /// Get the `JaVaFo` extension records.
+    ///
+    /// See [`field@Situation::extensions`].
+    #[must_use]
+    pub const fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Get the total number of rounds of the tournament.
+    ///
+    /// It is read from the number of rounds (DIN 142), then from the `JaVaFo` extension
+    /// (XXR), then from the number of round dates (DIN 132). Returns [`None`] if none of
+    /// those is available.
+    #[must_use]
+    pub fn number_of_rounds(&self) -> Option<u16> {
+        let declared =
+            |field: Result<&Option<u16>, &TRFError>| field.ok().copied().flatten();
+
+        declared(self.tournament.number_of_rounds())
+            .or_else(|| declared(self.extensions.number_of_rounds()))
+            .or_else(|| u16::try_from(self.tournament.round_dates().len()).ok())
+            .filter(|rounds| *rounds > 0)
+    }
+
+    /// Get the revision of the TRF format used by the situation.
+    ///
+    /// The situation uses TRF-2025 if it contains any record added by the revision (see
+    /// [`FormatVersion::Trf2025`]), and TRF16 otherwise.
+    #[must_use]
+    pub fn format_version(&self) -> FormatVersion {
+        let trf_2025 = self
+            .records
+            .iter()
+            .any(|record| record.kind.trf_2025() && self.write_record(record).is_some());
+
+        if trf_2025 {
+            FormatVersion::Trf2025
+        } else {
+            FormatVersion::Trf16
+        }
+    }
+
+    /// Check the points column of every player against the points computed from their
+    /// rounds with the scoring system.
+    ///
+    /// Returns the players whose points don't match or are invalid, empty points aren't
+    /// checked. See [`ScoringSystem::score`].
+    #[must_use]
+    pub fn verify_points(&self) -> Vec<PointsMismatch> {
+        self.players
+            .iter()
+            .filter_map(|player| {
+                let id = *player.starting_rank_number().ok()?;
+                let found = match player.points() {
+                    Ok(None) => return None,
+                    Ok(Some(points)) => Some(*points),
+                    Err(_) => None,
+                };
+                let computed = self.scoring_system.score(player);
+
+                // Points are written with one decimal.
+                (!found.is_some_and(|points| (points - computed).abs() < 0.05))
+                    .then(|| PointsMismatch::new(id, found, computed))
+            })
+            .collect()
+    }
+
+    /// Cross-check the round data of the players.
+    ///
+    /// Reports opponents which don't exist, pairings which aren't mirrored by the
+    /// opponent, players paired twice in a round, extra pairing-allocated byes, and
+    /// colours or results which don't match or can't be used together. See
+    /// [`validation::validate`].
+    #[must_use]
+    pub fn validate(&self) -> Vec<Inconsistency> {
+        validation::validate(&self.players)
+    }
+
+    /// Get the teams ranked by their points, best team first.
+    ///
+    /// See [`TeamData::points`].
+    #[must_use]
+    pub fn team_ranking(&self) -> Vec<(&TeamData, f32)> {
+        let mut ranking: Vec<(&TeamData, f32)> = self
+            .teams
+            .iter()
+            .map(|team| (team, team.points(&self.players)))
+            .collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranking
+    }
+
+    /// Get the original text of the lines read from the TRF file, in order.
+    ///
+    /// Lines added with [`Situation::push_line`] are included, records created
+    /// otherwise (e.g. with [`Situation::new`]) aren't.
+    pub fn original_lines(&self) -> impl Iterator<Item = &str> {
+        self.records
+            .iter()
+            .filter_map(|record| record.source.as_deref())
+    }
+
+    /// Get the records which aren't supported by the parser, in order.
+    ///
+    /// Those are usually the records of other programs. They are kept as is and written
+    /// back verbatim, along with comments and blank lines (which aren't included here).
+    ///
+    /// ```
+    /// # use openswisspairings_lib::Situation;
+    /// let file_contents = "\
+    /// 012 Test Open
+    /// ABC 1 vendor data
+    ///
+    /// 001    1      Alpha, Anna                       2000                             0.0";
+    ///
+    /// let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+    /// assert_eq!(situation.unknown_records().collect::<Vec<_>>(), ["ABC 1 vendor data"]);
+    ///
+    /// situation.push_line("XYZ our own record");
+    /// situation.retain_unknown_records(|line| !line.starts_with("ABC"));
+    /// assert_eq!(
+    ///     String::from(&situation),
+    ///     "012 Test Open\n\n\
+    ///      001    1      Alpha, Anna                       2000                             0.0\n\
+    ///      XYZ our own record"
+    /// );
+    /// ```
+    pub fn unknown_records(&self) -> impl Iterator<Item = &str> {
+        self.records
+            .iter()
+            .filter(|record| matches!(record.kind, RecordKind::Other))
+            .filter_map(|record| record.source.as_deref())
+            .filter(|line| Self::is_record(line))
+    }
+
+    /// Add a line after the last record.
+    ///
+    /// The line is read like a line of a TRF file: supported records update the data of
+    /// the situation, other lines are kept as is. Returns the problems found, see
+    /// [`Situation::read`].
+    pub fn push_line(&mut self, line: &str) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        self.read_record(line, &mut diagnostics);
+
+        diagnostics
+    }
+
+    /// Keep only the unsupported records for which `f` returns `true`.
+    ///
+    /// Comments, blank lines and supported records are always kept. See
+    /// [`Situation::unknown_records`].
+    pub fn retain_unknown_records(&mut self, mut f: impl FnMut(&str) -> bool) {
+        self.records
+            .retain(|record| match (&record.kind, &record.source) {
+                (RecordKind::Other, Some(line)) => !Self::is_record(line) || f(line),
+                _ => true,
+            });
+    }
+
+    /// Whether a line kept as is is a record, rather than a comment or a blank line.
+    ///
+    /// Records start with a data identification number of three letters or digits
+    /// followed by a space.
+    fn is_record(line: &str) -> bool {
+        let mut chars = line.chars();
+        let din = chars.by_ref().take(3);
+
+        din.filter(char::is_ascii_alphanumeric).count() == 3 && chars.next() == Some(' ')
+    }
+
+    /// Read a TRF file in [`ParseMode::Lenient`], along with the problems found.
+    ///
+    /// Data which can't be read doesn't stop the parser: incorrectly formatted fields are
+    /// stored as [`Err`], and unsupported records are kept as is. Each problem is
+    /// reported as a diagnostic, see [`trf::diagnostic`].
+    #[must_use]
+    pub fn read(value: &str) -> (Self, Diagnostics) {
+        Self::parse(value, ParseMode::Lenient)
+    }
+
+    /// Read a TRF file in the given mode, along with the problems found.
+    ///
+    /// See [`Situation::read`] and [`ParseMode`].
+    ///
+    /// ```
+    /// # use openswisspairings_lib::{trf::ParseMode, Situation};
+    /// let file_contents = "\
+    /// 012 Test Open\r
+    /// 001    1      Alpha, Anna                       2000";
+    ///
+    /// let (_, diagnostics) =
+    ///     Situation::read_with(file_contents, ParseMode::Lenient).unwrap();
+    /// assert_eq!(diagnostics.warnings().count(), 1);
+    /// assert_eq!(
+    ///     diagnostics.all()[0].to_string(),
+    ///     "warning: Expected the FIDE \\r line separator, found \\r\\n"
+    /// );
+    ///
+    /// assert!(Situation::read_with(file_contents, ParseMode::Strict).is_err());
+    /// let fide = file_contents.replace("\r\n", "\r");
+    /// assert!(Situation::read_with(&fide, ParseMode::Strict).is_ok());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// In [`ParseMode::Strict`], returns the diagnostics if any deviation from TRF16 was
+    /// found. This never fails in [`ParseMode::Lenient`].
+    pub fn read_with(
+        value: &str,
+        mode: ParseMode,
+    ) -> Result<(Self, Diagnostics), Diagnostics> {
+        let (situation, diagnostics) = Self::parse(value, mode);
+
+        if mode == ParseMode::Strict && !diagnostics.is_empty() {
+            Err(diagnostics)
+        } else {
+            Ok((situation, diagnostics))
+        }
+    }
+
+    /// Read a TRF file in the given mode.
+    ///
+    /// In [`ParseMode::Strict`], every diagnostic is an error.
+    fn parse(value: &str, mode: ParseMode) -> (Self, Diagnostics) {
+        let mut diagnostics = Diagnostics::default();
+        let warning = match mode {
+            ParseMode::Strict => Severity::Error,
+            ParseMode::Lenient => Severity::Warning,
+        };
+
+        let line_separator: LineSeparator = if value.contains("\r\n") {
+            "\r\n"
+        } else if value.contains('\r') {
+            "\r"
         } else {
-            eprintln!("Using non-FIDE \\n line separator");
-            '\n'
+            "\n"
         };
-        let lines: Split<'_, char> = value.split(split_char);
+        if line_separator != "\r" {
+            diagnostics.push(Diagnostic::whole_file(
+                warning,
+                TRFError::NonFideLineSeparator(line_separator.escape_debug().to_string()),
+            ));
+        }
+        let lines: Split<'_, &str> = value.split(line_separator);
 
-        let mut players: Vec<Section> = vec![];
+        let mut situation = Self {
+            players: vec![],
+            tournament: TournamentData::default(),
+            teams: vec![],
+            scoring_system: ScoringSystem::default(),
+            absent_players: vec![],
+            accelerations: vec![],
+            forbidden_pairs: vec![],
+            extensions: Extensions::default(),
+            records: vec![],
+            line_separator,
+            mode,
+        };
 
         for line in lines {
-            if line.len() > 4 {
-                let mut chars: Chars<'_> = line.chars();
-                let din = String::from_iter(
-                    chars
-                        .next_chunk::<3>()
-                        .map_err(|_| TRFError::UnexpectedEndOfString())?,
-                );
-                chars.next(); // Drop space between DIN and rest of data
-                let argument: String = chars.collect::<String>();
-
-                println!(": {din} {argument}");
-
-                match din.as_str() {
-                    "001" => {
-                        if let Ok(i) = Section::try_from(argument.clone()) {
-                            players.push(i);
-                        } else {
-                            eprintln!("### Failed to read player data: {argument}");
-                        }
-                    } // (player data)
-                    "012" => println!("Tournament Name"),
-                    "022" => println!("City"),
-                    "032" => println!("Federation"),
-                    "042" => println!("Date of start"),
-                    "052" => println!("Date of end"),
-                    "062" => println!("Number of players"),
-                    "072" => println!("Number of rated players"),
-                    "082" => println!("Number of teams"),
-                    "092" => println!("Type of tournament"),
-                    "102" => println!("Chief Arbiter"),
-                    "112" => println!("Deputy Chief Arbiter"),
-                    "122" => println!("Alloted time per moves/game"),
-                    "132" => println!("Dates of the round"),
-                    "013" => println!("(team data)"),
-                    "XXR" => println!("JaVaFo TRF(x)"),
-                    "XRS" => println!(
-                        "Unknown extension - Found in Tornelo TRF(x) files (supposed: Source)"
-                    ),
-                    "DAT" => println!("Tornelo TRF(x) Extension"),
-                    _ => println!("{}", &line[0..3]),
+            situation.read_record(line, &mut diagnostics);
+        }
+
+        (situation, diagnostics)
+    }
+
+    /// Read a line and add it as the last record of the situation.
+    ///
+    /// Unsupported records are kept as is. The problems found are added to
+    /// `diagnostics`, the line number is the position of the record.
+    fn read_record(&mut self, line: &str, diagnostics: &mut Diagnostics) {
+        let number = self.records.len() + 1;
+        let warning = match self.mode {
+            ParseMode::Strict => Severity::Error,
+            ParseMode::Lenient => Severity::Warning,
+        };
+        let mut kind = RecordKind::Other;
+        let mut chars: Chars<'_> = line.chars();
+
+        if let (true, Ok(din)) = (line.chars().count() > 4, chars.next_chunk::<3>()) {
+            let din = String::from_iter(din);
+            chars.next(); // Drop space between DIN and rest of data
+            let argument: String = chars.collect::<String>();
+
+            if let Some((record_kind, errors)) = self.read_line(&din, &argument) {
+                kind = record_kind;
+                for (columns, error) in errors {
+                    diagnostics.push(Diagnostic::new(
+                        number,
+                        &din,
+                        columns.start + 4..columns.end + 4,
+                        Severity::Error,
+                        error,
+                    ));
                 }
+            } else {
+                diagnostics.push(Diagnostic::new(
+                    number,
+                    &din,
+                    0..3,
+                    warning,
+                    TRFError::UnsupportedRecordError(din.clone()),
+                ));
             }
         }
 
-        Ok(Self { players })
+        self.records.push(Record::read(kind, line));
+    }
+
+    /// Read the data of a line into the situation.
+    ///
+    /// `argument` is the line without its data identification number and the following
+    /// space. Returns the kind of record along with the errors of its fields (columns
+    /// don't include the data identification number and the space), or [`None`] if the
+    /// record isn't supported.
+    fn read_line(
+        &mut self,
+        din: &str,
+        argument: &str,
+    ) -> Option<(RecordKind, FieldErrors)> {
+        let whole_argument = 0..argument.chars().count();
+        let strict = self.mode == ParseMode::Strict;
+        let mut errors: FieldErrors = vec![];
+
+        let kind = match din {
+            "001" => {
+                let i = Section::read(argument, self.mode);
+                errors = i.errors();
+                if strict {
+                    errors.extend(Section::strict_errors(argument));
+                }
+                self.players.push(i);
+                RecordKind::Player(self.players.len() - 1)
+            } // (player data)
+            "012" | "022" | "032" | "042" | "052" | "062" | "072" | "082" | "092"
+            | "102" | "112" | "122" | "132" | "142" | "162" | "172" | "202" | "XRS" => {
+                let index = if din == TournamentData::DEPUTY_CHIEF_ARBITER {
+                    self.tournament.deputy_chief_arbiters().len()
+                } else {
+                    0
+                };
+                self.tournament.read_record(din, argument, self.mode);
+                errors = self.tournament.errors(din, argument);
+                if strict {
+                    errors.extend(TournamentData::strict_errors(din, argument));
+                }
+                RecordKind::Tournament(din.to_string(), index)
+            }
+            "013" => match TeamData::try_from(argument) {
+                Ok(i) => {
+                    errors = i.errors(argument);
+                    self.teams.push(i);
+                    RecordKind::Team(self.teams.len() - 1)
+                }
+                Err(error) => {
+                    errors.push((whole_argument, error));
+                    RecordKind::Other
+                }
+            },
+            "DAT" => match PlayerMetadata::try_from(argument) {
+                Ok(i) => {
+                    errors = i.errors();
+                    self.read_metadata(&i, &mut errors)
+                }
+                Err(error) => {
+                    errors.push((whole_argument, error));
+                    RecordKind::Other
+                }
+            },
+            "240" => match AbsentPlayers::try_from(argument) {
+                Ok(i) => {
+                    errors = i.errors(argument);
+                    self.absent_players.push(i);
+                    RecordKind::AbsentPlayers(self.absent_players.len() - 1)
+                }
+                Err(error) => {
+                    errors.push((whole_argument, error));
+                    RecordKind::Other
+                }
+            },
+            "250" => match Acceleration::try_from(argument) {
+                Ok(i) => {
+                    errors = i.errors();
+                    self.accelerations.push(i);
+                    RecordKind::Acceleration(self.accelerations.len() - 1)
+                }
+                Err(error) => {
+                    errors.push((whole_argument, error));
+                    RecordKind::Other
+                }
+            },
+            "260" => match ForbiddenPairs::try_from(argument) {
+                Ok(i) => {
+                    errors = i.errors(argument);
+                    self.forbidden_pairs.push(i);
+                    RecordKind::ForbiddenPairs(self.forbidden_pairs.len() - 1)
+                }
+                Err(error) => {
+                    errors.push((whole_argument, error));
+                    RecordKind::Other
+                }
+            },
+            "XXR" | "XXC" | "XXA" | "XXP" | "XXS" => {
+                let index = self.extensions.read_record(din, argument);
+                errors = self.extensions.errors(din, index, argument);
+                RecordKind::Extension(din.to_string(), index)
+            }
+            "BBW" | "BBD" | "BBL" | "BBZ" | "BBF" | "BBU" => {
+                self.scoring_system.read_record(din, argument);
+                errors.extend(self.scoring_system.error(din, argument));
+                RecordKind::Scoring(din.to_string())
+            }
+            _ => return None,
+        };
+
+        if strict && kind.trf_2025() {
+            errors.push((
+                0..argument.chars().count(),
+                TRFError::Trf2025RecordError(din.to_string()),
+            ));
+        }
+
+        Some((kind, errors))
+    }
+
+    /// Attach a player metadata record to the player section of the same starting rank
+    /// number.
+    ///
+    /// If the player doesn't exist, the record is kept as is and an error is added.
+    fn read_metadata(
+        &mut self,
+        metadata: &PlayerMetadata,
+        errors: &mut FieldErrors,
+    ) -> RecordKind {
+        let Ok(id) = metadata.player() else {
+            return RecordKind::Other;
+        };
+        let found = self
+            .players
+            .iter_mut()
+            .enumerate()
+            .find(|(_, player)| player.starting_rank_number().is_ok_and(|i| i == id));
+
+        if let Some((index, player)) = found {
+            player.set_metadata(metadata.key(), metadata.value());
+            RecordKind::Metadata(index, metadata.key().to_string())
+        } else {
+            errors.push((0..4, TRFError::UnknownPlayerError(*id)));
+            RecordKind::Other
+        }
+    }
+
+    /// Write a single record as a TRF line.
+    ///
+    /// If the data of the record didn't change since it was read, the original line is
+    /// returned. Returns [`None`] if the record is now empty.
+    fn write_record(&self, record: &Record) -> Option<String> {
+        let written = match &record.kind {
+            RecordKind::Player(index) => self.players.get(*index).map(Into::into),
+            RecordKind::Team(index) => self.teams.get(*index).map(Into::into),
+            RecordKind::Metadata(index, key) => self.players.get(*index).and_then(|i| {
+                let metadata = PlayerMetadata::new(
+                    *i.starting_rank_number().ok()?,
+                    key,
+                    i.metadata_value(key)?,
+                );
+                Some((&metadata).into())
+            }),
+            RecordKind::Tournament(din, index) => {
+                self.tournament.write_record(din, *index)
+            }
+            RecordKind::Scoring(din) => self.scoring_system.write_record(din),
+            RecordKind::AbsentPlayers(index) => {
+                self.absent_players.get(*index).map(Into::into)
+            }
+            RecordKind::Acceleration(index) => {
+                self.accelerations.get(*index).map(Into::into)
+            }
+            RecordKind::ForbiddenPairs(index) => {
+                self.forbidden_pairs.get(*index).map(Into::into)
+            }
+            RecordKind::Extension(din, index) => {
+                self.extensions.write_record(din, *index)
+            }
+            RecordKind::Other => None,
+        };
+
+        let Some(source) = &record.source else {
+            return written;
+        };
+
+        // Read the original line again to see whether its data changed.
+        let argument: String = source.chars().skip(4).collect();
+        let original: Option<String> = match &record.kind {
+            RecordKind::Player(_) => Some((&Section::read(&argument, self.mode)).into()),
+            RecordKind::Team(_) => TeamData::try_from(argument.as_str())
+                .ok()
+                .map(|i| (&i).into()),
+            RecordKind::Metadata(..) => PlayerMetadata::try_from(argument.as_str())
+                .ok()
+                .map(|i| (&i).into()),
+            RecordKind::Tournament(din, _) => {
+                let mut tournament = TournamentData::default();
+                tournament.read_record(din, &argument, self.mode);
+                tournament.write_record(din, 0)
+            }
+            RecordKind::Scoring(din) => {
+                let mut scoring_system = ScoringSystem::default();
+                scoring_system.read_record(din, &argument);
+                scoring_system.write_record(din)
+            }
+            RecordKind::AbsentPlayers(_) => AbsentPlayers::try_from(argument.as_str())
+                .ok()
+                .map(|i| (&i).into()),
+            RecordKind::Acceleration(_) => Acceleration::try_from(argument.as_str())
+                .ok()
+                .map(|i| (&i).into()),
+            RecordKind::ForbiddenPairs(_) => ForbiddenPairs::try_from(argument.as_str())
+                .ok()
+                .map(|i| (&i).into()),
+            RecordKind::Extension(din, _) => {
+                let mut extensions = Extensions::default();
+                extensions.read_record(din, &argument);
+                extensions.write_record(din, 0)
+            }
+            RecordKind::Other => None,
+        };
+
+        if written == original {
+            Some(source.clone())
+        } else {
+            written
+        }
+    }
+}
+
+impl From<&Situation> for String {
+    /// Write the situation as a TRF file.
+    ///
+    /// Lines are written in the order they were read, using the same line separator.
+    /// Lines whose data didn't change are written back verbatim, so reading and writing
+    /// a file gives back the exact same file.
+    ///
+    /// ```
+    /// use std::fs;
+    /// # use openswisspairings_lib::Situation;
+    ///
+    /// for file_path in [
+    ///     "test_file.trf",
+    ///     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+    ///     "lichess_swiss_2024.10.13_hId1KZmF_rapid.trf",
+    /// ] {
+    ///     let file_contents = fs::read_to_string(file_path).unwrap();
+    ///     let situation = Situation::try_from(file_contents.clone()).unwrap();
+    ///
+    ///     assert_eq!(String::from(&situation), file_contents);
+    /// }
+    /// ```
+    fn from(val: &Situation) -> Self {
+        val.records
+            .iter()
+            .filter_map(|record| val.write_record(record))
+            .collect::<Vec<Self>>()
+            .join(val.line_separator)
+    }
+}
+
+impl TryFrom<String> for Situation {
+    type Error = TRFError;
+
+    /// Read a TRF file.
+    ///
+    /// Data which can't be read doesn't stop the parser, use [`Situation::read`] to get
+    /// the problems found.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(Self::read(&value).0)
     }
 }
@@ -0,0 +1,143 @@
+//! # Burstein system
+//!
+//! This module implements the Burstein system, C.04.4.2 of the FIDE Handbook.
+//!
+//! ## Status
+//!
+//! Players are ranked by score, then by starting rank number during the seeding rounds
+//! (half of the rounds, at most [`MAX_SEEDING_ROUNDS`]). After the seeding rounds,
+//! players with the same score are ranked by their index: Buchholz, then
+//! Sonneborn-Berger, both computed from the previous rounds (see [`crate::tiebreak`]),
+//! then starting rank number.
+//!
+//! Score groups are paired from the top with the search of the Dutch system (see
+//! [`super::dutch`]), except that the bottom half is paired in reverse order against the
+//! top half: the first player of a score group meets the last one, the second player the
+//! second to last, and so on. Players moved down from a higher score group meet the
+//! lowest players of their new score group.
+//!
+//! The absolute criteria, the float criteria and the colour allocation are those of the
+//! Dutch system. There are no topscorers, so absolute colour preferences are always
+//! respected.
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::burstein, Situation};
+//!
+//! let file_contents = fs::read_to_string(
+//!     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+//! )
+//! .unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! let pairings = burstein::pair(&situation, 6).unwrap();
+//!
+//! assert_eq!(pairings.games().len(), 4);
+//! assert!(pairings.bye().is_some());
+//! ```
+use crate::{
+    tiebreak::{Tiebreak, Tiebreaks},
+    Situation,
+};
+
+use super::{
+    dutch::Dutch,
+    history::{histories, PlayerHistory},
+    PairingError, Pairings,
+};
+
+/// Maximum number of seeding rounds.
+pub const MAX_SEEDING_ROUNDS: u16 = 4;
+
+/// Get the number of seeding rounds, during which players with the same score are ranked
+/// by starting rank number.
+///
+/// If the number of rounds of the tournament is unknown, there are
+/// [`MAX_SEEDING_ROUNDS`] seeding rounds.
+#[must_use]
+pub fn seeding_rounds(situation: &Situation) -> u16 {
+    situation
+        .number_of_rounds()
+        .map_or(MAX_SEEDING_ROUNDS, |rounds| {
+            (rounds / 2).min(MAX_SEEDING_ROUNDS)
+        })
+}
+
+/// Pair a round of a tournament with the Burstein system.
+///
+/// Players are paired based on the rounds before `round`. Players who already have a
+/// bye (other than a pairing-allocated bye) in `round` are considered absent.
+///
+/// Player 10 is moved down to the bracket of the players on ½ and meets player 9, the
+/// last resident. The remainder of the bracket is paired in rank order, the top half
+/// against the reversed bottom half, so the last player (8) floats down to the bottom
+/// bracket:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{burstein, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             0.0         11 w 0
+/// 001    2      Bravo, Ben                        1900                             0.0         10 b 0
+/// 001    3      Charlie, Cleo                     1800                             0.5          9 w =
+/// 001    4      Delta, Dan                        1700                             0.5          8 b =
+/// 001    5      Echo, Eve                         1600                             0.5          7 w =
+/// 001    6      Foxtrot, Fay                      1500                             1.0            - U
+/// 001    7      Golf, Gus                         1400                             0.5          5 b =
+/// 001    8      Hotel, Hal                        1300                             0.5          4 w =
+/// 001    9      India, Ida                        1200                             0.5          3 b =
+/// 001   10      Juliet, Jo                        1100                             1.0          2 w 1
+/// 001   11      Kilo, Kim                         1000                             1.0          1 b 1";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = burstein::pair(&situation, 2).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [
+///         Game::new(11, 6),
+///         Game::new(9, 10),
+///         Game::new(7, 3),
+///         Game::new(4, 5),
+///         Game::new(2, 8),
+///     ]
+/// );
+/// assert_eq!(pairings.bye(), Some(1));
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the round is invalid, if a player's starting rank number is
+/// invalid, or if no pairing satisfies the absolute criteria.
+pub fn pair(situation: &Situation, round: u16) -> Result<Pairings, PairingError> {
+    let mut histories: Vec<PlayerHistory> = histories(situation, round)?
+        .into_iter()
+        .filter(|history| !history.absent)
+        .collect();
+
+    if round > seeding_rounds(situation) {
+        let tiebreaks = Tiebreaks::before(situation, round);
+        let index = |history: &PlayerHistory| {
+            [Tiebreak::Buchholz, Tiebreak::SonnebornBerger]
+                .map(|tiebreak| tiebreaks.value(tiebreak, history.id, &[]))
+        };
+
+        histories.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| {
+                    index(b)
+                        .iter()
+                        .zip(index(a))
+                        .map(|(b, a)| b.total_cmp(&a))
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then(a.id.cmp(&b.id))
+        });
+    } else {
+        histories.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    }
+
+    Dutch::ranked(situation, histories, true, |_| false).pairings()
+}
@@ -0,0 +1,411 @@
+//! # Pairing checker
+//!
+//! This module checks a round which was already paired (e.g. by another program or by
+//! hand) against the FIDE Dutch system: the round is paired again from the previous
+//! rounds with [`dutch::pair`], and every difference is reported along with the
+//! criterion it breaks.
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::checker, Situation};
+//!
+//! let file_contents = fs::read_to_string(
+//!     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+//! )
+//! .unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! // The third round was paired exactly like the engine pairs it.
+//! assert!(checker::check(&situation, 3).unwrap().is_empty());
+//!
+//! for discrepancy in checker::check(&situation, 1).unwrap() {
+//!     println!("Player {}: {}", discrepancy.player(), discrepancy.criterion());
+//! }
+//! ```
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    trf::player::round::{ByeRoundResult, Color, Result as RoundResult},
+    Situation,
+};
+
+use super::{
+    dutch::{self, Dutch, Pair, Player, Quality},
+    Game, PairingError, Pairings,
+};
+
+/// A rule of the FIDE Dutch system broken by a pairing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Criterion {
+    /// C1: the two players already played each other, or their pairing is forbidden.
+    Rematch,
+
+    /// C2: the player already received a pairing-allocated bye, or scored a win without
+    /// playing.
+    RepeatedBye,
+
+    /// C3: two non-topscorers with the same absolute colour preference meet.
+    AbsoluteColor,
+
+    /// C4: a player taking part in the round was neither paired nor given the
+    /// pairing-allocated bye.
+    Unpaired,
+
+    /// The pairing is worse than the engine's on a quality criterion, stores the number
+    /// of the criterion (e.g. 12 for C12).
+    Quality(u8),
+
+    /// The pairing is as good as the engine's, but the engine finds another one first
+    /// following the order of sections B and D (transpositions and exchanges).
+    Sequence,
+
+    /// The pairing is the same, but colours aren't allocated with the rules of section E
+    /// (e.g. a player doesn't get their absolute colour preference).
+    ColorAllocation,
+}
+
+impl Display for Criterion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rematch => write!(
+                f,
+                "C1: the players already played each other or must not meet"
+            ),
+            Self::RepeatedBye => write!(f, "C2: the player already had a bye"),
+            Self::AbsoluteColor => {
+                write!(
+                    f,
+                    "C3: the players have the same absolute colour preference"
+                )
+            }
+            Self::Unpaired => write!(f, "C4: the player isn't paired"),
+            Self::Quality(criterion) => {
+                write!(f, "C{criterion}: the quality criterion isn't optimised")
+            }
+            Self::Sequence => write!(f, "B/D: another pairing comes first"),
+            Self::ColorAllocation => write!(f, "E: colours are allocated differently"),
+        }
+    }
+}
+
+/// What a player got in a round.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Assignment {
+    /// A game.
+    Game(Game),
+
+    /// The pairing-allocated bye, stores the starting rank number of the player.
+    Bye(u16),
+}
+
+/// A difference between a round and the pairing of the engine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    /// Starting rank number of the player the discrepancy is about (the player with
+    /// white for games).
+    player: u16,
+
+    /// The broken criterion.
+    criterion: Criterion,
+
+    /// What the player got in the round.
+    actual: Option<Assignment>,
+
+    /// What the player gets with the engine.
+    expected: Option<Assignment>,
+}
+
+impl Discrepancy {
+    /// Get the starting rank number of the player the discrepancy is about (the player
+    /// with white for games).
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the broken criterion.
+    #[must_use]
+    pub const fn criterion(&self) -> Criterion {
+        self.criterion
+    }
+
+    /// Get what the player got in the round.
+    #[must_use]
+    pub const fn actual(&self) -> Option<Assignment> {
+        self.actual
+    }
+
+    /// Get what the player gets with the engine.
+    #[must_use]
+    pub const fn expected(&self) -> Option<Assignment> {
+        self.expected
+    }
+}
+
+/// Get the assignment of a player in some pairings.
+fn assignment(pairings: &Pairings, player: u16) -> Option<Assignment> {
+    pairings.game(player).map_or_else(
+        || (pairings.bye() == Some(player)).then_some(Assignment::Bye(player)),
+        |game| Some(Assignment::Game(*game)),
+    )
+}
+
+/// Read the games and pairing-allocated byes of a round.
+fn read_round(
+    situation: &Situation,
+    round: u16,
+) -> Result<(Vec<Game>, Vec<u16>), PairingError> {
+    let mut games = vec![];
+    let mut byes = vec![];
+
+    for (index, player) in situation.players().iter().enumerate() {
+        let id = *player
+            .starting_rank_number()
+            .map_err(|_| PairingError::InvalidStartingRankNumberError(index))?;
+        let Some(section) = player
+            .rounds()
+            .get(usize::from(round) - 1)
+            .and_then(Option::as_ref)
+        else {
+            continue;
+        };
+
+        let opponent = section.id().ok().copied().flatten();
+        let color = section.color().ok().copied().flatten();
+        let result = section.result().ok().copied().flatten();
+
+        match (opponent, color) {
+            (Some(opponent), Some(Color::White)) => games.push(Game::new(id, opponent)),
+            (Some(opponent), Some(Color::None) | None) if id < opponent => {
+                games.push(Game::new(id, opponent));
+            }
+            (None, _)
+                if result
+                    == Some(RoundResult::Bye(ByeRoundResult::PairingAllocatedBye)) =>
+            {
+                byes.push(id);
+            }
+            _ => {}
+        }
+    }
+
+    Ok((games, byes))
+}
+
+/// Get the pairs of some games, the higher ranked player first.
+fn pairs<'a>(engine: &'a Dutch, games: &[Game]) -> Vec<Pair<'a>> {
+    games
+        .iter()
+        .filter_map(|game| {
+            let white = engine.player(game.white())?;
+            let black = engine.player(game.black())?;
+            Some(if white.rank < black.rank {
+                (white, black)
+            } else {
+                (black, white)
+            })
+        })
+        .collect()
+}
+
+/// Check the absolute criteria C1 and C3 on a game.
+fn check_game(white: &Player, black: &Player) -> Option<Criterion> {
+    if white.history.played(black.history.id) || white.history.forbids(black.history.id) {
+        Some(Criterion::Rematch)
+    } else if !Dutch::compatible(white, black) {
+        Some(Criterion::AbsoluteColor)
+    } else {
+        None
+    }
+}
+
+/// Evaluate the brackets of a round from the top, see [`Dutch::evaluate`].
+///
+/// There is a bracket for each score. A pair belongs to the bracket of its lower score,
+/// and the floaters of a bracket are the players with at least its score who are paired
+/// in a lower bracket or get the bye.
+fn brackets(engine: &Dutch, pairings: &Pairings) -> Vec<Quality> {
+    let pairs = pairs(engine, pairings.games());
+    let bye = pairings.bye().and_then(|id| engine.player(id));
+
+    let mut scores: Vec<f32> = engine.players.iter().map(|i| i.history.score).collect();
+    scores.dedup_by(|a, b| a.total_cmp(b).is_eq());
+
+    scores
+        .iter()
+        .enumerate()
+        .map(|(index, score)| {
+            let above = |player: &Player| player.history.score >= *score;
+            let lower = |(a, b): &Pair<'_>| a.history.score.min(b.history.score);
+
+            let bracket: Vec<Pair<'_>> = pairs
+                .iter()
+                .filter(|pair| lower(pair).total_cmp(score).is_eq())
+                .copied()
+                .collect();
+            let floaters: Vec<&Player> = pairs
+                .iter()
+                .filter(|pair| lower(pair) < *score)
+                .flat_map(|(a, b)| [*a, *b])
+                .chain(bye)
+                .filter(|player| above(player))
+                .collect();
+            let next: Vec<&Player> = scores
+                .get(index + 1)
+                .map(|next| {
+                    engine
+                        .players
+                        .iter()
+                        .filter(|player| player.history.score.total_cmp(next).is_eq())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            engine.evaluate(
+                &bracket,
+                &floaters,
+                *score,
+                Dutch::next_pairs(&floaters, &next),
+                next.is_empty(),
+            )
+        })
+        .collect()
+}
+
+/// Check a round which was already paired against the FIDE Dutch system.
+///
+/// The round is paired again with [`dutch::pair`] from the rounds before `round`, and
+/// the games and byes found in the players' sections are compared to the result:
+/// - games and byes breaking an absolute criterion (C1 to C3) are reported with that
+///   criterion,
+/// - players taking part in the round who weren't paired are reported with C4,
+/// - other games and byes which differ from the engine are reported with the first
+///   quality criterion on which the round is worse than the engine's pairing, or with
+///   [`Criterion::Sequence`] if there is none. Brackets are compared from the top, the
+///   first bracket whose quality differs gives the criterion,
+/// - games between the same players with different colours are reported with
+///   [`Criterion::ColorAllocation`].
+///
+/// An empty list means the round is paired exactly like the engine pairs it.
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::checker::{self, Criterion}, Situation};
+/// // All the games of the first two rounds are drawn.
+/// let situation = |round_2: [&str; 4]| {
+///     let players = [
+///         "001    1      Alpha, Anna                       2000                             1.0          3 w =",
+///         "001    2      Bravo, Ben                        1900                             1.0          4 b =",
+///         "001    3      Charlie, Cleo                     1800                             1.0          1 b =",
+///         "001    4      Delta, Dan                        1700                             1.0          2 w =",
+///     ];
+///     let players: Vec<String> = players
+///         .iter()
+///         .zip(round_2)
+///         .map(|(player, game)| format!("{player}  {game}"))
+///         .collect();
+///     Situation::try_from(format!("012 Test Open\n{}", players.join("\n"))).unwrap()
+/// };
+/// let criteria = |round_2| {
+///     checker::check(&situation(round_2), 2)
+///         .unwrap()
+///         .iter()
+///         .map(|i| (i.player(), i.criterion()))
+///         .collect::<Vec<_>>()
+/// };
+///
+/// // The engine pairs 2-1 and 3-4.
+/// assert!(criteria(["   2 b =", "   1 w =", "   4 w =", "   3 b ="]).is_empty());
+///
+/// // 1 and 2 are paired like the engine, but with the wrong colours.
+/// assert_eq!(
+///     criteria(["   2 w =", "   1 b =", "   4 w =", "   3 b ="]),
+///     [(1, Criterion::ColorAllocation)]
+/// );
+///
+/// // 1-4 and 2-3 both meet two players expecting the same colour.
+/// assert_eq!(
+///     criteria(["   4 w =", "   3 w =", "   2 b =", "   1 b ="]),
+///     [(1, Criterion::Quality(12)), (2, Criterion::Quality(12))]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the round is invalid or wasn't paired yet, if a player's starting
+/// rank number is invalid, or if the engine can't pair the round.
+pub fn check(
+    situation: &Situation,
+    round: u16,
+) -> Result<Vec<Discrepancy>, PairingError> {
+    let engine = Dutch::new(situation, round)?;
+    let expected = dutch::pair(situation, round)?;
+    let (games, byes) = read_round(situation, round)?;
+    if games.is_empty() && byes.is_empty() {
+        return Err(PairingError::InvalidRoundError(round));
+    }
+    let actual = Pairings::new(games.clone(), byes.first().copied());
+
+    // Compare the round to the engine's pairing, bracket by bracket.
+    let difference = brackets(&engine, &actual)
+        .iter()
+        .zip(brackets(&engine, &expected))
+        .find(|(actual, expected)| actual.compare(expected).is_ne())
+        .and_then(|(actual, expected)| actual.worse_criterion(&expected))
+        .map_or(Criterion::Sequence, Criterion::Quality);
+
+    let mut discrepancies = vec![];
+    let mut report = |player: u16, criterion: Criterion, actual: Option<Assignment>| {
+        discrepancies.push(Discrepancy {
+            player,
+            criterion,
+            actual,
+            expected: assignment(&expected, player),
+        });
+    };
+
+    for game in &games {
+        let (Some(white), Some(black)) =
+            (engine.player(game.white()), engine.player(game.black()))
+        else {
+            continue;
+        };
+        let expected_game = expected.game(game.white());
+
+        let criterion = check_game(white, black).or_else(|| {
+            if expected_game == Some(game) {
+                None
+            } else if expected_game.is_some_and(|i| i.contains(game.black())) {
+                Some(Criterion::ColorAllocation)
+            } else {
+                Some(difference)
+            }
+        });
+
+        if let Some(criterion) = criterion {
+            report(game.white(), criterion, Some(Assignment::Game(*game)));
+        }
+    }
+
+    for bye in &byes {
+        let Some(player) = engine.player(*bye) else {
+            continue;
+        };
+
+        if !Dutch::bye_eligible(player) {
+            report(*bye, Criterion::RepeatedBye, Some(Assignment::Bye(*bye)));
+        } else if expected.bye() != Some(*bye) {
+            report(*bye, difference, Some(Assignment::Bye(*bye)));
+        }
+    }
+
+    for player in &engine.players {
+        let id = player.history.id;
+        if !byes.contains(&id) && !games.iter().any(|game| game.contains(id)) {
+            report(id, Criterion::Unpaired, None);
+        }
+    }
+
+    Ok(discrepancies)
+}
@@ -0,0 +1,509 @@
+//! # Dubov system
+//!
+//! This module implements the Dubov system, C.04.4.1 of the FIDE Handbook.
+//!
+//! ## Status
+//!
+//! Players are ranked by score, then by rating (`fide_rating`, unrated players last),
+//! then by starting rank number. Score groups are paired from the top:
+//! - A score group with an odd number of players receives an upfloater from the lower
+//!   score groups: the player with the highest score, then who has the colour preference
+//!   the group lacks, then who upfloated the fewest times, then with the highest rating.
+//!   A player who upfloated in the previous round only upfloats again if no other player
+//!   with the same score can.
+//!   In the last score group, the lowest ranked player who can receive it gets the
+//!   pairing-allocated bye instead.
+//! - The players of the score group are split between white seekers and black seekers
+//!   by colour preference. Players without a preference complete the smaller half, the
+//!   highest ranked ones as white seekers. If the halves are still unbalanced, the
+//!   players of the larger half with the mildest preference, then the lowest ranked,
+//!   change sides.
+//! - White seekers are sorted by ascending ARO (average rating of the opponents, see
+//!   [`Tiebreak::AverageRatingOfOpponents`]) then by descending rating, black seekers by
+//!   descending rating then by ascending ARO. The first white seeker meets the first
+//!   black seeker, the second the second, and so on. When two players can't meet, the
+//!   black seekers are transposed.
+//!
+//! The absolute criteria and the colour allocation are those of the Dutch system (see
+//! [`super::dutch`]), without topscorers. If a score group can't be paired this way
+//! (including when the transpositions exceed the search limit), it is paired with a
+//! maximum matching, which satisfies the absolute criteria but not the order above, and
+//! the pairings are marked as approximate (see [`super::Pairings::approximate`]).
+//!
+//! In the first round, every player is in the same score group without a colour
+//! preference: the first half of the players meets the second half, in rating order.
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::dubov, Situation};
+//!
+//! let file_contents = fs::read_to_string(
+//!     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+//! )
+//! .unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! let pairings = dubov::pair(&situation, 6).unwrap();
+//!
+//! assert_eq!(pairings.games().len(), 4);
+//! assert!(pairings.bye().is_some());
+//! ```
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{
+    tiebreak::{Tiebreak, Tiebreaks},
+    trf::player::round::Color,
+    Situation,
+};
+
+use super::{
+    dutch::{Dutch, Pair, Player, CANDIDATE_LIMIT},
+    history::{histories, Float, PlayerHistory},
+    PairingError, Pairings,
+};
+
+/// The pairing of a score group.
+struct Bracket<'a> {
+    /// The pairs.
+    pairs: Vec<Pair<'a>>,
+
+    /// The player who receives the pairing-allocated bye, in the last score group.
+    bye: Option<&'a Player>,
+
+    /// The players of the lower score groups left to pair.
+    lower: Vec<&'a Player>,
+}
+
+/// The pairing engine state for a round.
+struct Dubov {
+    /// The Dutch engine, for the absolute criteria and the colour allocation.
+    engine: Dutch,
+
+    /// Rating of each player, by starting rank number.
+    ratings: HashMap<u16, u16>,
+
+    /// Average rating of the opponents of each player, by starting rank number.
+    aros: HashMap<u16, f32>,
+}
+
+impl Dubov {
+    /// Create the engine for a round, with the players who aren't absent.
+    fn new(situation: &Situation, round: u16) -> Result<Self, PairingError> {
+        let ratings: HashMap<u16, u16> = situation
+            .players()
+            .iter()
+            .filter_map(|player| {
+                Some((
+                    *player.starting_rank_number().ok()?,
+                    (*player.fide_rating().ok()?)?,
+                ))
+            })
+            .collect();
+        let rating = |id: u16| ratings.get(&id).copied().unwrap_or_default();
+
+        let mut histories: Vec<PlayerHistory> = histories(situation, round)?
+            .into_iter()
+            .filter(|history| !history.absent)
+            .collect();
+        histories.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| rating(b.id).cmp(&rating(a.id)))
+                .then(a.id.cmp(&b.id))
+        });
+
+        let tiebreaks = Tiebreaks::before(situation, round);
+        let aros = histories
+            .iter()
+            .map(|history| {
+                let aro =
+                    tiebreaks.value(Tiebreak::AverageRatingOfOpponents, history.id, &[]);
+                (history.id, aro)
+            })
+            .collect();
+
+        Ok(Self {
+            engine: Dutch::ranked(situation, histories, false, |_| false),
+            ratings,
+            aros,
+        })
+    }
+
+    /// Get the rating of a player, 0 if unrated.
+    fn rating(&self, player: &Player) -> u16 {
+        self.ratings
+            .get(&player.history.id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the average rating of the opponents of a player, 0 before the first game.
+    fn aro(&self, player: &Player) -> f32 {
+        self.aros
+            .get(&player.history.id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Split an even score group between white seekers and black seekers, sorted in
+    /// pairing order.
+    fn split<'a>(&self, group: &[&'a Player]) -> (Vec<&'a Player>, Vec<&'a Player>) {
+        let half = group.len() / 2;
+        let seeking = |color: Option<Color>| -> Vec<&'a Player> {
+            group
+                .iter()
+                .filter(|player| player.preference.map(|i| i.color) == color)
+                .copied()
+                .collect()
+        };
+        let mut white = seeking(Some(Color::White));
+        let mut black = seeking(Some(Color::Black));
+
+        // Players without a preference complete the smaller half.
+        let neutral = seeking(None);
+        let (first, second) =
+            neutral.split_at(half.saturating_sub(white.len()).min(neutral.len()));
+        white.extend(first);
+        black.extend(second);
+
+        // Then players with the mildest preference change sides.
+        let balance = |from: &mut Vec<&'a Player>, to: &mut Vec<&'a Player>| {
+            from.sort_by(|a, b| {
+                a.preference
+                    .map(|i| i.strength)
+                    .cmp(&b.preference.map(|i| i.strength))
+                    .then(b.rank.cmp(&a.rank))
+            });
+            let moved = from.len().saturating_sub(half);
+            to.extend(from.drain(..moved));
+        };
+        balance(&mut white, &mut black);
+        balance(&mut black, &mut white);
+
+        white.sort_by(|a, b| {
+            self.aro(a)
+                .total_cmp(&self.aro(b))
+                .then_with(|| self.rating(b).cmp(&self.rating(a)))
+                .then(a.rank.cmp(&b.rank))
+        });
+        black.sort_by(|a, b| {
+            self.rating(b)
+                .cmp(&self.rating(a))
+                .then_with(|| self.aro(a).total_cmp(&self.aro(b)))
+                .then(a.rank.cmp(&b.rank))
+        });
+
+        (white, black)
+    }
+
+    /// Pair each white seeker with a black seeker, trying transpositions of the black
+    /// seekers in lexicographic order.
+    ///
+    /// Returns whether every white seeker was paired.
+    fn transpositions<'a>(
+        white: &[&'a Player],
+        black: &[&'a Player],
+        pairs: &mut Vec<Pair<'a>>,
+        evaluated: &mut usize,
+    ) -> bool {
+        let Some((first, white)) = white.split_first() else {
+            return true;
+        };
+
+        for (index, opponent) in black.iter().enumerate() {
+            if *evaluated >= CANDIDATE_LIMIT {
+                return false;
+            }
+            *evaluated += 1;
+            if !Dutch::compatible(first, opponent) {
+                continue;
+            }
+
+            let mut rest = black.to_vec();
+            rest.remove(index);
+            pairs.push(if first.rank < opponent.rank {
+                (first, opponent)
+            } else {
+                (opponent, first)
+            });
+            if Self::transpositions(white, &rest, pairs, evaluated) {
+                return true;
+            }
+            pairs.pop();
+        }
+
+        false
+    }
+
+    /// Pair every player of an even score group, returns [`None`] if it isn't possible.
+    ///
+    /// If `exact` is false and the transpositions fail, the score group is paired with a
+    /// maximum matching instead, regardless of the pairing order.
+    fn pair_group<'a>(&self, group: &[&'a Player], exact: bool) -> Option<Vec<Pair<'a>>> {
+        let (white, black) = self.split(group);
+
+        let mut pairs = vec![];
+        if Self::transpositions(&white, &black, &mut pairs, &mut 0) {
+            return Some(pairs);
+        }
+        if exact {
+            return None;
+        }
+
+        // The colour preferences can't all be met, pair the score group anyway.
+        let (pairs, unpaired) = Dutch::matching(group);
+        unpaired.is_empty().then_some(pairs)
+    }
+
+    /// Get the players who may upfloat to an odd score group, in order of priority.
+    fn upfloaters<'a>(&self, group: &[&Player], lower: &[&'a Player]) -> Vec<&'a Player> {
+        let seeking = |color: Color| {
+            group
+                .iter()
+                .filter(|player| player.preference.is_some_and(|i| i.color == color))
+                .count()
+        };
+        let lacking = match seeking(Color::White).cmp(&seeking(Color::Black)) {
+            Ordering::Greater => Some(Color::Black),
+            Ordering::Less => Some(Color::White),
+            Ordering::Equal => None,
+        };
+        let fits = |player: &Player| {
+            lacking
+                .is_some_and(|color| player.preference.is_some_and(|i| i.color == color))
+        };
+        let repeated = |player: &Player| player.history.float(1) == Float::Up;
+        let upfloats = |player: &Player| {
+            player
+                .history
+                .floats
+                .iter()
+                .filter(|float| **float == Float::Up)
+                .count()
+        };
+
+        let mut candidates = lower.to_vec();
+        candidates.sort_by(|a, b| {
+            b.history
+                .score
+                .total_cmp(&a.history.score)
+                .then_with(|| repeated(a).cmp(&repeated(b)))
+                .then_with(|| fits(b).cmp(&fits(a)))
+                .then_with(|| upfloats(a).cmp(&upfloats(b)))
+                .then_with(|| self.rating(b).cmp(&self.rating(a)))
+                .then(a.rank.cmp(&b.rank))
+        });
+
+        candidates
+    }
+
+    /// Pair a score group, with an upfloater or the bye if it is odd.
+    ///
+    /// Returns [`None`] if the score group can't be paired while the lower score groups
+    /// remain pairable. See [`Dubov::pair_group`] for `exact`.
+    fn bracket<'a>(
+        &self,
+        group: &[&'a Player],
+        lower: &[&'a Player],
+        exact: bool,
+    ) -> Option<Bracket<'a>> {
+        let without = |players: &[&'a Player], removed: &Player| -> Vec<&'a Player> {
+            players
+                .iter()
+                .filter(|player| player.rank != removed.rank)
+                .copied()
+                .collect()
+        };
+
+        let odd = group.len() % 2 == 1;
+        if !odd {
+            if !Dutch::pairable(lower) {
+                return None;
+            }
+            return self.pair_group(group, exact).map(|pairs| Bracket {
+                pairs,
+                bye: None,
+                lower: lower.to_vec(),
+            });
+        }
+
+        if lower.is_empty() {
+            return group
+                .iter()
+                .rev()
+                .filter(|player| Dutch::bye_eligible(player))
+                .find_map(|bye| {
+                    self.pair_group(&without(group, bye), exact)
+                        .map(|pairs| Bracket {
+                            pairs,
+                            bye: Some(bye),
+                            lower: vec![],
+                        })
+                });
+        }
+
+        self.upfloaters(group, lower)
+            .into_iter()
+            .find_map(|upfloater| {
+                let lower = without(lower, upfloater);
+                if !Dutch::pairable(&lower) {
+                    return None;
+                }
+                let group: Vec<&Player> =
+                    group.iter().copied().chain([upfloater]).collect();
+
+                self.pair_group(&group, exact).map(|pairs| Bracket {
+                    pairs,
+                    bye: None,
+                    lower,
+                })
+            })
+    }
+}
+
+/// Pair a round of a tournament with the Dubov system.
+///
+/// Players are paired based on the rounds before `round`. Players who already have a
+/// bye (other than a pairing-allocated bye) in `round` are considered absent.
+///
+/// In the first round, the lowest rated player receives the pairing-allocated bye and
+/// the first half of the other players meets the second half:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dubov, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             0.0
+/// 001    2      Bravo, Ben                        1900                             0.0
+/// 001    3      Charlie, Cleo                     1800                             0.0
+/// 001    4      Delta, Dan                        1700                             0.0
+/// 001    5      Echo, Eve                         1600                             0.0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dubov::pair(&situation, 1).unwrap();
+/// assert_eq!(pairings.games(), [Game::new(1, 3), Game::new(4, 2)]);
+/// assert_eq!(pairings.bye(), Some(5));
+/// ```
+///
+/// The white seekers of a score group are taken by ascending ARO: player 4, whose
+/// opponent was the lowest rated, meets the highest rated black seeker:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dubov, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             1.0          5 w 1
+/// 001    2      Bravo, Ben                        1900                             1.0          6 b 1
+/// 001    3      Charlie, Cleo                     1800                             1.0          7 w 1
+/// 001    4      Delta, Dan                        1700                             1.0          8 b 1
+/// 001    5      Echo, Eve                         1600                             0.0          1 b 0
+/// 001    6      Foxtrot, Fay                      1500                             0.0          2 w 0
+/// 001    7      Golf, Gus                         1400                             0.0          3 b 0
+/// 001    8      Hotel, Hal                        1300                             0.0          4 w 0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dubov::pair(&situation, 2).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [
+///         Game::new(4, 1),
+///         Game::new(2, 3),
+///         Game::new(5, 8),
+///         Game::new(7, 6),
+///     ]
+/// );
+/// assert!(!pairings.approximate());
+/// ```
+///
+/// The score group on 1 point lacks a white seeker, so player 5 upfloats rather than the
+/// higher rated player 4, who is due black:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dubov, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             1.0          4 b 1
+/// 001    2      Bravo, Ben                        1900                             1.0          5 w 1
+/// 001    3      Charlie, Cleo                     1800                             1.0          6 w 1
+/// 001    4      Delta, Dan                        1700                             0.0          1 w 0
+/// 001    5      Echo, Eve                         1600                             0.0          2 b 0
+/// 001    6      Foxtrot, Fay                      1500                             0.0          3 b 0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dubov::pair(&situation, 2).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(1, 2), Game::new(5, 3), Game::new(6, 4)]
+/// );
+/// ```
+///
+/// Player 2 upfloated in round 2, so player 5 upfloats to player 7 instead:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dubov, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2400                             1.0          4 w 1     2 b 0
+/// 001    2      Bravo, Ben                        2390                             1.5          5 b =     1 w 1
+/// 001    3      Charlie, Cleo                     2380                             1.0          6 w 0       - U
+/// 001    4      Delta, Dan                        2370                             0.0          1 b 0     5 w 0
+/// 001    5      Echo, Eve                         2360                             1.5          2 w =     4 b 1
+/// 001    6      Foxtrot, Fay                      2350                             1.0          3 b 1     7 w 0
+/// 001    7      Golf, Gus                         2340                             2.0            - U     6 b 1";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dubov::pair(&situation, 3).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(7, 5), Game::new(2, 3), Game::new(1, 6)]
+/// );
+/// assert_eq!(pairings.bye(), Some(4));
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the round is invalid, if a player's starting rank number is
+/// invalid, or if no pairing satisfies the absolute criteria.
+pub fn pair(situation: &Situation, round: u16) -> Result<Pairings, PairingError> {
+    let dubov = Dubov::new(situation, round)?;
+
+    let mut remaining: Vec<&Player> = dubov.engine.players.iter().collect();
+    if !Dutch::pairable(&remaining) {
+        return Err(PairingError::NoValidPairingError());
+    }
+
+    let mut pairs: Vec<Pair<'_>> = vec![];
+    let mut bye: Option<&Player> = None;
+    let mut approximate = false;
+
+    while let Some(first) = remaining.first() {
+        let size = remaining
+            .iter()
+            .take_while(|i| i.history.score.total_cmp(&first.history.score).is_eq())
+            .count();
+        let (group, lower) = remaining.split_at(size);
+
+        // Follow the pairing order if possible, otherwise only the absolute criteria.
+        let bracket = dubov.bracket(group, lower, true).or_else(|| {
+            approximate = true;
+            dubov.bracket(group, lower, false)
+        });
+        if let Some(bracket) = bracket {
+            pairs.extend(bracket.pairs);
+            bye = bye.or(bracket.bye);
+            remaining = bracket.lower;
+            continue;
+        }
+
+        let (rest, unpaired) = Dutch::matching(&remaining);
+        pairs.extend(rest);
+        match unpaired.as_slice() {
+            [] => {}
+            [player] if Dutch::bye_eligible(player) => bye = Some(player),
+            _ => return Err(PairingError::NoValidPairingError()),
+        }
+        break;
+    }
+
+    let mut pairings = dubov.engine.games(pairs, bye);
+    pairings.set_approximate(approximate);
+    Ok(pairings)
+}
@@ -0,0 +1,1262 @@
+//! # FIDE Dutch system
+//!
+//! This module implements the FIDE (Dutch) system, C.04.3 of the FIDE Handbook:
+//! <https://handbook.fide.com/chapter/C0403Till2025>
+//!
+//! ## Status
+//!
+//! Players are grouped in scoregroups and brackets are paired from the top. Each bracket
+//! is paired by trying transpositions of S2, then exchanges between S1 and S2 (and
+//! between S1 and the limbo in heterogeneous brackets), in the order defined by the
+//! rules. The first candidate satisfying every criterion is used, otherwise the best
+//! candidate found is used.
+//!
+//! The following criteria are implemented:
+//! - Absolute criteria: C1 (no rematch), C2 (no second pairing-allocated bye) and C3
+//!   (non-topscorers with the same absolute colour preference don't meet).
+//! - Completion criterion: C4, the remaining players can always be paired.
+//! - Quality criteria: C5 to C7 and C9 to C21. C7 counts the pairs of the next bracket
+//!   with a maximum matching. C8 (minimise the score differences in the next bracket)
+//!   isn't implemented.
+//!
+//! Colours are allocated with the rules of section E. The initial colour is read from
+//! the `JaVaFo` configuration (XXC), white by default.
+//!
+//! Since the search is exponential in the worst case, at most [`CANDIDATE_LIMIT`]
+//! candidates are evaluated in each bracket, and exchanges of many players are left out
+//! of large brackets. When that happens before a candidate satisfying every criterion is
+//! found, the best candidate found is used and the pairings are marked as approximate
+//! (see [`Pairings::approximate`]).
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::dutch, Situation};
+//!
+//! let file_contents = fs::read_to_string(
+//!     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+//! )
+//! .unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! let pairings = dutch::pair(&situation, 6).unwrap();
+//!
+//! assert_eq!(pairings.games().len(), 4);
+//! assert!(pairings.bye().is_some());
+//! assert!(!pairings.approximate());
+//! ```
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{trf::player::round::Color, Situation};
+
+use super::{
+    history::{histories, opposite, win, Float, PlayerHistory, Preference, Strength},
+    matching::maximum_matching,
+    Game, PairingError, Pairings,
+};
+
+/// Maximum number of candidates evaluated in a single bracket.
+pub const CANDIDATE_LIMIT: usize = 20_000;
+
+/// Maximum number of exchanges generated for a given number of exchanged players.
+const EXCHANGE_LIMIT: usize = 10_000;
+
+/// A player taking part in the round.
+#[derive(Debug)]
+pub(crate) struct Player {
+    /// What happened in the previous rounds.
+    pub history: PlayerHistory,
+
+    /// Position in the pairing order (score, then pairing number), 0 is the first.
+    pub rank: usize,
+
+    /// Colour preference.
+    pub preference: Option<Preference>,
+
+    /// Whether the player is a topscorer (see C.04.3 A.7).
+    pub topscorer: bool,
+}
+
+impl Player {
+    /// Whether the player has an absolute colour preference.
+    pub(crate) fn absolute(&self) -> Option<Color> {
+        self.preference
+            .filter(|preference| preference.strength == Strength::Absolute)
+            .map(|preference| preference.color)
+    }
+}
+
+/// A pair of players, the higher ranked player first.
+pub(crate) type Pair<'a> = (&'a Player, &'a Player);
+
+/// The S1 and S2 subgroups of a bracket.
+type Subgroups<'a> = (Vec<&'a Player>, Vec<&'a Player>);
+
+/// What to do once the players of S1 are paired, given the pairs and the players left.
+type Continuation<'a, 'b, 'c> =
+    dyn FnMut(&mut Search<'a, 'b>, &mut Vec<Pair<'a>>, Vec<&'a Player>) -> bool + 'c;
+
+/// Quality of a candidate, see the quality criteria C5 to C21.
+///
+/// C8 (score differences in the next bracket) isn't evaluated. Counts are the number of
+/// players breaking each criterion, so lower is better.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Quality {
+    /// C5: number of pairs (higher is better).
+    pairs: usize,
+
+    /// C6: pairing score differences, in descending order.
+    score_differences: Vec<f32>,
+
+    /// C7: number of pairs in the next bracket (higher is better).
+    next_pairs: usize,
+
+    /// C9: number of unplayed games of the player receiving the bye.
+    bye_unplayed_rounds: usize,
+
+    /// C10: topscorers or opponents getting a colour difference over 2.
+    topscorer_color_difference: usize,
+
+    /// C11: topscorers or opponents getting the same colour three times in a row.
+    topscorer_same_color: usize,
+
+    /// C12: players not getting their colour preference.
+    color_preference: usize,
+
+    /// C13: players not getting their strong colour preference.
+    strong_color_preference: usize,
+
+    /// C14: players getting the same downfloat as in the previous round.
+    downfloat: usize,
+
+    /// C15: players getting the same upfloat as in the previous round.
+    upfloat: usize,
+
+    /// C16: players getting the same downfloat as two rounds before.
+    downfloat_two_rounds: usize,
+
+    /// C17: players getting the same upfloat as two rounds before.
+    upfloat_two_rounds: usize,
+
+    /// C18: score differences of the players counted by C14, in descending order.
+    downfloat_differences: Vec<f32>,
+
+    /// C19: score differences of the players counted by C15, in descending order.
+    upfloat_differences: Vec<f32>,
+
+    /// C20: score differences of the players counted by C16, in descending order.
+    downfloat_two_rounds_differences: Vec<f32>,
+
+    /// C21: score differences of the players counted by C17, in descending order.
+    upfloat_two_rounds_differences: Vec<f32>,
+}
+
+impl Quality {
+    /// Compare `self` to `other` on each criterion, in order of priority.
+    ///
+    /// Returns the number of each criterion (e.g. 12 for C12) along with the comparison,
+    /// [`Ordering::Less`] is better.
+    fn criteria(&self, other: &Self) -> Vec<(u8, Ordering)> {
+        let mut criteria = vec![
+            (5, other.pairs.cmp(&self.pairs)),
+            (
+                6,
+                compare_differences(&self.score_differences, &other.score_differences),
+            ),
+            (7, other.next_pairs.cmp(&self.next_pairs)),
+        ];
+        criteria.extend(
+            (9..)
+                .zip(self.counts().iter().zip(other.counts()))
+                .map(|(criterion, (a, b))| (criterion, a.cmp(&b))),
+        );
+        criteria.extend(
+            (18..)
+                .zip(
+                    self.float_differences()
+                        .iter()
+                        .zip(other.float_differences()),
+                )
+                .map(|(criterion, (a, b))| (criterion, compare_differences(a, b))),
+        );
+
+        criteria
+    }
+
+    /// Compare two qualities, [`Ordering::Less`] is better.
+    pub(crate) fn compare(&self, other: &Self) -> Ordering {
+        self.criteria(other)
+            .into_iter()
+            .map(|(_, ordering)| ordering)
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Get the first quality criterion on which `self` and `other` differ, if `self` is
+    /// worse on it.
+    ///
+    /// Returns the number of the criterion (e.g. 12 for C12).
+    pub(crate) fn worse_criterion(&self, other: &Self) -> Option<u8> {
+        self.criteria(other)
+            .into_iter()
+            .find(|(_, ordering)| ordering.is_ne())
+            .filter(|(_, ordering)| ordering.is_gt())
+            .map(|(criterion, _)| criterion)
+    }
+
+    /// Counts of the criteria C9 to C17, in order of priority.
+    const fn counts(&self) -> [usize; 9] {
+        [
+            self.bye_unplayed_rounds,
+            self.topscorer_color_difference,
+            self.topscorer_same_color,
+            self.color_preference,
+            self.strong_color_preference,
+            self.downfloat,
+            self.upfloat,
+            self.downfloat_two_rounds,
+            self.upfloat_two_rounds,
+        ]
+    }
+
+    /// Score differences of the criteria C18 to C21, in order of priority.
+    const fn float_differences(&self) -> [&Vec<f32>; 4] {
+        [
+            &self.downfloat_differences,
+            &self.upfloat_differences,
+            &self.downfloat_two_rounds_differences,
+            &self.upfloat_two_rounds_differences,
+        ]
+    }
+
+    /// Count a pair or a floater for the criteria C14 to C21.
+    ///
+    /// `repeated` tells whether the players get the same float as before, in the order
+    /// of the criteria C14 to C17, `difference` is the score difference.
+    fn repeated_floats(&mut self, repeated: [bool; 4], difference: f32) {
+        let counts = [
+            &mut self.downfloat,
+            &mut self.upfloat,
+            &mut self.downfloat_two_rounds,
+            &mut self.upfloat_two_rounds,
+        ];
+        let differences = [
+            &mut self.downfloat_differences,
+            &mut self.upfloat_differences,
+            &mut self.downfloat_two_rounds_differences,
+            &mut self.upfloat_two_rounds_differences,
+        ];
+
+        for ((repeated, count), differences) in
+            repeated.into_iter().zip(counts).zip(differences)
+        {
+            if repeated {
+                *count += 1;
+                differences.push(difference);
+            }
+        }
+    }
+}
+
+/// Compare two lists of score differences in descending order, [`Ordering::Less`] is
+/// better. A missing difference counts as zero.
+fn compare_differences(a: &[f32], b: &[f32]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            let difference = |list: &[f32]| list.get(i).copied().unwrap_or_default();
+            difference(a).total_cmp(&difference(b))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// A candidate pairing of a bracket.
+#[derive(Debug, Clone)]
+struct Candidate<'a> {
+    /// The pairs.
+    pairs: Vec<Pair<'a>>,
+
+    /// The players moved down to the next bracket (or the bye in the last bracket).
+    floaters: Vec<&'a Player>,
+
+    /// The quality of the candidate.
+    quality: Quality,
+}
+
+/// The pairing engine state for a round.
+pub(crate) struct Dutch {
+    /// Players taking part in the round, in pairing order.
+    pub players: Vec<Player>,
+
+    /// The colour given to the higher ranked player of the first board in round 1, see
+    /// [`crate::trf::extension::Configuration::initial_color`].
+    initial_color: Color,
+
+    /// Whether S2 is paired in reverse order, the first player of S1 meeting the last
+    /// player of S2 (see [`super::burstein`]).
+    reversed: bool,
+}
+
+impl Dutch {
+    /// Create the engine for a round, with the players who aren't absent.
+    pub(crate) fn new(situation: &Situation, round: u16) -> Result<Self, PairingError> {
+        let mut histories: Vec<PlayerHistory> = histories(situation, round)?
+            .into_iter()
+            .filter(|history| !history.absent)
+            .collect();
+        histories.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+
+        // Topscorers only exist when pairing the last round (see A.7).
+        let last_round = situation.number_of_rounds() == Some(round);
+        let max_score = f32::from(round - 1) * win(situation);
+
+        Ok(Self::ranked(situation, histories, false, |history| {
+            last_round && history.score > max_score / 2.0
+        }))
+    }
+
+    /// Create the engine from the histories of the players taking part in the round, in
+    /// pairing order.
+    ///
+    /// `reversed` pairs S2 in reverse order, `topscorer` tells whether a player is a
+    /// topscorer.
+    pub(crate) fn ranked(
+        situation: &Situation,
+        histories: Vec<PlayerHistory>,
+        reversed: bool,
+        topscorer: impl Fn(&PlayerHistory) -> bool,
+    ) -> Self {
+        Self {
+            players: histories
+                .into_iter()
+                .enumerate()
+                .map(|(rank, history)| Player {
+                    rank,
+                    preference: history.preference(),
+                    topscorer: topscorer(&history),
+                    history,
+                })
+                .collect(),
+            initial_color: situation
+                .extensions()
+                .initial_color()
+                .unwrap_or(Color::White),
+            reversed,
+        }
+    }
+
+    /// Get S2 in the order its players are tried against S1.
+    fn order<'a>(&self, s2: &[&'a Player]) -> Vec<&'a Player> {
+        let mut s2 = s2.to_vec();
+        if self.reversed {
+            s2.reverse();
+        }
+
+        s2
+    }
+
+    /// Get a player taking part in the round by starting rank number.
+    pub(crate) fn player(&self, id: u16) -> Option<&Player> {
+        self.players.iter().find(|player| player.history.id == id)
+    }
+
+    /// Whether two players can be paired together (absolute criteria C1 and C3).
+    ///
+    /// Forbidden pairs are treated like rematches.
+    pub(crate) fn compatible(a: &Player, b: &Player) -> bool {
+        a.rank != b.rank
+            && !a.history.played(b.history.id)
+            && !a.history.forbids(b.history.id)
+            && (a.topscorer
+                || b.topscorer
+                || a.absolute().is_none()
+                || a.absolute() != b.absolute())
+    }
+
+    /// Whether a player can receive the pairing-allocated bye (absolute criterion C2).
+    pub(crate) const fn bye_eligible(player: &Player) -> bool {
+        !player.history.had_bye
+    }
+
+    /// Pair as many players as possible, with at most one bye if there is an odd number
+    /// of players.
+    ///
+    /// Returns the pairs and the unpaired players.
+    pub(crate) fn matching<'a>(
+        players: &[&'a Player],
+    ) -> (Vec<Pair<'a>>, Vec<&'a Player>) {
+        let odd = players.len() % 2 == 1;
+        let edge = |i: usize, j: usize| match (players.get(i), players.get(j)) {
+            (Some(a), Some(b)) => Self::compatible(a, b),
+            (Some(a), None) | (None, Some(a)) => odd && Self::bye_eligible(a),
+            (None, None) => false,
+        };
+        let mate = maximum_matching(players.len() + usize::from(odd), &edge);
+
+        let mut pairs = vec![];
+        let mut unpaired = vec![];
+        for (i, a) in players.iter().enumerate() {
+            match mate.get(i).copied().flatten().and_then(|j| players.get(j)) {
+                Some(b) if a.rank < b.rank => pairs.push((*a, *b)),
+                Some(_) => {}
+                None => unpaired.push(*a),
+            }
+        }
+
+        (pairs, unpaired)
+    }
+
+    /// Whether every player can be paired, with at most one bye (completion criterion).
+    pub(crate) fn pairable(players: &[&Player]) -> bool {
+        let (_, unpaired) = Self::matching(players);
+
+        match unpaired.as_slice() {
+            [] => true,
+            [bye] => players.len() % 2 == 1 && Self::bye_eligible(bye),
+            _ => false,
+        }
+    }
+
+    /// Allocate colours to a pair (rules E.1 to E.5), returns the white player first.
+    pub(crate) fn allocate<'a>(&self, (a, b): Pair<'a>) -> Pair<'a> {
+        let a_white = match (a.preference, b.preference) {
+            // E.5
+            (None, None) => {
+                (a.history.id % 2 == 1) == (self.initial_color == Color::White)
+            }
+            // E.1
+            (Some(p), None) => p.color == Color::White,
+            (None, Some(q)) => q.color == Color::Black,
+            (Some(p), Some(q)) if p.color != q.color => p.color == Color::White,
+            // E.2
+            (Some(p), Some(q)) if p.strength != q.strength => {
+                (p.strength > q.strength) == (p.color == Color::White)
+            }
+            (Some(p), Some(_))
+                if p.strength == Strength::Absolute
+                    && a.history.color_difference().abs()
+                        != b.history.color_difference().abs() =>
+            {
+                (a.history.color_difference().abs() > b.history.color_difference().abs())
+                    == (p.color == Color::White)
+            }
+            // E.3, then E.4
+            (Some(p), Some(_)) => a
+                .history
+                .colors
+                .iter()
+                .rev()
+                .zip(b.history.colors.iter().rev())
+                .find(|(x, y)| x != y)
+                .and_then(|(x, _)| opposite(*x))
+                .map_or(p.color == Color::White, |color| color == Color::White),
+        };
+
+        if a_white {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Number of pairs in the next bracket (criterion C7): the floaters of a bracket
+    /// along with `next`, the residents of the next score group.
+    pub(crate) fn next_pairs(floaters: &[&Player], next: &[&Player]) -> usize {
+        let players: Vec<&Player> = floaters.iter().chain(next).copied().collect();
+        let (pairs, _) = Self::matching(&players);
+
+        pairs.len()
+    }
+
+    /// Evaluate a candidate.
+    ///
+    /// `lowest_score` is the score of the residents of the bracket, used for the score
+    /// differences of floaters. `next_pairs` is the number of pairs in the next bracket
+    /// (see [`Dutch::next_pairs`]), ignored in the last bracket.
+    pub(crate) fn evaluate(
+        &self,
+        pairs: &[Pair<'_>],
+        floaters: &[&Player],
+        lowest_score: f32,
+        next_pairs: usize,
+        last: bool,
+    ) -> Quality {
+        let mut quality = Quality {
+            pairs: pairs.len(),
+            score_differences: vec![],
+            next_pairs: if last { 0 } else { next_pairs },
+            bye_unplayed_rounds: 0,
+            topscorer_color_difference: 0,
+            topscorer_same_color: 0,
+            color_preference: 0,
+            strong_color_preference: 0,
+            downfloat: 0,
+            upfloat: 0,
+            downfloat_two_rounds: 0,
+            upfloat_two_rounds: 0,
+            downfloat_differences: vec![],
+            upfloat_differences: vec![],
+            downfloat_two_rounds_differences: vec![],
+            upfloat_two_rounds_differences: vec![],
+        };
+
+        for &(a, b) in pairs {
+            let difference = (a.history.score - b.history.score).abs();
+            quality.score_differences.push(difference);
+
+            let (white, black) = self.allocate((a, b));
+            for (player, color) in [(white, Color::White), (black, Color::Black)] {
+                if let Some(preference) = player.preference.filter(|i| i.color != color) {
+                    quality.color_preference += 1;
+                    if preference.strength >= Strength::Strong {
+                        quality.strong_color_preference += 1;
+                    }
+                }
+
+                if a.topscorer || b.topscorer {
+                    let difference = player.history.color_difference()
+                        + if color == Color::White { 1 } else { -1 };
+                    if difference.abs() > 2 {
+                        quality.topscorer_color_difference += 1;
+                    }
+                    if player
+                        .history
+                        .colors
+                        .iter()
+                        .rev()
+                        .take(2)
+                        .filter(|i| **i == color)
+                        .count()
+                        == 2
+                    {
+                        quality.topscorer_same_color += 1;
+                    }
+                }
+            }
+
+            if a.history.score > b.history.score {
+                quality.repeated_floats(
+                    [
+                        a.history.float(1) == Float::Down,
+                        b.history.float(1) == Float::Up,
+                        a.history.float(2) == Float::Down,
+                        b.history.float(2) == Float::Up,
+                    ],
+                    difference,
+                );
+            }
+        }
+
+        for floater in floaters {
+            let difference = floater.history.score - (lowest_score - 1.0);
+            quality.score_differences.push(difference);
+            quality.repeated_floats(
+                [
+                    floater.history.float(1) == Float::Down,
+                    false,
+                    floater.history.float(2) == Float::Down,
+                    false,
+                ],
+                difference,
+            );
+        }
+
+        if last {
+            quality.bye_unplayed_rounds = floaters
+                .iter()
+                .map(|floater| floater.history.unplayed_rounds)
+                .sum();
+        }
+
+        for differences in [
+            &mut quality.score_differences,
+            &mut quality.downfloat_differences,
+            &mut quality.upfloat_differences,
+            &mut quality.downfloat_two_rounds_differences,
+            &mut quality.upfloat_two_rounds_differences,
+        ] {
+            differences.sort_by(|a, b| b.total_cmp(a));
+        }
+
+        quality
+    }
+
+    /// Pair the round, bracket by bracket from the top.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no pairing satisfies the absolute criteria.
+    pub(crate) fn pairings(&self) -> Result<Pairings, PairingError> {
+        let players: Vec<&Player> = self.players.iter().collect();
+        if !Self::pairable(&players) {
+            return Err(PairingError::NoValidPairingError());
+        }
+
+        let mut pairs: Vec<Pair<'_>> = vec![];
+        let mut moved_down: Vec<&Player> = vec![];
+        let mut bye: Option<&Player> = None;
+        let mut remaining: &[&Player] = &players;
+        let mut approximate = false;
+
+        while let Some(first) = remaining.first() {
+            let (residents, lower) = remaining.split_at(score_group(remaining));
+            remaining = lower;
+
+            let bracket: Vec<&Player> =
+                moved_down.iter().chain(residents).copied().collect();
+            let mut search = Search {
+                engine: self,
+                lower,
+                next: lower.get(..score_group(lower)).unwrap_or_default(),
+                last: lower.is_empty(),
+                lowest_score: first.history.score,
+                ideal_pairs: bracket.len() / 2,
+                best: None,
+                evaluated: 0,
+                perfect: false,
+                cut: false,
+                completion: HashMap::new(),
+                next_pairs: HashMap::new(),
+            };
+
+            if moved_down.is_empty() {
+                search.homogeneous(&mut vec![], &[], residents);
+            } else {
+                search.heterogeneous(&moved_down, residents);
+            }
+
+            approximate |= search.truncated();
+            let (bracket_pairs, floaters) = match search.best {
+                Some(best) => (best.pairs, best.floaters),
+                None if search.last => {
+                    approximate = true;
+                    Self::matching(&bracket)
+                }
+                None => {
+                    approximate = true;
+                    (vec![], bracket)
+                }
+            };
+
+            pairs.extend(bracket_pairs);
+            if search.last {
+                match floaters.as_slice() {
+                    [] => {}
+                    [player] if Self::bye_eligible(player) => bye = Some(player),
+                    _ => return Err(PairingError::NoValidPairingError()),
+                }
+            }
+            moved_down = floaters;
+        }
+
+        let mut pairings = self.games(pairs, bye);
+        pairings.set_approximate(approximate);
+
+        Ok(pairings)
+    }
+
+    /// Sort the pairs in board order and allocate colours.
+    pub(crate) fn games(
+        &self,
+        mut pairs: Vec<Pair<'_>>,
+        bye: Option<&Player>,
+    ) -> Pairings {
+        // Board order: highest score, then sum of scores, then highest ranked player.
+        pairs.sort_by(|(a1, b1), (a2, b2)| {
+            a2.history
+                .score
+                .total_cmp(&a1.history.score)
+                .then_with(|| {
+                    (a2.history.score + b2.history.score)
+                        .total_cmp(&(a1.history.score + b1.history.score))
+                })
+                .then_with(|| a1.rank.cmp(&a2.rank))
+        });
+
+        Pairings::new(
+            pairs
+                .into_iter()
+                .map(|pair| {
+                    let (white, black) = self.allocate(pair);
+                    Game::new(white.history.id, black.history.id)
+                })
+                .collect(),
+            bye.map(|player| player.history.id),
+        )
+    }
+}
+
+/// The search for the best candidate of a bracket.
+struct Search<'a, 'b> {
+    /// The engine.
+    engine: &'b Dutch,
+
+    /// The players of the lower brackets.
+    lower: &'b [&'a Player],
+
+    /// The residents of the next bracket.
+    next: &'b [&'a Player],
+
+    /// Whether this is the last bracket.
+    last: bool,
+
+    /// Score of the residents of the bracket.
+    lowest_score: f32,
+
+    /// Number of pairs if every player of the bracket is paired.
+    ideal_pairs: usize,
+
+    /// Best candidate found so far.
+    best: Option<Candidate<'a>>,
+
+    /// Number of candidates evaluated.
+    evaluated: usize,
+
+    /// Whether the best candidate can't be improved.
+    perfect: bool,
+
+    /// Whether some exchanges weren't generated, see [`EXCHANGE_LIMIT`].
+    cut: bool,
+
+    /// Results of the completion criterion for each set of floaters.
+    completion: HashMap<Vec<usize>, bool>,
+
+    /// Number of pairs in the next bracket for each set of floaters.
+    next_pairs: HashMap<Vec<usize>, usize>,
+}
+
+impl<'a, 'b> Search<'a, 'b> {
+    /// Whether the search should stop.
+    const fn exhausted(&self) -> bool {
+        self.perfect || self.evaluated >= CANDIDATE_LIMIT
+    }
+
+    /// Whether the search was cut short before finding a candidate which can't be
+    /// improved, so that the best candidate found may not be the best one.
+    const fn truncated(&self) -> bool {
+        !self.perfect && (self.evaluated >= CANDIDATE_LIMIT || self.cut)
+    }
+
+    /// Whether a set of floaters satisfies the completion criterion.
+    fn complete(&mut self, floaters: &[&'a Player]) -> bool {
+        if self.last {
+            return match floaters {
+                [] => true,
+                [bye] => Dutch::bye_eligible(bye),
+                _ => false,
+            };
+        }
+
+        let key = ranks(floaters);
+        if let Some(result) = self.completion.get(&key) {
+            return *result;
+        }
+
+        let remaining: Vec<&Player> =
+            floaters.iter().chain(self.lower).copied().collect();
+        let result = Dutch::pairable(&remaining);
+        self.completion.insert(key, result);
+
+        result
+    }
+
+    /// Number of pairs in the next bracket with a set of floaters, see
+    /// [`Dutch::next_pairs`].
+    fn next_pairs(&mut self, floaters: &[&'a Player]) -> usize {
+        if self.last {
+            return 0;
+        }
+
+        *self
+            .next_pairs
+            .entry(ranks(floaters))
+            .or_insert_with(|| Dutch::next_pairs(floaters, self.next))
+    }
+
+    /// Evaluate a candidate, and keep it if it is the best so far.
+    ///
+    /// Returns whether the candidate is valid.
+    fn offer(&mut self, pairs: &[Pair<'a>], floaters: &[&'a Player]) -> bool {
+        self.evaluated += 1;
+
+        let next_pairs = self.next_pairs(floaters);
+        let quality = self.engine.evaluate(
+            pairs,
+            floaters,
+            self.lowest_score,
+            next_pairs,
+            self.last,
+        );
+        let better = self
+            .best
+            .as_ref()
+            .is_none_or(|best| quality.compare(&best.quality).is_lt());
+
+        if !better {
+            // The candidate might be valid, but it doesn't matter.
+            return true;
+        }
+        if !self.complete(floaters) {
+            return false;
+        }
+
+        self.perfect = quality.pairs == self.ideal_pairs
+            && (self.last
+                || quality.next_pairs
+                    == usize::midpoint(floaters.len(), self.next.len()))
+            && quality.counts().iter().all(|count| *count == 0)
+            && floaters
+                .iter()
+                .all(|floater| floater.history.score <= self.lowest_score);
+        self.best = Some(Candidate {
+            pairs: pairs.to_vec(),
+            floaters: floaters.to_vec(),
+            quality,
+        });
+
+        true
+    }
+
+    /// Pair each player of `s1` with a player of `s2`, trying transpositions of `s2` in
+    /// lexicographic order.
+    ///
+    /// Returns whether a valid candidate was found.
+    fn transpositions(
+        &mut self,
+        pairs: &mut Vec<Pair<'a>>,
+        limbo: &[&'a Player],
+        s1: &[&'a Player],
+        s2: &[&'a Player],
+        next: &mut Continuation<'a, 'b, '_>,
+    ) -> bool {
+        let Some((first, s1)) = s1.split_first() else {
+            let floaters: Vec<&Player> = limbo.iter().chain(s2).copied().collect();
+            return next(self, pairs, floaters);
+        };
+
+        let mut found = false;
+        for (index, opponent) in s2.iter().enumerate() {
+            if self.exhausted() {
+                break;
+            }
+            if !Dutch::compatible(first, opponent) {
+                continue;
+            }
+
+            let mut rest = s2.to_vec();
+            rest.remove(index);
+            pairs.push(if first.rank < opponent.rank {
+                (first, opponent)
+            } else {
+                (opponent, first)
+            });
+            found |= self.transpositions(pairs, limbo, s1, &rest, next);
+            pairs.pop();
+        }
+
+        found
+    }
+
+    /// Pair a homogeneous bracket (or the remainder of a heterogeneous bracket).
+    ///
+    /// `pairs` are the pairs already made in the bracket, `limbo` the players which will
+    /// float whatever happens. Returns whether a valid candidate was found.
+    fn homogeneous(
+        &mut self,
+        pairs: &mut Vec<Pair<'a>>,
+        limbo: &[&'a Player],
+        players: &[&'a Player],
+    ) -> bool {
+        let (max_pairs, _) = Dutch::matching(players);
+
+        for size in (0..=max_pairs.len()).rev() {
+            let (s1, s2) = players.split_at(size);
+            let mut found = false;
+
+            let (exchanges, complete) = exchanges(s1, s2);
+            self.cut |= !complete;
+            for (s1, s2) in exchanges {
+                if self.exhausted() {
+                    break;
+                }
+                found |= self.transpositions(
+                    pairs,
+                    limbo,
+                    &s1,
+                    &self.engine.order(&s2),
+                    &mut |search, pairs, floaters| search.offer(pairs, &floaters),
+                );
+            }
+
+            if found || self.exhausted() {
+                return found;
+            }
+        }
+
+        false
+    }
+
+    /// Pair a heterogeneous bracket: pair the moved down players first, then the
+    /// remainder as a homogeneous bracket.
+    fn heterogeneous(&mut self, moved_down: &[&'a Player], residents: &[&'a Player]) {
+        // M1: maximum number of moved down players which can be paired with residents.
+        let players: Vec<&Player> = moved_down.iter().chain(residents).copied().collect();
+        let edge = |i: usize, j: usize| {
+            (i < moved_down.len()) != (j < moved_down.len())
+                && players
+                    .get(i)
+                    .zip(players.get(j))
+                    .is_some_and(|(a, b)| Dutch::compatible(a, b))
+        };
+        let paired_down = maximum_matching(players.len(), &edge)
+            .iter()
+            .take(moved_down.len())
+            .filter(|i| i.is_some())
+            .count();
+
+        // Only S2 is reversed, the remainder is paired in rank order.
+        let s2 = self.engine.order(residents);
+        for size in (0..=paired_down).rev() {
+            let mut found = false;
+
+            for s1 in combinations(moved_down.len(), size) {
+                if self.exhausted() {
+                    break;
+                }
+                let limbo: Vec<&Player> = moved_down
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !s1.contains(index))
+                    .map(|(_, i)| *i)
+                    .collect();
+                let s1: Vec<&Player> = s1
+                    .iter()
+                    .filter_map(|i| moved_down.get(*i).copied())
+                    .collect();
+
+                found |= self.transpositions(
+                    &mut vec![],
+                    &[],
+                    &s1,
+                    &s2,
+                    &mut |search, pairs, mut remainder| {
+                        remainder.sort_by_key(|i| i.rank);
+                        search.homogeneous(pairs, &limbo, &remainder)
+                    },
+                );
+            }
+
+            if found || self.exhausted() {
+                return;
+            }
+        }
+    }
+}
+
+/// Get the number of players at the start of `players` with the same score as the first
+/// one: the residents of the next bracket.
+fn score_group(players: &[&Player]) -> usize {
+    players.first().map_or(0, |first| {
+        players
+            .iter()
+            .take_while(|i| i.history.score.total_cmp(&first.history.score).is_eq())
+            .count()
+    })
+}
+
+/// Get the ranks of a set of players in ascending order, used as a key.
+fn ranks(players: &[&Player]) -> Vec<usize> {
+    let mut ranks: Vec<usize> = players.iter().map(|i| i.rank).collect();
+    ranks.sort_unstable();
+
+    ranks
+}
+
+/// Get all combinations of `size` elements out of `count`, in lexicographic order.
+fn combinations(count: usize, size: usize) -> Vec<Vec<usize>> {
+    let mut result = vec![];
+    let mut current: Vec<usize> = (0..size).collect();
+
+    if size > count {
+        return result;
+    }
+
+    loop {
+        result.push(current.clone());
+
+        // Find the rightmost element which can be incremented.
+        let Some(position) = (0..size).rev().find(|i| {
+            current
+                .get(*i)
+                .is_some_and(|value| *value < count - size + i)
+        }) else {
+            return result;
+        };
+
+        if let Some(value) = current.get_mut(position) {
+            *value += 1;
+        }
+        for i in position + 1..size {
+            let previous = current.get(i - 1).copied().unwrap_or_default();
+            if let Some(value) = current.get_mut(i) {
+                *value = previous + 1;
+            }
+        }
+    }
+}
+
+/// Get the S1 and S2 subgroups to try in a homogeneous bracket, starting with the
+/// original ones and followed by exchanges (see C.04.3 D.2).
+///
+/// Also returns whether every exchange was generated: exchanges of more players are
+/// left out once there are more than [`EXCHANGE_LIMIT`] of them.
+fn exchanges<'a>(s1: &[&'a Player], s2: &[&'a Player]) -> (Vec<Subgroups<'a>>, bool) {
+    let mut result = vec![(s1.to_vec(), s2.to_vec())];
+
+    for size in 1..=s1.len().min(s2.len()) {
+        let from_s1 = combinations(s1.len(), size);
+        let from_s2 = combinations(s2.len(), size);
+        if from_s1.len().saturating_mul(from_s2.len()) > EXCHANGE_LIMIT {
+            return (result, false);
+        }
+
+        let mut exchanges: Vec<(&Vec<usize>, &Vec<usize>)> = from_s1
+            .iter()
+            .flat_map(|a| from_s2.iter().map(move |b| (a, b)))
+            .collect();
+
+        // Bracket sequence numbers of S2 start after S1.
+        let sum = |a: &Vec<usize>, b: &Vec<usize>| {
+            b.iter().map(|i| i + s1.len()).sum::<usize>() - a.iter().sum::<usize>()
+        };
+        exchanges.sort_by(|(a1, b1), (a2, b2)| {
+            sum(a1, b1)
+                .cmp(&sum(a2, b2))
+                .then_with(|| a2.iter().rev().cmp(a1.iter().rev()))
+                .then_with(|| b1.cmp(b2))
+        });
+
+        for (from_s1, from_s2) in exchanges {
+            let mut new_s1: Vec<&Player> = s1
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !from_s1.contains(index))
+                .map(|(_, i)| *i)
+                .chain(from_s2.iter().filter_map(|i| s2.get(*i).copied()))
+                .collect();
+            let mut new_s2: Vec<&Player> = s2
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !from_s2.contains(index))
+                .map(|(_, i)| *i)
+                .chain(from_s1.iter().filter_map(|i| s1.get(*i).copied()))
+                .collect();
+            new_s1.sort_by_key(|i| i.rank);
+            new_s2.sort_by_key(|i| i.rank);
+
+            result.push((new_s1, new_s2));
+        }
+    }
+
+    (result, true)
+}
+
+/// Pair a round of a tournament with the FIDE Dutch system.
+///
+/// Players are paired based on the rounds before `round`. Players who already have a
+/// bye (other than a pairing-allocated bye) in `round` are considered absent.
+///
+/// In round 1, S1 meets S2 and colours alternate from the initial colour (E.5):
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// 012 Test Open
+/// 001    1      Alpha, Anna                       2000                             0.0
+/// 001    2      Bravo, Ben                        1900                             0.0
+/// 001    3      Charlie, Cleo                     1800                             0.0
+/// 001    4      Delta, Dan                        1700                             0.0
+/// 001    5      Echo, Eve                         1600                             0.0
+/// 001    6      Foxtrot, Fay                      1500                             0.0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 1).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(1, 4), Game::new(5, 2), Game::new(3, 6)]
+/// );
+///
+/// let file_contents = file_contents.replace("012 Test Open", "XXC black1");
+/// let situation = Situation::try_from(file_contents).unwrap();
+/// let pairings = dutch::pair(&situation, 1).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(4, 1), Game::new(2, 5), Game::new(6, 3)]
+/// );
+/// ```
+///
+/// Players 1 and 3 already met (C1), and exchanging players 2 and 3 gives every player
+/// their colour preference (C12):
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             0.5          3 w =
+/// 001    2      Bravo, Ben                        1900                             0.5          4 b =
+/// 001    3      Charlie, Cleo                     1800                             0.5          1 b =
+/// 001    4      Delta, Dan                        1700                             0.5          2 w =";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 2).unwrap();
+/// assert_eq!(pairings.games(), [Game::new(2, 1), Game::new(3, 4)]);
+/// ```
+///
+/// Player 1 is moved down to a heterogeneous bracket and meets player 4. Players 4 and
+/// 5 already had a bye (C2), so player 3 gets it:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             2.0          3 w 1     2 b 1
+/// 001    2      Bravo, Ben                        1900                             1.0          4 b 1     1 w 0
+/// 001    3      Charlie, Cleo                     1800                             1.0          1 b 0     5 w 1
+/// 001    4      Delta, Dan                        1700                             1.0          2 w 0       - U
+/// 001    5      Echo, Eve                         1600                             1.0            - U     3 b 0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 3).unwrap();
+/// assert_eq!(pairings.games(), [Game::new(1, 4), Game::new(5, 2)]);
+/// assert_eq!(pairings.bye(), Some(3));
+/// ```
+///
+/// Players 1 and 2 both prefer black strongly: player 1 gets white, since they had
+/// black when player 2 had white in the previous round (E.3). Player 4 gets their
+/// absolute preference over player 6 (E.2):
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             3.0          3 w 1     4 w 1     5 b 1
+/// 001    2      Bravo, Ben                        1900                             3.0          6 w 1     3 b 1     4 w 1
+/// 001    3      Charlie, Cleo                     1800                             0.5          1 b 0     2 w 0     6 b =
+/// 001    4      Delta, Dan                        1700                             0.5          5 w =     1 b 0     2 b 0
+/// 001    5      Echo, Eve                         1600                             1.0          4 b =     6 w =     1 w 0
+/// 001    6      Foxtrot, Fay                      1500                             1.0          2 b 0     5 b =     3 w =";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 4).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(1, 2), Game::new(3, 5), Game::new(4, 6)]
+/// );
+/// ```
+///
+/// Players 1 and 2 both have an absolute preference for black, they can only meet as
+/// topscorers in the last round (C3):
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// XXR 3
+/// 001    1      Alpha, Anna                       2000                             2.0          3 w 1     5 w 1
+/// 001    2      Bravo, Ben                        1900                             2.0          4 w 1     6 w 1
+/// 001    3      Charlie, Cleo                     1800                             0.5          1 b 0     4 w =
+/// 001    4      Delta, Dan                        1700                             0.5          2 b 0     3 b =
+/// 001    5      Echo, Eve                         1600                             0.5          6 w =     1 b 0
+/// 001    6      Foxtrot, Fay                      1500                             0.5          5 b =     2 b 0";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 3).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(2, 1), Game::new(6, 3), Game::new(4, 5)]
+/// );
+///
+/// let situation = Situation::try_from(file_contents.replace("XXR 3", "XXR 5")).unwrap();
+/// let pairings = dutch::pair(&situation, 3).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(4, 1), Game::new(5, 2), Game::new(6, 3)]
+/// );
+/// ```
+///
+/// The pairing constraints of TRF-2025 (see [`crate::trf::constraint`]) and of the
+/// `JaVaFo` extension (see [`crate::trf::extension`]) are followed: forbidden pairs
+/// (DIN 260 or XXP) don't meet, absent players (DIN 240) aren't paired, and
+/// accelerations (DIN 250 or XXA) are added to the scores:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let players = "\
+/// 001    1      Alpha, Anna                       2000                             0.0
+/// 001    2      Bravo, Ben                        1900                             0.0
+/// 001    3      Charlie, Cleo                     1800                             0.0
+/// 001    4      Delta, Dan                        1700                             0.0
+/// 001    5      Echo, Eve                         1600                             0.0
+/// 001    6      Foxtrot, Fay                      1500                             0.0";
+/// let pair = |constraint: &str| {
+///     let situation =
+///         Situation::try_from(format!("012 Test Open\n{constraint}\n{players}")).unwrap();
+///     dutch::pair(&situation, 1).unwrap()
+/// };
+///
+/// // Without constraints: 1-4, 5-2 and 3-6.
+/// assert_eq!(
+///     pair("").games(),
+///     [Game::new(1, 4), Game::new(5, 2), Game::new(3, 6)]
+/// );
+///
+/// for forbidden in ["260    1    4", "XXP    1    4"] {
+///     assert_eq!(
+///         pair(forbidden).games(),
+///         [Game::new(1, 5), Game::new(4, 2), Game::new(3, 6)]
+///     );
+/// }
+///
+/// let pairings = pair("240 H   1    6");
+/// assert_eq!(pairings.games(), [Game::new(1, 3), Game::new(4, 2)]);
+/// assert_eq!(pairings.bye(), Some(5));
+///
+/// for acceleration in ["250  1.0   1   1    1    2", "XXA    1  1.0\nXXA    2  1.0"] {
+///     assert_eq!(
+///         pair(acceleration).games(),
+///         [Game::new(1, 2), Game::new(3, 5), Game::new(6, 4)]
+///     );
+/// }
+/// ```
+///
+/// Scores use the custom score values (XXS) if any. Player 5 gets a point for their
+/// pairing-allocated bye and meets player 1, but the bye is worth nothing with
+/// `XXS PAB=0` and player 5 stays in the bottom score group:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{dutch, Game}, Situation};
+/// let file_contents = "\
+/// 012 Test Open
+/// 001    1      Alpha, Anna                       2000                             1.0          3 w 1
+/// 001    2      Bravo, Ben                        1900                             1.0          4 b 1
+/// 001    3      Charlie, Cleo                     1800                             0.0          1 b 0
+/// 001    4      Delta, Dan                        1700                             0.0          2 w 0
+/// 001    5      Echo, Eve                         1600                             1.0            - U";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = dutch::pair(&situation, 2).unwrap();
+/// assert_eq!(pairings.games(), [Game::new(5, 1), Game::new(2, 3)]);
+///
+/// let file_contents = file_contents
+///     .replace("Open", "Open\nXXS PAB=0")
+///     .replace("1.0            - U", "0.0            - U");
+/// let situation = Situation::try_from(file_contents).unwrap();
+/// let pairings = dutch::pair(&situation, 2).unwrap();
+/// assert_eq!(pairings.games(), [Game::new(2, 1), Game::new(3, 5)]);
+/// assert_eq!(pairings.bye(), Some(4));
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the round is invalid, if a player's starting rank number is
+/// invalid, or if no pairing satisfies the absolute criteria.
+pub fn pair(situation: &Situation, round: u16) -> Result<Pairings, PairingError> {
+    Dutch::new(situation, round)?.pairings()
+}
@@ -0,0 +1,403 @@
+//! # Pairing history
+//!
+//! Pairing engines don't work on [`Section`]s directly, they need to know what happened
+//! in the previous rounds: scores, opponents, colours, floats and byes. This module
+//! extracts that information from the [`PlayerRoundSection`]s of each player.
+//!
+//! Scores use the custom score values of the `JaVaFo` extension if any (XXS, see
+//! [`crate::trf::extension::ScoreValues`]), and the scoring system of the situation
+//! otherwise (see [`crate::trf::scoring::ScoringSystem`]). The pairing constraints of
+//! TRF-2025 (see [`crate::trf::constraint`]) and of the extension (XXA and XXP) are
+//! applied as well: accelerations are added to the scores, absent players are marked
+//! absent, and forbidden pairs are stored for each player.
+//!
+//! Following C.04.1, only games which were actually played count as played: forfeits
+//! and byes don't give a colour and don't prevent two players from meeting again.
+use crate::{
+    trf::player::{
+        round::{
+            ByeRoundResult, Color, NotPlayedRoundResult, PlayerRoundSection,
+            RegularRoundResult, Result as RoundResult,
+        },
+        Section,
+    },
+    Situation,
+};
+
+use super::PairingError;
+
+/// The float received by a player in a round (see C.04.3 A.4).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Float {
+    /// Paired against an opponent with a lower score, or didn't play.
+    Down,
+
+    /// Paired against an opponent with a higher score.
+    Up,
+
+    /// Paired against an opponent with the same score.
+    None,
+}
+
+/// Strength of a colour preference (see C.04.1 and C.04.3 A.6).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+    /// The colour difference is zero, the player prefers to alternate colours.
+    Mild,
+
+    /// The colour difference is +1 or -1.
+    Strong,
+
+    /// The colour difference is greater than +1 or less than -1, or the player had the
+    /// same colour in the two latest played games.
+    Absolute,
+}
+
+/// A colour preference.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Preference {
+    /// The preferred colour, either [`Color::White`] or [`Color::Black`].
+    pub color: Color,
+
+    /// How strong the preference is.
+    pub strength: Strength,
+}
+
+/// Everything pairing engines need to know about a player before a round.
+#[derive(Debug, Clone)]
+pub struct PlayerHistory {
+    /// Player starting rank number, also used as pairing number.
+    pub id: u16,
+
+    /// Score before the round, including the accelerations of the round.
+    pub score: f32,
+
+    /// Opponents of the games actually played.
+    pub opponents: Vec<u16>,
+
+    /// Colours of the games actually played, in order.
+    pub colors: Vec<Color>,
+
+    /// Float received in each previous round.
+    pub floats: Vec<Float>,
+
+    /// Number of previous rounds which weren't played.
+    pub unplayed_rounds: usize,
+
+    /// Whether the player already received a pairing-allocated bye, or scored a win
+    /// without playing (forfeit win, full-point bye).
+    pub had_bye: bool,
+
+    /// Whether the player is absent from the round.
+    ///
+    /// A player is absent if the round was already filled with a bye which isn't a
+    /// pairing-allocated bye (e.g. a half-point bye requested in advance), or if an
+    /// absent players record names them for the round.
+    pub absent: bool,
+
+    /// Players the player must not be paired with (forbidden pairs).
+    pub forbidden: Vec<u16>,
+}
+
+impl PlayerHistory {
+    /// Colour difference: number of games played with white minus number of games
+    /// played with black.
+    pub fn color_difference(&self) -> i32 {
+        self.colors.iter().fold(0, |difference, color| match color {
+            Color::White => difference + 1,
+            Color::Black => difference - 1,
+            Color::None => difference,
+        })
+    }
+
+    /// Colour preference, [`None`] if the player hasn't played any game yet.
+    pub fn preference(&self) -> Option<Preference> {
+        let last = *self.colors.last()?;
+        let difference = self.color_difference();
+        let same_twice = self.colors.len() >= 2
+            && self.colors.get(self.colors.len() - 2) == Some(&last);
+
+        let (color, strength) = if difference > 1 {
+            (Color::Black, Strength::Absolute)
+        } else if difference < -1 {
+            (Color::White, Strength::Absolute)
+        } else if same_twice {
+            (opposite(last)?, Strength::Absolute)
+        } else if difference == 1 {
+            (Color::Black, Strength::Strong)
+        } else if difference == -1 {
+            (Color::White, Strength::Strong)
+        } else {
+            (opposite(last)?, Strength::Mild)
+        };
+
+        Some(Preference { color, strength })
+    }
+
+    /// Whether the player already played against `opponent`.
+    pub fn played(&self, opponent: u16) -> bool {
+        self.opponents.contains(&opponent)
+    }
+
+    /// Whether the player must not be paired with `opponent`.
+    pub fn forbids(&self, opponent: u16) -> bool {
+        self.forbidden.contains(&opponent)
+    }
+
+    /// Float received `rounds_ago` rounds before this one (1 is the previous round).
+    pub fn float(&self, rounds_ago: usize) -> Float {
+        self.floats
+            .len()
+            .checked_sub(rounds_ago)
+            .and_then(|index| self.floats.get(index))
+            .copied()
+            .unwrap_or(Float::None)
+    }
+}
+
+/// Get the opposite of a colour.
+pub const fn opposite(color: Color) -> Option<Color> {
+    match color {
+        Color::White => Some(Color::Black),
+        Color::Black => Some(Color::White),
+        Color::None => None,
+    }
+}
+
+/// What a player did in a single round.
+struct RoundEntry {
+    /// Opponent, if any.
+    opponent: Option<u16>,
+
+    /// Colour, if the game was played.
+    color: Option<Color>,
+
+    /// Result, if any.
+    result: Option<RoundResult>,
+}
+
+impl RoundEntry {
+    /// Read a round section, an empty or unreadable section gives an empty entry.
+    fn read(section: Option<&PlayerRoundSection>) -> Self {
+        let opponent = section.and_then(|i| i.id().ok().copied().flatten());
+        let result = section.and_then(|i| i.result().ok().copied().flatten());
+        let color = section
+            .and_then(|i| i.color().ok().copied().flatten())
+            .filter(|color| *color != Color::None);
+
+        Self {
+            opponent,
+            color,
+            result,
+        }
+    }
+
+    /// Whether the player scored a win without playing (forfeit win, full-point bye or
+    /// pairing-allocated bye).
+    const fn unplayed_win(&self) -> bool {
+        !self.played()
+            && matches!(
+                self.result,
+                Some(
+                    RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitWin)
+                        | RoundResult::Bye(
+                            ByeRoundResult::FullPointBye
+                                | ByeRoundResult::PairingAllocatedBye
+                        )
+                )
+            )
+    }
+
+    /// Whether the player is absent: no opponent, and a result which isn't a
+    /// pairing-allocated bye.
+    fn absent(&self) -> bool {
+        self.opponent.is_none()
+            && self.result.is_some_and(|result| {
+                result != RoundResult::Bye(ByeRoundResult::PairingAllocatedBye)
+            })
+    }
+
+    /// Whether the game was actually played.
+    const fn played(&self) -> bool {
+        self.opponent.is_some()
+            && self.color.is_some()
+            && matches!(
+                self.result,
+                Some(RoundResult::Regular(_) | RoundResult::LessThanOneMove(_))
+            )
+    }
+}
+
+/// Get the points given for a round result, see
+/// [`crate::trf::extension::ScoreValues::points`] and
+/// [`crate::trf::scoring::ScoringSystem::points`].
+fn points(situation: &Situation, result: RoundResult, color: Option<Color>) -> f32 {
+    situation
+        .extensions()
+        .score_values()
+        .ok()
+        .and_then(Option::as_ref)
+        .and_then(|values| values.points(result, color))
+        .unwrap_or_else(|| situation.scoring_system().points(result))
+}
+
+/// Get the points for a win with white, used for the maximum score of a round.
+pub fn win(situation: &Situation) -> f32 {
+    points(
+        situation,
+        RoundResult::Regular(RegularRoundResult::Win),
+        Some(Color::White),
+    )
+}
+
+/// Points added to the score of a player for the pairing of a round, see
+/// [`crate::trf::constraint::Acceleration`] and
+/// [`crate::trf::extension::PlayerAcceleration`].
+fn acceleration(situation: &Situation, round: u16, player: u16) -> f32 {
+    situation
+        .accelerations()
+        .iter()
+        .filter(|acceleration| acceleration.applies(round, player))
+        .filter_map(|acceleration| acceleration.points().ok().copied().flatten())
+        .sum::<f32>()
+        + situation.extensions().acceleration(player, round)
+}
+
+/// Whether an absent players record names a player for a round, see
+/// [`crate::trf::constraint::AbsentPlayers`].
+fn announced_absence(situation: &Situation, round: u16, player: u16) -> bool {
+    situation
+        .absent_players()
+        .iter()
+        .any(|absent| absent.includes(round, player))
+}
+
+/// Get the players among `players` who must not be paired with a player, see
+/// [`crate::trf::constraint::ForbiddenPairs`] (DIN 260 and XXP).
+fn forbidden(
+    situation: &Situation,
+    player: u16,
+    players: &[(u16, &Section)],
+) -> Vec<u16> {
+    players
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| {
+            situation
+                .forbidden_pairs()
+                .iter()
+                .chain(situation.extensions().forbidden_pairs())
+                .any(|pairs| pairs.forbids(player, *id))
+        })
+        .collect()
+}
+
+/// Get the history of every player before `round`.
+///
+/// Players are returned in the same order as [`Situation::players`].
+///
+/// # Errors
+///
+/// Returns an error if `round` is zero or if a starting rank number is invalid.
+pub fn histories(
+    situation: &Situation,
+    round: u16,
+) -> Result<Vec<PlayerHistory>, PairingError> {
+    if round == 0 {
+        return Err(PairingError::InvalidRoundError(round));
+    }
+    let previous_rounds = usize::from(round - 1);
+
+    let players: Vec<(u16, &Section)> = situation
+        .players()
+        .iter()
+        .enumerate()
+        .map(|(index, player)| {
+            player
+                .starting_rank_number()
+                .map(|id| (*id, player))
+                .map_err(|_| PairingError::InvalidStartingRankNumberError(index))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let entries: Vec<Vec<RoundEntry>> = players
+        .iter()
+        .map(|(_, player)| {
+            (0..=previous_rounds)
+                .map(|round| {
+                    RoundEntry::read(player.rounds().get(round).and_then(Option::as_ref))
+                })
+                .collect()
+        })
+        .collect();
+
+    // Scores before each round, with the accelerations of the round, used for floats.
+    let scores: Vec<Vec<f32>> = players
+        .iter()
+        .zip(&entries)
+        .map(|((id, _), entries)| {
+            let mut score = 0.0;
+            entries
+                .iter()
+                .take(previous_rounds + 1)
+                .zip(1..)
+                .map(|(entry, round)| {
+                    let before = score + acceleration(situation, round, *id);
+                    score += entry
+                        .result
+                        .map_or(0.0, |result| points(situation, result, entry.color));
+                    before
+                })
+                .collect()
+        })
+        .collect();
+    let score_before = |id: u16, round: usize| {
+        players
+            .iter()
+            .position(|(i, _)| *i == id)
+            .and_then(|index| scores.get(index)?.get(round).copied())
+    };
+
+    Ok(players
+        .iter()
+        .zip(&entries)
+        .zip(&scores)
+        .map(|(((id, _), entries), scores)| {
+            let previous = entries.get(..previous_rounds).unwrap_or_default();
+            let played = || previous.iter().filter(|entry| entry.played());
+
+            let floats = previous
+                .iter()
+                .enumerate()
+                .map(|(round, entry)| {
+                    let own = scores.get(round).copied().unwrap_or_default();
+                    match entry.opponent.and_then(|i| score_before(i, round)) {
+                        Some(opponent) if entry.played() => {
+                            match own.total_cmp(&opponent) {
+                                std::cmp::Ordering::Greater => Float::Down,
+                                std::cmp::Ordering::Less => Float::Up,
+                                std::cmp::Ordering::Equal => Float::None,
+                            }
+                        }
+                        _ => Float::Down,
+                    }
+                })
+                .collect();
+
+            let current = entries.get(previous_rounds);
+
+            PlayerHistory {
+                id: *id,
+                score: scores.get(previous_rounds).copied().unwrap_or_default(),
+                opponents: played().filter_map(|entry| entry.opponent).collect(),
+                colors: played().filter_map(|entry| entry.color).collect(),
+                floats,
+                unplayed_rounds: previous.iter().filter(|entry| !entry.played()).count(),
+                had_bye: previous.iter().any(RoundEntry::unplayed_win),
+                absent: current.is_some_and(RoundEntry::absent)
+                    || announced_absence(situation, round, *id),
+                forbidden: forbidden(situation, *id, &players),
+            }
+        })
+        .collect())
+}
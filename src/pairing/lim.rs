@@ -0,0 +1,510 @@
+//! # Lim system
+//!
+//! This module implements the Lim system, C.04.4.3 of the FIDE Handbook.
+//!
+//! ## Status
+//!
+//! Players are ranked by score, then by starting rank number. If there is an odd number
+//! of players, the lowest ranked player who can receive it gets the pairing-allocated
+//! bye first.
+//!
+//! Score groups above the median score (half of the maximum score) are paired from the
+//! top down, score groups below it from the bottom up, and the median score group is
+//! paired last:
+//! - A score group with an odd number of players sends a floater towards the median
+//!   score group: a player who didn't float in the same direction in the previous round,
+//!   then who has the colour preference in excess in the group, then the lowest ranked
+//!   for downfloaters and the highest ranked for upfloaters. A player who floated into
+//!   the score group only floats again if no other player can. The floater joins the next
+//!   score group, or the median score group for the downfloater of the last score group
+//!   above it. A downfloater meets the highest ranked player of its new score
+//!   group it can, an upfloater the lowest ranked.
+//! - The first half of the score group meets the second half, trying transpositions of
+//!   the second half, then exchanges of one player between the halves, until every player
+//!   gets their colour preference, at most [`CANDIDATE_LIMIT`] times. The pairing
+//!   satisfying the most preferences is kept.
+//!
+//! The absolute criteria and the colour allocation are those of the Dutch system (see
+//! [`super::dutch`]), without topscorers. A floater is only chosen if the remaining
+//! players can still be paired. If a score group can't be paired this way, it is paired
+//! with a maximum matching, which satisfies the absolute criteria but not the order
+//! above, and the pairings are marked as approximate (see
+//! [`super::Pairings::approximate`]).
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::lim, Situation};
+//!
+//! let file_contents = fs::read_to_string(
+//!     "lichess_swiss_2021.02.20_sniu3bgA_fm-maurizio-brancaleoni-pract.trf",
+//! )
+//! .unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! let pairings = lim::pair(&situation, 6).unwrap();
+//!
+//! assert_eq!(pairings.games().len(), 4);
+//! assert!(pairings.bye().is_some());
+//! ```
+use std::{
+    cmp::{Ordering, Reverse},
+    mem,
+};
+
+use crate::{trf::player::round::Color, Situation};
+
+use super::{
+    dutch::{Dutch, Pair, Player, CANDIDATE_LIMIT},
+    history::{histories, win, Float, PlayerHistory},
+    PairingError, Pairings,
+};
+
+/// The best pairing of a score group found so far, with the number of players who don't
+/// get their colour preference.
+type Best<'a> = Option<(usize, Vec<Pair<'a>>)>;
+
+/// The pairing engine state for a round.
+struct Lim {
+    /// The Dutch engine, for the absolute criteria and the colour allocation.
+    engine: Dutch,
+
+    /// The median score: half of the maximum score before the round.
+    median: f32,
+}
+
+impl Lim {
+    /// Create the engine for a round, with the players who aren't absent.
+    fn new(situation: &Situation, round: u16) -> Result<Self, PairingError> {
+        let mut histories: Vec<PlayerHistory> = histories(situation, round)?
+            .into_iter()
+            .filter(|history| !history.absent)
+            .collect();
+        histories.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+
+        Ok(Self {
+            engine: Dutch::ranked(situation, histories, false, |_| false),
+            median: f32::from(round - 1) * win(situation) / 2.0,
+        })
+    }
+
+    /// Number of players of the pairs who don't get their colour preference.
+    fn color_violations(&self, pairs: &[Pair<'_>]) -> usize {
+        pairs
+            .iter()
+            .map(|pair| self.engine.allocate(*pair))
+            .flat_map(|(white, black)| [(white, Color::White), (black, Color::Black)])
+            .filter(|(player, color)| {
+                player.preference.is_some_and(|i| i.color != *color)
+            })
+            .count()
+    }
+
+    /// Pair each player of `s1` with a player of `s2`, trying transpositions of `s2` in
+    /// lexicographic order, and keep the pairing satisfying the most colour preferences.
+    fn transpositions<'a>(
+        &self,
+        s1: &[&'a Player],
+        s2: &[&'a Player],
+        pairs: &mut Vec<Pair<'a>>,
+        best: &mut Best<'a>,
+        evaluated: &mut usize,
+    ) {
+        let Some((first, s1)) = s1.split_first() else {
+            *evaluated += 1;
+            let violations = self.color_violations(pairs);
+            if best.as_ref().is_none_or(|(best, _)| violations < *best) {
+                *best = Some((violations, pairs.clone()));
+            }
+            return;
+        };
+
+        for (index, opponent) in s2.iter().enumerate() {
+            if *evaluated >= CANDIDATE_LIMIT
+                || best
+                    .as_ref()
+                    .is_some_and(|(violations, _)| *violations == 0)
+            {
+                return;
+            }
+            if !Dutch::compatible(first, opponent) {
+                continue;
+            }
+
+            let mut rest = s2.to_vec();
+            rest.remove(index);
+            pairs.push(if first.rank < opponent.rank {
+                (first, opponent)
+            } else {
+                (opponent, first)
+            });
+            self.transpositions(s1, &rest, pairs, best, evaluated);
+            pairs.pop();
+        }
+    }
+
+    /// Pair every player of an even score group, returns [`None`] if it isn't possible.
+    ///
+    /// If `exact` is false and neither the transpositions nor the exchanges work, the
+    /// score group is paired with a maximum matching instead, regardless of the pairing
+    /// order.
+    fn pair_group<'a>(&self, group: &[&'a Player], exact: bool) -> Option<Vec<Pair<'a>>> {
+        let (s1, s2) = group.split_at(group.len() / 2);
+
+        let mut best = None;
+        let mut evaluated = 0;
+        self.transpositions(s1, s2, &mut vec![], &mut best, &mut evaluated);
+
+        // Then exchange one player of each half, the closest in rank first.
+        let mut exchanges: Vec<(usize, usize)> = (0..s1.len())
+            .flat_map(|i| (0..s2.len()).map(move |j| (i, j)))
+            .collect();
+        exchanges.sort_by_key(|(i, j)| (s1.len() - i + j, Reverse(*i)));
+        for (i, j) in exchanges {
+            if evaluated >= CANDIDATE_LIMIT
+                || best
+                    .as_ref()
+                    .is_some_and(|(violations, _)| *violations == 0)
+            {
+                break;
+            }
+
+            let (mut s1, mut s2) = (s1.to_vec(), s2.to_vec());
+            if let (Some(a), Some(b)) = (s1.get_mut(i), s2.get_mut(j)) {
+                mem::swap(a, b);
+            }
+            s1.sort_by_key(|player| player.rank);
+            s2.sort_by_key(|player| player.rank);
+            self.transpositions(&s1, &s2, &mut vec![], &mut best, &mut evaluated);
+        }
+
+        if let Some((_, pairs)) = best {
+            return Some(pairs);
+        }
+        if exact {
+            return None;
+        }
+
+        // No transposition works, pair the score group anyway.
+        let (pairs, unpaired) = Dutch::matching(group);
+        unpaired.is_empty().then_some(pairs)
+    }
+
+    /// Pair the floaters who joined a score group, then the residents of the score group.
+    ///
+    /// A downfloater meets the highest ranked resident it can, an upfloater the lowest
+    /// ranked, and the other floater if no resident can. Returns [`None`] if it isn't
+    /// possible. See [`Lim::pair_group`] for `exact`.
+    fn pair_floaters<'a>(
+        &self,
+        floaters: &[(&'a Player, Float)],
+        group: &[&'a Player],
+        exact: bool,
+    ) -> Option<Vec<Pair<'a>>> {
+        let Some(((floater, direction), floaters)) = floaters.split_first() else {
+            return self.pair_group(group, exact);
+        };
+
+        let mut opponents = group.to_vec();
+        if *direction == Float::Up {
+            opponents.reverse();
+        }
+        opponents.extend(floaters.iter().map(|(player, _)| *player));
+        opponents
+            .into_iter()
+            .filter(|opponent| Dutch::compatible(floater, opponent))
+            .find_map(|opponent| {
+                let group: Vec<&Player> = group
+                    .iter()
+                    .filter(|player| player.rank != opponent.rank)
+                    .copied()
+                    .collect();
+                let floaters: Vec<(&Player, Float)> = floaters
+                    .iter()
+                    .filter(|(player, _)| player.rank != opponent.rank)
+                    .copied()
+                    .collect();
+                let mut pairs = self.pair_floaters(&floaters, &group, exact)?;
+                pairs.push(if floater.rank < opponent.rank {
+                    (floater, opponent)
+                } else {
+                    (opponent, floater)
+                });
+                Some(pairs)
+            })
+    }
+
+    /// Pair a score group, with the floater `carried` from the previous score group.
+    ///
+    /// `direction` is the direction of the floaters, [`Float::Down`] above the median
+    /// score. Returns the pairs and the floater sent to the next score group, or [`None`]
+    /// if the score group can't be paired while the other players remain pairable. See
+    /// [`Lim::pair_group`] for `exact`.
+    fn bracket<'a>(
+        &self,
+        remaining: &[&'a Player],
+        score: f32,
+        carried: Option<&'a Player>,
+        direction: Float,
+        exact: bool,
+    ) -> Option<(Vec<Pair<'a>>, Option<&'a Player>)> {
+        let (group, rest): (Vec<&Player>, Vec<&Player>) = remaining
+            .iter()
+            .filter(|player| carried.is_none_or(|i| i.rank != player.rank))
+            .partition(|player| player.history.score.total_cmp(&score).is_eq());
+        let floaters: Vec<(&Player, Float)> =
+            carried.map(|i| (i, direction)).into_iter().collect();
+
+        let odd = (group.len() + floaters.len()) % 2 == 1;
+        if !odd {
+            if !Dutch::pairable(&rest) {
+                return None;
+            }
+            return self
+                .pair_floaters(&floaters, &group, exact)
+                .map(|pairs| (pairs, None));
+        }
+
+        // The colour preference in excess in the score group.
+        let seeking = |color| {
+            group
+                .iter()
+                .chain(carried.as_slice())
+                .filter(|player| player.preference.is_some_and(|i| i.color == color))
+                .count()
+        };
+        let excess = match seeking(Color::White).cmp(&seeking(Color::Black)) {
+            Ordering::Greater => Some(Color::White),
+            Ordering::Less => Some(Color::Black),
+            Ordering::Equal => None,
+        };
+
+        let mut candidates = group.clone();
+        if direction == Float::Down {
+            candidates.reverse();
+        }
+        candidates.sort_by_key(|player| {
+            (
+                player.history.float(1) == direction,
+                excess.is_none_or(|color| {
+                    player.preference.is_none_or(|i| i.color != color)
+                }),
+            )
+        });
+
+        // The floater carried from the previous score group only floats again if no
+        // resident can.
+        let residents = candidates.into_iter().map(|floater| {
+            let group: Vec<&Player> = group
+                .iter()
+                .filter(|player| player.rank != floater.rank)
+                .copied()
+                .collect();
+            (floater, group, floaters.as_slice())
+        });
+        let carried = carried.map(|floater| (floater, group.clone(), &[][..]));
+
+        residents
+            .chain(carried)
+            .find_map(|(floater, group, floaters)| {
+                let remaining: Vec<&Player> =
+                    rest.iter().copied().chain([floater]).collect();
+                if !Dutch::pairable(&remaining) {
+                    return None;
+                }
+
+                self.pair_floaters(floaters, &group, exact)
+                    .map(|pairs| (pairs, Some(floater)))
+            })
+    }
+
+    /// Pair the score groups in the order of the Lim system.
+    ///
+    /// Returns [`None`] if a score group can't be paired. See [`Lim::pair_group`] for
+    /// `exact`.
+    fn brackets<'a>(&self, players: &[&'a Player], exact: bool) -> Option<Vec<Pair<'a>>> {
+        let mut scores: Vec<f32> = players.iter().map(|i| i.history.score).collect();
+        scores.dedup_by(|a, b| a.total_cmp(b).is_eq());
+
+        let above = scores.iter().filter(|score| **score > self.median);
+        let below = scores.iter().rev().filter(|score| **score < self.median);
+        let order = above
+            .map(|score| (*score, Float::Down))
+            .chain(below.map(|score| (*score, Float::Up)));
+
+        let mut remaining = players.to_vec();
+        let mut pairs = vec![];
+        let mut floater = None;
+        let mut pending = None;
+        let mut direction = Float::Down;
+
+        for (score, next) in order {
+            if next != direction {
+                // The downfloater waits for the median score group.
+                direction = next;
+                pending = floater.take();
+            }
+
+            let (bracket, next) =
+                self.bracket(&remaining, score, floater, direction, exact)?;
+            remaining.retain(|player| {
+                !bracket
+                    .iter()
+                    .any(|(a, b)| a.rank == player.rank || b.rank == player.rank)
+            });
+            pairs.extend(bracket);
+            floater = next;
+        }
+
+        // The median score group, with the floaters from both sides.
+        let floaters: Vec<(&Player, Float)> = pending
+            .map(|i| (i, Float::Down))
+            .into_iter()
+            .chain(floater.map(|i| (i, direction)))
+            .collect();
+        let group: Vec<&Player> = remaining
+            .iter()
+            .filter(|player| !floaters.iter().any(|(i, _)| i.rank == player.rank))
+            .copied()
+            .collect();
+        pairs.extend(self.pair_floaters(&floaters, &group, exact)?);
+        Some(pairs)
+    }
+}
+
+/// Pair a round of a tournament with the Lim system.
+///
+/// Players are paired based on the rounds before `round`. Players who already have a
+/// bye (other than a pairing-allocated bye) in `round` are considered absent.
+///
+/// Player 6, the lowest ranked, receives the pairing-allocated bye. Player 7 floated down
+/// with its bye, so player 5 floats down from the score group on 1 point. Player 2 floats
+/// up from the score group on 0, and the median score group is paired last: the
+/// downfloater meets its highest ranked player, the upfloater its lowest ranked:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{lim, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2400                             0.5          4 w =
+/// 001    2      Bravo, Ben                        2390                             0.0          5 b 0
+/// 001    3      Charlie, Cleo                     2380                             1.0          6 w 1
+/// 001    4      Delta, Dan                        2370                             0.5          1 b =
+/// 001    5      Echo, Eve                         2360                             1.0          2 w 1
+/// 001    6      Foxtrot, Fay                      2350                             0.0          3 b 0
+/// 001    7      Golf, Gus                         2340                             1.0            - U";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = lim::pair(&situation, 2).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(7, 3), Game::new(1, 5), Game::new(4, 2)]
+/// );
+/// assert_eq!(pairings.bye(), Some(6));
+/// assert!(!pairings.approximate());
+/// ```
+///
+/// No transposition gives every player their colour preference, exchanging players 3
+/// and 4 between the halves does:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{lim, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             0.5          4 w =
+/// 001    2      Bravo, Ben                        1900                             0.5          5 w =
+/// 001    3      Charlie, Cleo                     1800                             0.5          6 b =
+/// 001    4      Delta, Dan                        1700                             0.5          1 b =
+/// 001    5      Echo, Eve                         1600                             0.5          2 b =
+/// 001    6      Foxtrot, Fay                      1500                             0.5          3 w =";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = lim::pair(&situation, 2).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [Game::new(5, 1), Game::new(3, 2), Game::new(4, 6)]
+/// );
+/// ```
+///
+/// Players 2 and 7, the score group on 0, must both have white and can't meet. The round
+/// is paired with a maximum matching instead, and the pairings are approximate:
+///
+/// ```
+/// # use openswisspairings_lib::{pairing::{lim, Game}, Situation};
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2400                             1.0          5 w =     8 w =
+/// 001    2      Bravo, Ben                        2390                             0.0          6 b 0     5 b 0
+/// 001    3      Charlie, Cleo                     2380                             1.0          7 w 1     6 b 0
+/// 001    4      Delta, Dan                        2370                             1.0          8 b 0     7 w 1
+/// 001    5      Echo, Eve                         2360                             1.5          1 b =     2 w 1
+/// 001    6      Foxtrot, Fay                      2350                             2.0          2 w 1     3 w 1
+/// 001    7      Golf, Gus                         2340                             0.0          3 b 0     4 b 0
+/// 001    8      Hotel, Hal                        2330                             1.5          4 w 1     1 b =";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let pairings = lim::pair(&situation, 3).unwrap();
+/// assert_eq!(
+///     pairings.games(),
+///     [
+///         Game::new(5, 6),
+///         Game::new(7, 8),
+///         Game::new(4, 1),
+///         Game::new(2, 3),
+///     ]
+/// );
+/// assert!(pairings.approximate());
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the round is invalid, if a player's starting rank number is
+/// invalid, or if no pairing satisfies the absolute criteria.
+pub fn pair(situation: &Situation, round: u16) -> Result<Pairings, PairingError> {
+    let lim = Lim::new(situation, round)?;
+
+    let mut players: Vec<&Player> = lim.engine.players.iter().collect();
+    if !Dutch::pairable(&players) {
+        return Err(PairingError::NoValidPairingError());
+    }
+
+    // The pairing-allocated bye goes to the lowest ranked player who can receive it.
+    let bye = if players.len() % 2 == 1 {
+        let without = |bye: &Player| -> Vec<&Player> {
+            players
+                .iter()
+                .filter(|player| player.rank != bye.rank)
+                .copied()
+                .collect()
+        };
+        let player = players
+            .iter()
+            .rev()
+            .find(|player| {
+                Dutch::bye_eligible(player) && Dutch::pairable(&without(player))
+            })
+            .copied()
+            .ok_or(PairingError::NoValidPairingError())?;
+
+        players = without(player);
+        Some(player)
+    } else {
+        None
+    };
+
+    // Follow the pairing order if possible, otherwise only the absolute criteria.
+    let mut approximate = false;
+    let pairs = lim
+        .brackets(&players, true)
+        .or_else(|| {
+            approximate = true;
+            lim.brackets(&players, false)
+        })
+        .or_else(|| {
+            let (pairs, unpaired) = Dutch::matching(&players);
+            unpaired.is_empty().then_some(pairs)
+        })
+        .ok_or(PairingError::NoValidPairingError())?;
+
+    let mut pairings = lim.engine.games(pairs, bye);
+    pairings.set_approximate(approximate);
+    Ok(pairings)
+}
@@ -0,0 +1,178 @@
+//! # Maximum matching
+//!
+//! Pairing engines need to know whether a set of players can still be paired without
+//! breaking the absolute criteria (the completion criterion of C.04.3). This is a
+//! maximum matching problem on a general graph, solved here with Edmonds' blossom
+//! algorithm.
+use std::collections::VecDeque;
+
+/// Edmonds' blossom algorithm state.
+struct Edmonds<'a> {
+    /// Whether two vertices can be matched.
+    edge: &'a dyn Fn(usize, usize) -> bool,
+
+    /// Number of vertices.
+    size: usize,
+
+    /// Vertex each vertex is matched with.
+    mate: Vec<Option<usize>>,
+
+    /// Parent of each vertex in the alternating tree.
+    parent: Vec<Option<usize>>,
+
+    /// Base of the blossom each vertex belongs to.
+    base: Vec<usize>,
+
+    /// Whether each vertex is in the alternating tree.
+    used: Vec<bool>,
+
+    /// Whether each vertex belongs to the blossom being contracted.
+    blossom: Vec<bool>,
+}
+
+/// Get a value of a vector, the index is always in bounds.
+fn at<T: Copy + Default>(values: &[T], index: usize) -> T {
+    values.get(index).copied().unwrap_or_default()
+}
+
+/// Set a value of a vector, the index is always in bounds.
+fn set<T>(values: &mut [T], index: usize, value: T) {
+    if let Some(i) = values.get_mut(index) {
+        *i = value;
+    }
+}
+
+impl Edmonds<'_> {
+    /// Lowest common ancestor of two vertices in the alternating tree.
+    fn lowest_common_ancestor(&self, mut a: usize, mut b: usize) -> usize {
+        let mut path = vec![false; self.size];
+
+        loop {
+            a = at(&self.base, a);
+            set(&mut path, a, true);
+            match at(&self.mate, a).and_then(|i| at(&self.parent, i)) {
+                Some(i) => a = i,
+                None => break,
+            }
+        }
+
+        loop {
+            b = at(&self.base, b);
+            if at(&path, b) {
+                return b;
+            }
+            match at(&self.mate, b).and_then(|i| at(&self.parent, i)) {
+                Some(i) => b = i,
+                None => return b,
+            }
+        }
+    }
+
+    /// Mark the path from `vertex` to the blossom base `base`.
+    fn mark_path(&mut self, mut vertex: usize, base: usize, mut child: usize) {
+        while at(&self.base, vertex) != base {
+            let Some(mate) = at(&self.mate, vertex) else {
+                return;
+            };
+            set(&mut self.blossom, at(&self.base, vertex), true);
+            set(&mut self.blossom, at(&self.base, mate), true);
+            set(&mut self.parent, vertex, Some(child));
+            child = mate;
+            match at(&self.parent, mate) {
+                Some(i) => vertex = i,
+                None => return,
+            }
+        }
+    }
+
+    /// Find an augmenting path starting at `root`, returns its last vertex.
+    fn find_path(&mut self, root: usize) -> Option<usize> {
+        self.used = vec![false; self.size];
+        self.parent = vec![None; self.size];
+        self.base = (0..self.size).collect();
+
+        set(&mut self.used, root, true);
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(vertex) = queue.pop_front() {
+            for to in 0..self.size {
+                if to == vertex
+                    || !(self.edge)(vertex, to)
+                    || at(&self.base, vertex) == at(&self.base, to)
+                    || at(&self.mate, vertex) == Some(to)
+                {
+                    continue;
+                }
+
+                if to == root
+                    || at(&self.mate, to).is_some_and(|i| at(&self.parent, i).is_some())
+                {
+                    let base = self.lowest_common_ancestor(vertex, to);
+                    self.blossom = vec![false; self.size];
+                    self.mark_path(vertex, base, to);
+                    self.mark_path(to, base, vertex);
+
+                    for i in 0..self.size {
+                        if at(&self.blossom, at(&self.base, i)) {
+                            set(&mut self.base, i, base);
+                            if !at(&self.used, i) {
+                                set(&mut self.used, i, true);
+                                queue.push_back(i);
+                            }
+                        }
+                    }
+                } else if at(&self.parent, to).is_none() {
+                    set(&mut self.parent, to, Some(vertex));
+                    match at(&self.mate, to) {
+                        None => return Some(to),
+                        Some(mate) => {
+                            set(&mut self.used, mate, true);
+                            queue.push_back(mate);
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Find a maximum matching in a graph of `size` vertices, where `edge` tells whether two
+/// vertices can be matched.
+///
+/// Returns the vertex each vertex is matched with.
+pub fn maximum_matching(
+    size: usize,
+    edge: &dyn Fn(usize, usize) -> bool,
+) -> Vec<Option<usize>> {
+    let mut state = Edmonds {
+        edge,
+        size,
+        mate: vec![None; size],
+        parent: vec![None; size],
+        base: (0..size).collect(),
+        used: vec![false; size],
+        blossom: vec![false; size],
+    };
+
+    for root in 0..size {
+        if at(&state.mate, root).is_some() {
+            continue;
+        }
+
+        // Augment along the path found, if any.
+        let mut vertex = state.find_path(root);
+        while let Some(i) = vertex {
+            let Some(parent) = at(&state.parent, i) else {
+                break;
+            };
+            let next = at(&state.mate, parent);
+            set(&mut state.mate, i, Some(parent));
+            set(&mut state.mate, parent, Some(i));
+            vertex = next;
+        }
+    }
+
+    state.mate
+}
@@ -0,0 +1,197 @@
+//! # Pairing engines
+//!
+//! This module contains the pairing systems which produce the next round of a
+//! [`crate::Situation`].
+//!
+//! ## Status
+//!
+//! The FIDE Dutch system (see [`dutch`]), the Burstein system (see [`burstein`]), the
+//! Dubov system (see [`dubov`]) and the Lim system (see [`lim`]) are supported,
+//! [`System`] chooses between them. Rounds which were already paired can be
+//! checked against the Dutch system with [`checker`].
+//!
+//! ## Usage
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::{pairing::{dutch, System}, Situation};
+//!
+//! let file_contents =
+//!     fs::read_to_string("lichess_swiss_2024.10.13_hId1KZmF_rapid.trf").unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//!
+//! // Pair the fourth round:
+//! let pairings = dutch::pair(&situation, 4).unwrap();
+//!
+//! for game in pairings.games() {
+//!     println!("{} - {}", game.white(), game.black());
+//! }
+//!
+//! // Or with the system chosen for the event:
+//! let pairings = System::Burstein.pair(&situation, 4).unwrap();
+//! ```
+
+use thiserror::Error;
+
+use crate::Situation;
+
+pub mod burstein;
+pub mod checker;
+pub mod dubov;
+pub mod dutch;
+pub(crate) mod history;
+pub mod lim;
+pub(crate) mod matching;
+
+/// An error in a pairing engine.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PairingError {
+    /// Error when the round to pair is invalid (rounds start at 1).
+    #[error("Expected a valid round number, found {0}")]
+    InvalidRoundError(u16),
+
+    /// Error when a player's starting rank number couldn't be read.
+    ///
+    /// Stores the index of the player in [`crate::Situation::players`].
+    #[error("The starting rank number of the player at index {0} is invalid")]
+    InvalidStartingRankNumberError(usize),
+
+    /// Error when no pairing satisfies the absolute criteria (no rematches, at most one
+    /// pairing-allocated bye per player, colour limits).
+    #[error("No pairing satisfies the absolute criteria")]
+    NoValidPairingError(),
+}
+
+/// A pairing system.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum System {
+    /// The FIDE Dutch system, see [`dutch`].
+    #[default]
+    Dutch,
+
+    /// The Burstein system, see [`burstein`].
+    Burstein,
+
+    /// The Dubov system, see [`dubov`].
+    Dubov,
+
+    /// The Lim system, see [`lim`].
+    Lim,
+}
+
+impl System {
+    /// Pair a round of a tournament with this system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the round is invalid, if a player's starting rank number is
+    /// invalid, or if no pairing satisfies the absolute criteria.
+    pub fn pair(
+        self,
+        situation: &Situation,
+        round: u16,
+    ) -> Result<Pairings, PairingError> {
+        match self {
+            Self::Dutch => dutch::pair(situation, round),
+            Self::Burstein => burstein::pair(situation, round),
+            Self::Dubov => dubov::pair(situation, round),
+            Self::Lim => lim::pair(situation, round),
+        }
+    }
+}
+
+/// A game between two players, identified by their starting rank numbers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Game {
+    /// Starting rank number of the player with white.
+    white: u16,
+
+    /// Starting rank number of the player with black.
+    black: u16,
+}
+
+impl Game {
+    /// Create a game.
+    #[must_use]
+    pub const fn new(white: u16, black: u16) -> Self {
+        Self { white, black }
+    }
+
+    /// Get the starting rank number of the player with white.
+    #[must_use]
+    pub const fn white(&self) -> u16 {
+        self.white
+    }
+
+    /// Get the starting rank number of the player with black.
+    #[must_use]
+    pub const fn black(&self) -> u16 {
+        self.black
+    }
+
+    /// Whether `player` plays in this game.
+    #[must_use]
+    pub const fn contains(&self, player: u16) -> bool {
+        self.white == player || self.black == player
+    }
+}
+
+/// The pairings of a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pairings {
+    /// The games, in board order.
+    games: Vec<Game>,
+
+    /// The player who receives the pairing-allocated bye, if any.
+    bye: Option<u16>,
+
+    /// Whether the engine settled for pairings which may not be the ones of the rules.
+    approximate: bool,
+}
+
+impl Pairings {
+    /// Create the pairings of a round.
+    #[must_use]
+    pub const fn new(games: Vec<Game>, bye: Option<u16>) -> Self {
+        Self {
+            games,
+            bye,
+            approximate: false,
+        }
+    }
+
+    /// Get the games, in board order.
+    #[must_use]
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Get the starting rank number of the player who receives the pairing-allocated
+    /// bye, if any.
+    #[must_use]
+    pub const fn bye(&self) -> Option<u16> {
+        self.bye
+    }
+
+    /// Whether the engine settled for pairings which may not be the ones of the rules.
+    ///
+    /// This happens when the search of the engine is cut short (see
+    /// [`dutch::CANDIDATE_LIMIT`]), or when players are paired with a maximum matching
+    /// because the rules of the system couldn't be followed. The pairings still satisfy
+    /// the absolute criteria.
+    #[must_use]
+    pub const fn approximate(&self) -> bool {
+        self.approximate
+    }
+
+    /// Mark the pairings as approximate, see [`Pairings::approximate`].
+    pub(crate) const fn set_approximate(&mut self, approximate: bool) {
+        self.approximate = approximate;
+    }
+
+    /// Get the game `player` plays in, if any.
+    #[must_use]
+    pub fn game(&self, player: u16) -> Option<&Game> {
+        self.games.iter().find(|game| game.contains(player))
+    }
+}
@@ -0,0 +1,454 @@
+//! # Round-robin
+//!
+//! This module schedules round-robin and double round-robin tournaments with the Berger
+//! tables of the FIDE Handbook (C.05, Annex 1), and checks that the rounds of a
+//! [`Situation`] follow them.
+//!
+//! Players are identified by their Berger number (their draw number, from 1). With an odd
+//! number of players, a dummy player is added and the player paired with it has a bye.
+//! In a double round-robin, the second cycle repeats the first one with the colours
+//! reversed, and the last two rounds of the first cycle are swapped, so that no player
+//! gets the same colour three times in a row.
+//!
+//! In a [`Situation`], the Berger numbers are allocated in the order of the starting rank
+//! numbers: the player with the lowest starting rank number has the Berger number 1.
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::{
+//! #     pairing::Game,
+//! #     roundrobin::{self, Cycles},
+//! #     Situation,
+//! # };
+//! // The first round of a round-robin between 6 players:
+//! let schedule = roundrobin::schedule(6, Cycles::Single);
+//! assert_eq!(schedule.len(), 5);
+//! assert_eq!(
+//!     schedule[0].games(),
+//!     [Game::new(1, 6), Game::new(2, 5), Game::new(3, 4)]
+//! );
+//!
+//! let file_contents = "\
+//! 001    1      Alpha, Anna                       2000
+//! 001    2      Bravo, Ben                        1900
+//! 001    3      Charlie, Cleo                     1800";
+//!
+//! let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! situation.schedule_round_robin(Cycles::Single);
+//!
+//! // Player 1 is paired with the dummy player in round 1:
+//! assert_eq!(
+//!     String::from(&situation.players()[0]),
+//!     "001    1      Alpha, Anna                       2000                                            - Z     2 w       3 b"
+//! );
+//! assert!(situation.validate().is_empty());
+//!
+//! assert_eq!(situation.round_robin(), Some(Cycles::Single));
+//! assert!(situation.verify_round_robin(Cycles::Single).is_empty());
+//! ```
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    pairing::{Game, Pairings},
+    trf::{
+        extension::Extensions,
+        player::round::{
+            ByeRoundResult, Color, PlayerRoundSection, Result as RoundResult,
+        },
+        record::RecordKind,
+    },
+    Situation,
+};
+
+/// The number of cycles of a round-robin: how many times each player meets every other
+/// player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Cycles {
+    /// Each player meets every other player once.
+    Single,
+
+    /// Each player meets every other player twice, once with each colour.
+    Double,
+}
+
+impl Cycles {
+    /// Get the number of times each player meets every other player.
+    #[must_use]
+    pub const fn count(self) -> u16 {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+        }
+    }
+}
+
+/// Get the number of rounds of a round-robin between `players` players.
+#[must_use]
+pub const fn number_of_rounds(players: u16, cycles: Cycles) -> u16 {
+    let rounds = if players % 2 == 1 {
+        players
+    } else {
+        players.saturating_sub(1)
+    };
+
+    rounds.saturating_mul(cycles.count())
+}
+
+/// Get a round of the Berger table for `size` players (an even number, including the
+/// dummy player), the round starts at 1.
+fn berger_round(size: u16, round: u16) -> Vec<Game> {
+    let size = i32::from(size);
+    let modulus = size - 1;
+    // Berger number from 1 to `modulus`.
+    let wrap = |number: i32| (number - 1).rem_euclid(modulus) + 1;
+    let number = |number: i32| u16::try_from(number).unwrap_or_default();
+
+    // The opponent of the last player is `k`, where 2k = round + 1 modulo `modulus`.
+    let k = wrap((i32::from(round) + 1) * (modulus + 1) / 2);
+    // The last player has white in even rounds.
+    let first = if round % 2 == 1 {
+        Game::new(number(k), number(size))
+    } else {
+        Game::new(number(size), number(k))
+    };
+
+    std::iter::once(first)
+        .chain((1..size / 2).map(|distance| {
+            Game::new(number(wrap(k + distance)), number(wrap(k - distance)))
+        }))
+        .collect()
+}
+
+/// Get the pairings of every round of a round-robin between `players` players, by Berger
+/// number.
+///
+/// With an odd number of players, the player paired with the dummy player receives the
+/// bye (see [`Pairings::bye`]).
+#[must_use]
+pub fn schedule(players: u16, cycles: Cycles) -> Vec<Pairings> {
+    if players < 2 {
+        return vec![];
+    }
+    let size = players + players % 2;
+
+    let cycle: Vec<Pairings> = (1..size)
+        .map(|round| {
+            let games = berger_round(size, round);
+            let bye = games
+                .iter()
+                .find(|game| game.contains(size) && size > players)
+                .map(|game| game.white() + game.black() - size);
+
+            Pairings::new(
+                games
+                    .into_iter()
+                    .filter(|game| !game.contains(size) || size == players)
+                    .collect(),
+                bye,
+            )
+        })
+        .collect();
+
+    match cycles {
+        Cycles::Single => cycle,
+        Cycles::Double => {
+            let mut first = cycle.clone();
+            let length = first.len();
+            if length >= 2 {
+                first.swap(length - 2, length - 1);
+            }
+
+            first
+                .into_iter()
+                .chain(cycle.iter().map(|pairings| {
+                    Pairings::new(
+                        pairings
+                            .games()
+                            .iter()
+                            .map(|game| Game::new(game.black(), game.white()))
+                            .collect(),
+                        pairings.bye(),
+                    )
+                }))
+                .collect()
+        }
+    }
+}
+
+/// A round of a player which doesn't follow the Berger tables.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Deviation {
+    /// The round, starting at 1.
+    round: u16,
+
+    /// Starting rank number of the player.
+    player: u16,
+
+    /// The starting rank number of the opponent and the colour of the player in the
+    /// Berger tables, [`None`] for the bye.
+    expected: Option<(u16, Color)>,
+
+    /// The starting rank number of the opponent and the colour of the player found in the
+    /// round, [`None`] if the player has no opponent.
+    found: Option<(u16, Color)>,
+}
+
+impl Deviation {
+    /// Get the round, starting at 1.
+    #[must_use]
+    pub const fn round(&self) -> u16 {
+        self.round
+    }
+
+    /// Get the starting rank number of the player.
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the starting rank number of the opponent and the colour of the player in the
+    /// Berger tables, [`None`] for the bye.
+    #[must_use]
+    pub const fn expected(&self) -> Option<(u16, Color)> {
+        self.expected
+    }
+
+    /// Get the starting rank number of the opponent and the colour of the player found in
+    /// the round, [`None`] if the player has no opponent.
+    #[must_use]
+    pub const fn found(&self) -> Option<(u16, Color)> {
+        self.found
+    }
+}
+
+impl Display for Deviation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let describe = |assignment: Option<(u16, Color)>| {
+            assignment.map_or_else(
+                || "no opponent".to_string(),
+                |(opponent, color)| format!("{opponent} {}", String::from(color)),
+            )
+        };
+
+        write!(
+            f,
+            "Round {}, player {}: expected {}, found {}",
+            self.round,
+            self.player,
+            describe(self.expected),
+            describe(self.found)
+        )
+    }
+}
+
+impl Situation {
+    /// Get the starting rank numbers of the players, in Berger number order.
+    fn berger_numbers(&self) -> Vec<u16> {
+        let mut ids: Vec<u16> = self
+            .players
+            .iter()
+            .filter_map(|player| player.starting_rank_number().ok().copied())
+            .collect();
+        ids.sort_unstable();
+
+        ids
+    }
+
+    /// Get the round of every player in the Berger tables, by round then starting rank
+    /// number: the opponent and the colour, [`None`] for the bye.
+    fn berger_tables(&self, cycles: Cycles) -> Vec<HashMap<u16, Option<(u16, Color)>>> {
+        let ids = self.berger_numbers();
+        let id = |number: u16| {
+            ids.get(usize::from(number) - 1)
+                .copied()
+                .unwrap_or_default()
+        };
+        let players = u16::try_from(ids.len()).unwrap_or(u16::MAX);
+
+        schedule(players, cycles)
+            .iter()
+            .map(|pairings| {
+                pairings
+                    .games()
+                    .iter()
+                    .flat_map(|game| {
+                        let (white, black) = (id(game.white()), id(game.black()));
+                        [
+                            (white, Some((black, Color::White))),
+                            (black, Some((white, Color::Black))),
+                        ]
+                    })
+                    .chain(pairings.bye().map(|number| (id(number), None)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Set the number of rounds of the tournament: the `JaVaFo` extension (XXR), and the
+    /// number of rounds planned (DIN 142) if it is declared.
+    fn set_round_robin_rounds(&mut self, rounds: u16) {
+        if self
+            .tournament
+            .number_of_rounds()
+            .is_ok_and(Option::is_some)
+        {
+            self.tournament.set_number_of_rounds(Some(rounds));
+        }
+        self.extensions.set_number_of_rounds(rounds);
+
+        self.insert_header_record(RecordKind::Extension(
+            Extensions::NUMBER_OF_ROUNDS.to_string(),
+            0,
+        ));
+    }
+
+    /// Fill in the rounds of every player with the Berger tables.
+    ///
+    /// Games are scheduled without a result, and the player paired with the dummy player
+    /// gets a zero-point bye. Rounds which are already filled aren't changed, and players
+    /// whose starting rank number is invalid are ignored. The number of rounds of the
+    /// tournament is set to the number of rounds of the round-robin (see
+    /// [`Situation::number_of_rounds`]).
+    ///
+    /// In a double round-robin, the last two rounds of the first cycle are swapped, and
+    /// the second cycle has the colours reversed:
+    ///
+    /// ```
+    /// # use openswisspairings_lib::{roundrobin::Cycles, Situation};
+    /// let file_contents = "\
+    /// 012 Double round-robin
+    /// 001    1      Alpha, Anna                       2000
+    /// 001    2      Bravo, Ben                        1900
+    /// 001    3      Charlie, Cleo                     1800
+    /// 001    4      Delta, Dan                        1700";
+    ///
+    /// let mut situation = Situation::try_from(file_contents.to_string()).unwrap();
+    /// situation.schedule_round_robin(Cycles::Double);
+    ///
+    /// assert_eq!(situation.number_of_rounds(), Some(6));
+    /// assert!(String::from(&situation).contains("XXR 6"));
+    ///
+    /// // Rounds 2 and 3 of the Berger tables are swapped, round 4 reverses round 1:
+    /// assert_eq!(
+    ///     String::from(&situation.players()[0]),
+    ///     "001    1      Alpha, Anna                       2000                                          4 w       3 b       2 w       4 b       2 b       3 w"
+    /// );
+    /// assert!(situation.verify_round_robin(Cycles::Double).is_empty());
+    /// ```
+    pub fn schedule_round_robin(&mut self, cycles: Cycles) {
+        let players = u16::try_from(self.berger_numbers().len()).unwrap_or(u16::MAX);
+        if players >= 2 {
+            self.set_round_robin_rounds(number_of_rounds(players, cycles));
+        }
+
+        for (round, assignments) in (1..).zip(self.berger_tables(cycles)) {
+            for (id, assignment) in assignments {
+                let Some(player) = self.player_mut(id) else {
+                    continue;
+                };
+                let index = usize::from(round - 1);
+                if player.rounds().get(index).is_some_and(Option::is_some) {
+                    continue;
+                }
+
+                let section = assignment.map_or_else(
+                    || {
+                        PlayerRoundSection::new(
+                            None,
+                            Some(Color::None),
+                            Some(RoundResult::Bye(ByeRoundResult::ZeroPointBye)),
+                        )
+                    },
+                    |(opponent, color)| {
+                        PlayerRoundSection::new(Some(opponent), Some(color), None)
+                    },
+                );
+                player.set_round(round, Some(section));
+            }
+        }
+    }
+
+    /// Detect whether the situation is a round-robin.
+    ///
+    /// The situation is a round-robin if it has the number of rounds of a round-robin
+    /// between its players (see [`Situation::number_of_rounds`], or the highest number of
+    /// rounds of a player), and no two players met more often than the number of cycles.
+    /// It doesn't mean that the rounds follow the Berger tables, see
+    /// [`Situation::verify_round_robin`].
+    #[must_use]
+    pub fn round_robin(&self) -> Option<Cycles> {
+        let players = u16::try_from(self.berger_numbers().len()).ok()?;
+        let rounds = self.number_of_rounds().or_else(|| {
+            let rounds = self
+                .players
+                .iter()
+                .map(|player| player.rounds().len())
+                .max();
+            rounds.and_then(|rounds| u16::try_from(rounds).ok())
+        })?;
+
+        let mut meetings: HashMap<(u16, u16), u16> = HashMap::new();
+        for player in &self.players {
+            let Ok(id) = player.starting_rank_number() else {
+                continue;
+            };
+            for section in player.rounds().iter().flatten() {
+                if let Ok(Some(opponent)) = section.id() {
+                    *meetings.entry((*id, *opponent)).or_default() += 1;
+                }
+            }
+        }
+
+        [Cycles::Single, Cycles::Double].into_iter().find(|cycles| {
+            players >= 2
+                && rounds == number_of_rounds(players, *cycles)
+                && meetings.values().all(|count| *count <= cycles.count())
+        })
+    }
+
+    /// Verify that the rounds of every player follow the Berger tables.
+    ///
+    /// Only rounds which are filled are checked, so that a round-robin in progress can be
+    /// verified. Returns the rounds which don't follow the tables.
+    #[must_use]
+    pub fn verify_round_robin(&self, cycles: Cycles) -> Vec<Deviation> {
+        let mut deviations = vec![];
+
+        for (round, assignments) in (1..).zip(self.berger_tables(cycles)) {
+            let mut assignments: Vec<(u16, Option<(u16, Color)>)> =
+                assignments.into_iter().collect();
+            assignments.sort_unstable_by_key(|(id, _)| *id);
+
+            for (id, expected) in assignments {
+                let section = self
+                    .players
+                    .iter()
+                    .find(|player| player.starting_rank_number().is_ok_and(|i| *i == id))
+                    .and_then(|player| player.rounds().get(usize::from(round - 1)))
+                    .and_then(Option::as_ref);
+                let Some(section) = section else {
+                    continue;
+                };
+
+                let found = section.id().ok().copied().flatten().map(|opponent| {
+                    let color = section.color().ok().copied().flatten();
+                    (opponent, color.unwrap_or(Color::None))
+                });
+                if found != expected {
+                    deviations.push(Deviation {
+                        round,
+                        player: id,
+                        expected,
+                        found,
+                    });
+                }
+            }
+        }
+
+        deviations
+    }
+}
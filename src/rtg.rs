@@ -0,0 +1,308 @@
+//! # Random Tournament Generator
+//!
+//! Pairing programs are tested by simulating thousands of tournaments and pairing every
+//! round with the engine (this is required for the FIDE endorsement of a pairing
+//! program). This module generates such tournaments:
+//! - ratings are drawn from a normal distribution, see [`Config`],
+//! - each round is paired with [`dutch::pair`],
+//! - results are drawn with the Elo model, with draws, forfeits and half-point byes.
+//!
+//! The generation only depends on [`Config`], so a tournament which breaks the engine
+//! can be generated again from its seed to reproduce the problem. The random number
+//! generator is included, to make sure the same seed always gives the same tournament.
+//!
+//! ## Usage
+//!
+//! ```
+//! use openswisspairings_lib::{
+//!     rtg::{self, Config},
+//!     Situation,
+//! };
+//!
+//! let config = Config {
+//!     players: 21,
+//!     rounds: 7,
+//!     seed: 42,
+//!     ..Config::default()
+//! };
+//! let file_contents = String::from(&rtg::generate(&config).unwrap());
+//!
+//! // The same seed always gives the same tournament:
+//! assert_eq!(String::from(&rtg::generate(&config).unwrap()), file_contents);
+//!
+//! let situation = Situation::try_from(file_contents).unwrap();
+//! assert_eq!(situation.players().len(), 21);
+//! assert_eq!(situation.players()[0].rounds().len(), 7);
+//! ```
+use std::f64::consts::TAU;
+
+use crate::{
+    pairing::{dutch, PairingError},
+    trf::{
+        player::{
+            fields::{Date, Name},
+            round::{
+                ByeRoundResult, Color, NotPlayedRoundResult, PlayerRoundSection,
+                RegularRoundResult, Result as RoundResult,
+            },
+            Section,
+        },
+        tournament::TournamentData,
+    },
+    Situation,
+};
+
+/// Settings of a generated tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Number of players.
+    pub players: u16,
+
+    /// Number of rounds.
+    pub rounds: u16,
+
+    /// Seed of the random number generator.
+    pub seed: u64,
+
+    /// Mean of the ratings.
+    pub rating_mean: f64,
+
+    /// Standard deviation of the ratings.
+    pub rating_deviation: f64,
+
+    /// Lowest rating, lower ratings are raised to it.
+    pub min_rating: u16,
+
+    /// Highest rating, higher ratings are lowered to it.
+    pub max_rating: u16,
+
+    /// Probability of a draw between two players with the same rating.
+    ///
+    /// Draws are less likely as the rating difference grows.
+    pub draw_rate: f64,
+
+    /// Probability of a game being forfeited, by either player.
+    pub forfeit_rate: f64,
+
+    /// Probability of a player taking a half-point bye in a round.
+    pub bye_rate: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            players: 40,
+            rounds: 9,
+            seed: 0,
+            rating_mean: 1800.0,
+            rating_deviation: 250.0,
+            min_rating: 1400,
+            max_rating: 2800,
+            draw_rate: 0.3,
+            forfeit_rate: 0.01,
+            bye_rate: 0.02,
+        }
+    }
+}
+
+/// `SplitMix64` pseudo-random number generator.
+struct Random {
+    /// Current state.
+    state: u64,
+}
+
+impl Random {
+    /// Get the next random number.
+    const fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random number in `[0, 1)`.
+    fn uniform(&mut self) -> f64 {
+        // Use the 52 highest bits as the mantissa of a number in [1, 2).
+        f64::from_bits(0x3FF0_0000_0000_0000 | (self.next() >> 12)) - 1.0
+    }
+
+    /// Whether an event with the given probability happens.
+    fn chance(&mut self, probability: f64) -> bool {
+        self.uniform() < probability
+    }
+
+    /// Get a random number from the standard normal distribution (Box-Muller transform).
+    fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        let v = self.uniform();
+        (-2.0 * u.ln()).sqrt() * (TAU * v).cos()
+    }
+
+    /// Get a random rating.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to u16
+    fn rating(&mut self, config: &Config) -> u16 {
+        (config
+            .rating_deviation
+            .mul_add(self.normal(), config.rating_mean))
+        .round()
+        .clamp(f64::from(config.min_rating), f64::from(config.max_rating)) as u16
+    }
+
+    /// Draw the results of a game with the Elo model.
+    fn play(
+        &mut self,
+        config: &Config,
+        white: u16,
+        black: u16,
+    ) -> (RoundResult, RoundResult) {
+        if self.chance(config.forfeit_rate) {
+            let win = RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitWin);
+            let loss = RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitLoss);
+            return if self.chance(0.5) {
+                (win, loss)
+            } else {
+                (loss, win)
+            };
+        }
+
+        let expected =
+            1.0 / (1.0 + 10_f64.powf((f64::from(black) - f64::from(white)) / 400.0));
+        let draw = config.draw_rate * 2.0 * expected.min(1.0 - expected);
+        let value = self.uniform();
+
+        let (white, black) = if value < draw.mul_add(-0.5, expected) {
+            (RegularRoundResult::Win, RegularRoundResult::Loss)
+        } else if value < draw.mul_add(0.5, expected) {
+            (RegularRoundResult::Draw, RegularRoundResult::Draw)
+        } else {
+            (RegularRoundResult::Loss, RegularRoundResult::Win)
+        };
+
+        (RoundResult::Regular(white), RoundResult::Regular(black))
+    }
+}
+
+/// Get the date of a round, one round per day starting from 24/01/01.
+fn round_date(round: u16) -> Date {
+    let month = u8::try_from(round / 28 % 12).unwrap_or_default();
+    let day = u8::try_from(round % 28).unwrap_or_default();
+
+    Date::new(24, month + 1, day + 1)
+}
+
+/// Generate a random tournament.
+///
+/// Players are given starting rank numbers by descending rating. Each player has a
+/// [`Config::bye_rate`] probability of taking a half-point bye before each round is
+/// paired, and points and ranks are set once every round was played.
+///
+/// # Errors
+///
+/// Returns an error if the pairing engine fails to pair a round. Generating the
+/// tournament again with the same [`Config`] gives the same error.
+pub fn generate(config: &Config) -> Result<Situation, PairingError> {
+    let mut random = Random { state: config.seed };
+
+    let mut ratings: Vec<u16> =
+        (0..config.players).map(|_| random.rating(config)).collect();
+    ratings.sort_unstable_by(|a, b| b.cmp(a));
+    let rating = |id: u16| {
+        ratings
+            .get(usize::from(id) - 1)
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let mut tournament = TournamentData::default();
+    tournament.set_name(Some(format!("Random tournament {}", config.seed)));
+    tournament.set_number_of_players(Some(config.players));
+    tournament.set_number_of_rated_players(Some(config.players));
+    tournament.set_tournament_type(Some("Individual: Swiss-System".to_string()));
+    tournament.set_round_dates((0..config.rounds).map(|i| Some(round_date(i))).collect());
+
+    let players = (1..=config.players)
+        .map(|id| {
+            let mut player = Section::new(id);
+            player.set_name(Some(Name::new("Player", &id.to_string())));
+            player.set_fide_rating(Some(rating(id)));
+            player
+        })
+        .collect();
+    let mut situation = Situation::new(tournament, players, vec![]);
+
+    for round in 1..=config.rounds {
+        // Half-point byes are requested before the round is paired.
+        for player in situation.players_mut() {
+            if random.chance(config.bye_rate) {
+                player.set_round(
+                    round,
+                    Some(PlayerRoundSection::new(
+                        None,
+                        Some(Color::None),
+                        Some(RoundResult::Bye(ByeRoundResult::HalfPointBye)),
+                    )),
+                );
+            }
+        }
+
+        let pairings = dutch::pair(&situation, round)?;
+
+        let mut sections: Vec<(u16, PlayerRoundSection)> = vec![];
+        for game in pairings.games() {
+            let (white, black) =
+                random.play(config, rating(game.white()), rating(game.black()));
+            sections.push((
+                game.white(),
+                PlayerRoundSection::new(
+                    Some(game.black()),
+                    Some(Color::White),
+                    Some(white),
+                ),
+            ));
+            sections.push((
+                game.black(),
+                PlayerRoundSection::new(
+                    Some(game.white()),
+                    Some(Color::Black),
+                    Some(black),
+                ),
+            ));
+        }
+        if let Some(bye) = pairings.bye() {
+            sections.push((
+                bye,
+                PlayerRoundSection::new(
+                    None,
+                    Some(Color::None),
+                    Some(RoundResult::Bye(ByeRoundResult::PairingAllocatedBye)),
+                ),
+            ));
+        }
+
+        for (id, section) in sections {
+            if let Some(player) = situation.players_mut().get_mut(usize::from(id) - 1) {
+                player.set_round(round, Some(section));
+            }
+        }
+    }
+
+    // Rank players by points, then by starting rank number.
+    let scores: Vec<f32> = situation
+        .players()
+        .iter()
+        .map(|player| situation.scoring_system().score(player))
+        .collect();
+    let score = |index: usize| scores.get(index).copied().unwrap_or_default();
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|a, b| score(*b).total_cmp(&score(*a)).then(a.cmp(b)));
+
+    for (rank, index) in (1..).zip(order) {
+        if let Some(player) = situation.players_mut().get_mut(index) {
+            player.set_points(Some(score(index)));
+            player.set_rank(Some(rank));
+        }
+    }
+
+    Ok(situation)
+}
@@ -0,0 +1,276 @@
+//! # Standings
+//!
+//! This module ranks the players of a tournament by points, then by an ordered list of
+//! tie-breaks (see [`crate::tiebreak`]). Players tied on points and on every tie-break
+//! share their place.
+//!
+//! The ranks read from the file ([`crate::trf::player::Section::rank`]) can be checked
+//! against the computed standings with [`Standings::verify_ranks`].
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::{standings::Standings, tiebreak::Tiebreak, Situation};
+//! let file_contents = "\
+//! 001    1      Alpha, Anna                       2000                             2.5    1     4 w 1     3 b =     2 w 1
+//! 001    2      Bravo, Ben                        1900                             1.5    3     3 w =     4 b 1     1 b 0
+//! 001    3      Charlie, Cleo                     1800                             2.0    2     2 b =     1 w =     4 w 1
+//! 001    4      Delta, Dan                        1700                             0.0    4     1 b 0     2 w 0     3 b 0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let standings = Standings::new(&situation, &[Tiebreak::Buchholz]);
+//!
+//! let ranking: Vec<u16> = standings.rows().iter().map(|row| row.player()).collect();
+//! assert_eq!(ranking, [1, 3, 2, 4]);
+//! assert!(standings.verify_ranks(&situation).is_empty());
+//! ```
+use crate::{
+    tiebreak::{Tiebreak, Tiebreaks},
+    Situation,
+};
+
+/// A row of the standings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// Starting rank number of the player.
+    player: u16,
+
+    /// First place of the player, starting at 1.
+    place: u16,
+
+    /// Last place of the player, different from the first place if it is shared.
+    last_place: u16,
+
+    /// Total points.
+    points: f32,
+
+    /// Value of each tie-break, in the order they were given.
+    tiebreaks: Vec<f32>,
+}
+
+impl Row {
+    /// Get the starting rank number of the player.
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the place of the player, starting at 1.
+    ///
+    /// If the place is shared, this is the first of the shared places.
+    #[must_use]
+    pub const fn place(&self) -> u16 {
+        self.place
+    }
+
+    /// Get the last place of the player.
+    ///
+    /// This is the same as [`Row::place`] unless the place is shared.
+    #[must_use]
+    pub const fn last_place(&self) -> u16 {
+        self.last_place
+    }
+
+    /// Whether the place is shared with other players.
+    #[must_use]
+    pub const fn shared(&self) -> bool {
+        self.place != self.last_place
+    }
+
+    /// Get the total points of the player.
+    #[must_use]
+    pub const fn points(&self) -> f32 {
+        self.points
+    }
+
+    /// Get the value of each tie-break, in the order they were given.
+    #[must_use]
+    pub fn tiebreaks(&self) -> &[f32] {
+        &self.tiebreaks
+    }
+}
+
+/// A player whose rank in the file doesn't match the standings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RankMismatch {
+    /// Starting rank number of the player.
+    player: u16,
+
+    /// Rank read from the file, [`None`] if it is invalid.
+    found: Option<u16>,
+
+    /// First place of the player in the standings.
+    place: u16,
+
+    /// Last place of the player in the standings.
+    last_place: u16,
+}
+
+impl RankMismatch {
+    /// Get the starting rank number of the player.
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the rank read from the file, [`None`] if it is invalid.
+    #[must_use]
+    pub const fn found(&self) -> Option<u16> {
+        self.found
+    }
+
+    /// Get the first place of the player in the standings.
+    #[must_use]
+    pub const fn place(&self) -> u16 {
+        self.place
+    }
+
+    /// Get the last place of the player in the standings.
+    #[must_use]
+    pub const fn last_place(&self) -> u16 {
+        self.last_place
+    }
+}
+
+/// The standings of a tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standings {
+    /// The tie-breaks used, in order.
+    tiebreaks: Vec<Tiebreak>,
+
+    /// The rows, best player first.
+    rows: Vec<Row>,
+}
+
+impl Standings {
+    /// Compute the standings of a situation.
+    ///
+    /// Players are ranked by points, then by each tie-break in order (higher is better).
+    /// Tie-breaks are computed for every player, [`Tiebreak::DirectEncounter`] is
+    /// computed among the players still tied when it is applied. Players tied on
+    /// everything share their place and are listed by starting rank number.
+    ///
+    /// Players whose starting rank number is invalid are ignored.
+    #[must_use]
+    pub fn new(situation: &Situation, tiebreaks: &[Tiebreak]) -> Self {
+        let values = Tiebreaks::new(situation);
+
+        let mut rows: Vec<Row> = situation
+            .players()
+            .iter()
+            .filter_map(|player| player.starting_rank_number().ok().copied())
+            .map(|player| Row {
+                player,
+                place: 0,
+                last_place: 0,
+                points: values.score(player),
+                tiebreaks: vec![],
+            })
+            .collect();
+        rows.sort_by(|a, b| b.points.total_cmp(&a.points).then(a.player.cmp(&b.player)));
+
+        // Split the players into groups of tied players, one tie-break at a time.
+        let mut groups: Vec<Vec<Row>> = Self::split(rows, |row| row.points);
+        for tiebreak in tiebreaks {
+            groups = groups
+                .into_iter()
+                .flat_map(|mut group| {
+                    let tied: Vec<u16> = group.iter().map(|row| row.player).collect();
+                    for row in &mut group {
+                        row.tiebreaks
+                            .push(values.value(*tiebreak, row.player, &tied));
+                    }
+
+                    let value =
+                        |row: &Row| row.tiebreaks.last().copied().unwrap_or_default();
+                    group.sort_by(|a, b| value(b).total_cmp(&value(a)));
+                    Self::split(group, value)
+                })
+                .collect();
+        }
+
+        let mut place: u16 = 1;
+        let mut rows = vec![];
+        for group in groups {
+            let size = u16::try_from(group.len()).unwrap_or(u16::MAX);
+            rows.extend(group.into_iter().map(|row| Row {
+                place,
+                last_place: place.saturating_add(size - 1),
+                ..row
+            }));
+            place = place.saturating_add(size);
+        }
+
+        Self {
+            tiebreaks: tiebreaks.to_vec(),
+            rows,
+        }
+    }
+
+    /// Split sorted rows into groups of rows with the same value.
+    fn split(rows: Vec<Row>, value: impl Fn(&Row) -> f32) -> Vec<Vec<Row>> {
+        let mut groups: Vec<Vec<Row>> = vec![];
+
+        for row in rows {
+            match groups.last_mut() {
+                Some(group)
+                    if group.last().is_some_and(|last| {
+                        value(last).total_cmp(&value(&row)).is_eq()
+                    }) =>
+                {
+                    group.push(row);
+                }
+                _ => groups.push(vec![row]),
+            }
+        }
+
+        groups
+    }
+
+    /// Get the tie-breaks used, in order.
+    #[must_use]
+    pub fn tiebreaks(&self) -> &[Tiebreak] {
+        &self.tiebreaks
+    }
+
+    /// Get the rows, best player first.
+    #[must_use]
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Get the row of a player.
+    #[must_use]
+    pub fn row(&self, player: u16) -> Option<&Row> {
+        self.rows.iter().find(|row| row.player == player)
+    }
+
+    /// Check the ranks read from the file against the standings.
+    ///
+    /// A rank matches if it is one of the places of the player (any of the shared places
+    /// if the place is shared). Returns the players whose rank doesn't match or is
+    /// invalid, empty ranks aren't checked.
+    #[must_use]
+    pub fn verify_ranks(&self, situation: &Situation) -> Vec<RankMismatch> {
+        situation
+            .players()
+            .iter()
+            .filter_map(|player| {
+                let row = self.row(*player.starting_rank_number().ok()?)?;
+                let found = match player.rank() {
+                    Ok(None) => return None,
+                    Ok(Some(rank)) => Some(*rank),
+                    Err(_) => None,
+                };
+
+                (!found.is_some_and(|rank| (row.place..=row.last_place).contains(&rank)))
+                    .then_some(RankMismatch {
+                        player: row.player,
+                        found,
+                        place: row.place,
+                        last_place: row.last_place,
+                    })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,475 @@
+//! # Tie-breaks
+//!
+//! This module computes the tie-breaks of C.07 of the FIDE Handbook from the rounds of
+//! each player: <https://handbook.fide.com/chapter/C07Regulations>
+//!
+//! ## Unplayed games
+//!
+//! Unplayed games (forfeits, byes, absences) are handled with the FIDE virtual opponent
+//! rules:
+//! - when a player has no opponent in a round, they are considered to have played a
+//!   virtual opponent whose score is `SPR + (1 - SfPR) + 0.5 * (n - R)`, where `SPR` is
+//!   the score of the player before round `R`, `SfPR` the points the player got in round
+//!   `R` and `n` the number of rounds,
+//! - when computing the score of an opponent, the unplayed games of that opponent are
+//!   counted as draws.
+//!
+//! Points use the scoring system of the situation (see
+//! [`crate::trf::scoring::ScoringSystem`]), the 1 and 0.5 above being the points for a
+//! win and for a draw.
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::{tiebreak::{Tiebreak, Tiebreaks}, Situation};
+//! let file_contents = "\
+//! 001    1      Alpha, Anna                       2000                             2.5    1     4 w 1     3 b =     2 w 1
+//! 001    2      Bravo, Ben                        1900                             1.5    3     3 w =     4 b 1     1 b 0
+//! 001    3      Charlie, Cleo                     1800                             2.0    2     2 b =     1 w =     4 w 1
+//! 001    4      Delta, Dan                        1700                             0.0    4     1 b 0     2 w 0     3 b 0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let tiebreaks = Tiebreaks::new(&situation);
+//!
+//! assert_eq!(tiebreaks.value(Tiebreak::Buchholz, 1, &[]), 3.5);
+//! assert_eq!(tiebreaks.value(Tiebreak::SonnebornBerger, 1, &[]), 2.5);
+//! assert_eq!(tiebreaks.value(Tiebreak::ProgressiveScore, 1, &[]), 5.0);
+//! assert_eq!(tiebreaks.value(Tiebreak::PerformanceRating, 1, &[]), 2073.0);
+//! ```
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    trf::player::round::{
+        Color, LessThanOneMoveRoundResult, PlayerRoundSection, RegularRoundResult,
+        Result as RoundResult,
+    },
+    trf::scoring::ScoringSystem,
+    trf::TRFError,
+    Situation,
+};
+
+/// A tie-break criterion, see C.07.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// Sum of the scores of the opponents.
+    Buchholz,
+
+    /// Buchholz without the given number of lowest opponent scores.
+    BuchholzCut(usize),
+
+    /// Buchholz without the given number of lowest and highest opponent scores.
+    MedianBuchholz(usize),
+
+    /// Sum of the scores of the defeated opponents, and half the scores of the drawn
+    /// opponents.
+    SonnebornBerger,
+
+    /// Sum of the scores after each round.
+    ProgressiveScore,
+
+    /// Points scored against the other tied players, if they all played each other.
+    DirectEncounter,
+
+    /// Number of games won over the board.
+    Wins,
+
+    /// Number of games played with black.
+    BlackGames,
+
+    /// Average rating of the opponents, rounded.
+    AverageRatingOfOpponents,
+
+    /// Tournament performance rating: average rating of the opponents plus the rating
+    /// difference corresponding to the percentage scored (FIDE table of B.02).
+    PerformanceRating,
+}
+
+impl Display for Tiebreak {
+    /// Write the FIDE acronym of the tie-break.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Buchholz => write!(f, "BH"),
+            Self::BuchholzCut(cut) => write!(f, "BH-C{cut}"),
+            Self::MedianBuchholz(cut) => write!(f, "BH-M{cut}"),
+            Self::SonnebornBerger => write!(f, "SB"),
+            Self::ProgressiveScore => write!(f, "PS"),
+            Self::DirectEncounter => write!(f, "DE"),
+            Self::Wins => write!(f, "WON"),
+            Self::BlackGames => write!(f, "BPG"),
+            Self::AverageRatingOfOpponents => write!(f, "ARO"),
+            Self::PerformanceRating => write!(f, "TPR"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Tiebreak {
+    type Error = TRFError;
+
+    /// Read the FIDE acronym of a tie-break, e.g. `BH-C1`.
+    ///
+    /// This is the format of the tie-breaks record (DIN 202), see
+    /// [`crate::trf::tournament::TournamentData::tiebreaks`].
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let cut = |cut: &str| {
+            cut.parse::<usize>()
+                .map_err(|_| TRFError::UnknownKeywordError(value.to_string()))
+        };
+
+        Ok(match value.trim().to_uppercase().as_str() {
+            "BH" => Self::Buchholz,
+            "SB" => Self::SonnebornBerger,
+            "PS" => Self::ProgressiveScore,
+            "DE" => Self::DirectEncounter,
+            "WON" => Self::Wins,
+            "BPG" => Self::BlackGames,
+            "ARO" => Self::AverageRatingOfOpponents,
+            "TPR" => Self::PerformanceRating,
+            other => {
+                if let Some(n) = other.strip_prefix("BH-C") {
+                    Self::BuchholzCut(cut(n)?)
+                } else if let Some(n) = other.strip_prefix("BH-M") {
+                    Self::MedianBuchholz(cut(n)?)
+                } else {
+                    return Err(TRFError::UnknownKeywordError(value.to_string()));
+                }
+            }
+        })
+    }
+}
+
+/// Rating difference for each percentage scored, from 50% to 100% (FIDE table of B.02).
+const RATING_DIFFERENCES: [u16; 51] = [
+    0, 7, 14, 21, 29, 36, 43, 50, 57, 65, 72, 80, 87, 95, 102, 110, 117, 125, 133, 141,
+    149, 158, 166, 175, 184, 193, 202, 211, 220, 230, 240, 251, 262, 273, 284, 296, 309,
+    322, 336, 351, 366, 383, 401, 422, 444, 470, 501, 538, 589, 677, 800,
+];
+
+/// What a player did in a single round.
+#[derive(Debug)]
+struct Round {
+    /// Opponent, if the game was played.
+    opponent: Option<u16>,
+
+    /// Colour, if the game was played.
+    color: Option<Color>,
+
+    /// Result, if any.
+    result: Option<RoundResult>,
+
+    /// Points scored in the round.
+    points: f32,
+}
+
+impl Round {
+    /// Read a round section, an empty or unreadable section gives an unplayed round
+    /// without points.
+    fn read(
+        section: Option<&PlayerRoundSection>,
+        scoring_system: &ScoringSystem,
+    ) -> Self {
+        let opponent = section.and_then(|i| i.id().ok().copied().flatten());
+        let color = section
+            .and_then(|i| i.color().ok().copied().flatten())
+            .filter(|color| *color != Color::None);
+        let result = section.and_then(|i| i.result().ok().copied().flatten());
+        let played = opponent.is_some()
+            && color.is_some()
+            && matches!(
+                result,
+                Some(RoundResult::Regular(_) | RoundResult::LessThanOneMove(_))
+            );
+
+        Self {
+            opponent: opponent.filter(|_| played),
+            color: color.filter(|_| played),
+            result,
+            points: result.map_or(0.0, |result| scoring_system.points(result)),
+        }
+    }
+}
+
+/// A player, as needed to compute tie-breaks.
+#[derive(Debug)]
+struct Player {
+    /// Starting rank number.
+    id: u16,
+
+    /// FIDE rating.
+    rating: Option<u16>,
+
+    /// Rounds of the player, unread rounds are empty.
+    rounds: Vec<Round>,
+}
+
+impl Player {
+    /// Total points.
+    fn score(&self) -> f32 {
+        self.rounds.iter().map(|round| round.points).sum()
+    }
+
+    /// Points with every unplayed game counted as a draw, used when the player is an
+    /// opponent.
+    fn adjusted_score(&self, draw: f32) -> f32 {
+        self.rounds
+            .iter()
+            .map(|round| {
+                if round.opponent.is_some() {
+                    round.points
+                } else {
+                    draw
+                }
+            })
+            .sum()
+    }
+}
+
+/// Tie-breaks of every player of a tournament.
+#[derive(Debug)]
+pub struct Tiebreaks {
+    /// The players with a valid starting rank number.
+    players: Vec<Player>,
+
+    /// Number of rounds.
+    rounds: usize,
+
+    /// The points given for each round result.
+    scoring_system: ScoringSystem,
+}
+
+impl Tiebreaks {
+    /// Read the rounds of every player of a situation.
+    ///
+    /// Players whose starting rank number is invalid are ignored. The number of rounds is
+    /// the highest number of rounds of a player.
+    #[must_use]
+    pub fn new(situation: &Situation) -> Self {
+        let rounds = situation
+            .players()
+            .iter()
+            .map(|player| player.rounds().len())
+            .max()
+            .unwrap_or_default();
+
+        Self::with_rounds(situation, rounds)
+    }
+
+    /// Read the rounds of every player of a situation before `round`, e.g. to rank the
+    /// players when pairing that round.
+    #[must_use]
+    pub fn before(situation: &Situation, round: u16) -> Self {
+        Self::with_rounds(situation, usize::from(round.saturating_sub(1)))
+    }
+
+    /// Read the first `rounds` rounds of every player of a situation.
+    fn with_rounds(situation: &Situation, rounds: usize) -> Self {
+        let players = situation
+            .players()
+            .iter()
+            .filter_map(|player| {
+                let id = *player.starting_rank_number().ok()?;
+                let rounds = (0..rounds)
+                    .map(|index| {
+                        Round::read(
+                            player.rounds().get(index).and_then(Option::as_ref),
+                            situation.scoring_system(),
+                        )
+                    })
+                    .collect();
+
+                Some(Player {
+                    id,
+                    rating: player.fide_rating().ok().copied().flatten(),
+                    rounds,
+                })
+            })
+            .collect();
+
+        Self {
+            players,
+            rounds,
+            scoring_system: situation.scoring_system().clone(),
+        }
+    }
+
+    /// Get a player by starting rank number.
+    fn player(&self, id: u16) -> Option<&Player> {
+        self.players.iter().find(|player| player.id == id)
+    }
+
+    /// Get the total points of a player, 0 if there is no such player.
+    #[must_use]
+    pub fn score(&self, player: u16) -> f32 {
+        self.player(player).map_or(0.0, Player::score)
+    }
+
+    /// Scores of the opponents of a player, and the points the player scored against
+    /// them, using virtual opponents for unplayed games.
+    fn opponents(&self, player: &Player) -> Vec<(f32, f32)> {
+        let mut score_before = 0.0;
+
+        player
+            .rounds
+            .iter()
+            .zip(1_u16..)
+            .map(|(round, number)| {
+                let opponent = round.opponent.and_then(|id| self.player(id));
+                let score = opponent.map_or_else(
+                    || {
+                        let remaining = self.rounds.saturating_sub(usize::from(number));
+                        let remaining = u16::try_from(remaining).unwrap_or(u16::MAX);
+                        self.scoring_system.draw().mul_add(
+                            f32::from(remaining),
+                            score_before + self.scoring_system.win() - round.points,
+                        )
+                    },
+                    |opponent| opponent.adjusted_score(self.scoring_system.draw()),
+                );
+                score_before += round.points;
+
+                (score, round.points)
+            })
+            .collect()
+    }
+
+    /// Get the value of a tie-break for a player, 0 if there is no such player.
+    ///
+    /// `tied` are the starting rank numbers of the players tied with the player
+    /// (including the player or not), it is only used for [`Tiebreak::DirectEncounter`].
+    #[must_use]
+    pub fn value(&self, tiebreak: Tiebreak, id: u16, tied: &[u16]) -> f32 {
+        let Some(player) = self.player(id) else {
+            return 0.0;
+        };
+        let games = || {
+            player
+                .rounds
+                .iter()
+                .filter(|round| round.opponent.is_some())
+        };
+        let rated_opponents = || {
+            games()
+                .filter_map(|round| self.player(round.opponent?)?.rating.map(f32::from))
+        };
+
+        match tiebreak {
+            Tiebreak::Buchholz => Self::cut(self.opponents(player), 0, 0),
+            Tiebreak::BuchholzCut(cut) => Self::cut(self.opponents(player), cut, 0),
+            Tiebreak::MedianBuchholz(cut) => Self::cut(self.opponents(player), cut, cut),
+            Tiebreak::SonnebornBerger => self
+                .opponents(player)
+                .iter()
+                .map(|(score, points)| score * points)
+                .sum(),
+            Tiebreak::ProgressiveScore => {
+                let mut score = 0.0;
+                player
+                    .rounds
+                    .iter()
+                    .map(|round| {
+                        score += round.points;
+                        score
+                    })
+                    .sum()
+            }
+            Tiebreak::DirectEncounter => self.direct_encounter(player, tied),
+            Tiebreak::Wins => Self::count(games().filter(|round| {
+                matches!(
+                    round.result,
+                    Some(
+                        RoundResult::Regular(RegularRoundResult::Win)
+                            | RoundResult::LessThanOneMove(
+                                LessThanOneMoveRoundResult::Win
+                            )
+                    )
+                )
+            })),
+            Tiebreak::BlackGames => {
+                Self::count(games().filter(|round| round.color == Some(Color::Black)))
+            }
+            Tiebreak::AverageRatingOfOpponents => {
+                Self::average(rated_opponents()).unwrap_or_default().round()
+            }
+            Tiebreak::PerformanceRating => {
+                let Some(average) = Self::average(rated_opponents()) else {
+                    return 0.0;
+                };
+                let rated: Vec<&Round> = games()
+                    .filter(|round| {
+                        round
+                            .opponent
+                            .and_then(|id| self.player(id)?.rating)
+                            .is_some()
+                    })
+                    .collect();
+                let percentage = Self::average(rated.iter().map(|round| round.points))
+                    .unwrap_or_default()
+                    / self.scoring_system.win();
+
+                average.round() + Self::rating_difference(percentage)
+            }
+        }
+    }
+
+    /// Sum of the scores of the opponents, without the `lowest` lowest scores and the
+    /// `highest` highest scores.
+    fn cut(opponents: Vec<(f32, f32)>, lowest: usize, highest: usize) -> f32 {
+        let mut scores: Vec<f32> =
+            opponents.into_iter().map(|(score, _)| score).collect();
+        scores.sort_by(f32::total_cmp);
+
+        scores
+            .iter()
+            .skip(lowest)
+            .take(scores.len().saturating_sub(lowest + highest))
+            .sum()
+    }
+
+    /// Points scored by `player` against the `tied` players, 0 unless every tied player
+    /// played each other.
+    fn direct_encounter(&self, player: &Player, tied: &[u16]) -> f32 {
+        let met = |a: u16, b: u16| {
+            self.player(a)
+                .is_some_and(|a| a.rounds.iter().any(|round| round.opponent == Some(b)))
+        };
+        let all_met = tied
+            .iter()
+            .all(|a| tied.iter().all(|b| a == b || met(*a, *b)));
+
+        if !all_met {
+            return 0.0;
+        }
+
+        player
+            .rounds
+            .iter()
+            .filter(|round| round.opponent.is_some_and(|id| tied.contains(&id)))
+            .map(|round| round.points)
+            .sum()
+    }
+
+    /// Number of rounds of an iterator, as a tie-break value.
+    fn count<'a>(rounds: impl Iterator<Item = &'a Round>) -> f32 {
+        rounds.map(|_| 1.0).sum()
+    }
+
+    /// Average of some values, [`None`] if there aren't any.
+    fn average(values: impl Iterator<Item = f32>) -> Option<f32> {
+        let (sum, count) =
+            values.fold((0.0, 0.0), |(sum, count), value| (sum + value, count + 1.0));
+
+        (count > 0.0).then(|| sum / count)
+    }
+
+    /// Rating difference for a percentage scored (between 0 and 1), using the FIDE table.
+    fn rating_difference(percentage: f32) -> f32 {
+        // The table is indexed by hundredths over 50%, and is symmetric below.
+        let hundredths = (percentage * 100.0).round() - 50.0;
+        let difference = RATING_DIFFERENCES
+            .iter()
+            .zip(0_u8..)
+            .find(|(_, index)| f32::from(*index) >= hundredths.abs())
+            .map_or(800.0, |(difference, _)| f32::from(*difference));
+
+        difference.copysign(hundredths)
+    }
+}
@@ -0,0 +1,509 @@
+//! # TRF Pairing Constraints
+//!
+//! This module contains the records of TRF-2025 which constrain the pairing of the next
+//! rounds:
+//! - absent players (DIN 240), see [`AbsentPlayers`],
+//! - accelerations (DIN 250), see [`Acceleration`],
+//! - forbidden pairs (DIN 260), see [`ForbiddenPairs`].
+//!
+//! Each record may be repeated. The pairing engines follow those constraints, see
+//! [`crate::pairing`].
+//!
+//! ## Usage
+//!
+//! Those structs are usually read with [`crate::Situation::try_from`], see
+//! [`crate::Situation::absent_players`], [`crate::Situation::accelerations`] and
+//! [`crate::Situation::forbidden_pairs`].
+//!
+//! ```
+//! # use openswisspairings_lib::{
+//! #     trf::{FormatVersion, ParseMode, TRFError},
+//! #     Situation,
+//! # };
+//! let file_contents = "\
+//! 012 Test Open
+//! 142 5
+//! 240 H   3    2    4
+//! 250  1.0   1   2    1    2
+//! 260    1    3
+//! 001    1      Alpha, Anna                       2000                             0.0
+//! 001    2      Bravo, Ben                        1900                             0.0
+//! 001    3      Charlie, Cleo                     1800                             0.0
+//! 001    4      Delta, Dan                        1700                             0.0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! assert_eq!(situation.format_version(), FormatVersion::Trf2025);
+//! assert_eq!(situation.tournament().number_of_rounds().unwrap(), &Some(5));
+//!
+//! let absent = &situation.absent_players()[0];
+//! assert_eq!(absent.round().unwrap(), &Some(3));
+//! assert_eq!(absent.players().len(), 2);
+//!
+//! let acceleration = &situation.accelerations()[0];
+//! assert_eq!(acceleration.points().unwrap(), &Some(1.0));
+//! assert!(acceleration.applies(2, 1));
+//! assert!(!acceleration.applies(3, 1));
+//!
+//! assert!(situation.forbidden_pairs()[0].forbids(1, 3));
+//! assert_eq!(String::from(&situation), file_contents);
+//!
+//! // Those records aren't part of TRF16, strict reading rejects them.
+//! let diagnostics = Situation::read_with(file_contents, ParseMode::Strict).unwrap_err();
+//! assert!(diagnostics.errors().any(|i| matches!(
+//!     i.error(),
+//!     TRFError::Trf2025RecordError(din) if din == "260"
+//! )));
+//! ```
+use std::ops::Range;
+
+use super::{
+    player::{
+        round::{ByeRoundResult, Result as RoundResult},
+        utils::{columns, field_error, format_field, parse_into, parse_number},
+    },
+    TRFError,
+};
+
+/// Get the columns of the non-empty 4-character slots separated by a space, starting at
+/// column `start`.
+fn player_columns(value: &str, start: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+    (start..value.chars().count())
+        .step_by(5)
+        .map(|n| n..n + 4)
+        .filter(|n| !columns(value, n.clone()).trim().is_empty())
+}
+
+/// Read the starting rank numbers stored in slots, see [`player_columns`].
+fn read_players(value: &str, start: usize) -> Vec<Result<u16, TRFError>> {
+    player_columns(value, start)
+        .filter_map(|n| parse_number(columns(value, n)).transpose())
+        .collect()
+}
+
+/// Get the starting rank numbers which were incorrectly formatted, along with their
+/// columns, see [`player_columns`].
+fn player_errors(
+    value: &str,
+    start: usize,
+    players: &[Result<u16, TRFError>],
+) -> Vec<(Range<usize>, TRFError)> {
+    player_columns(value, start)
+        .zip(players)
+        .filter_map(|(n, player)| field_error(player.as_ref(), n))
+        .collect()
+}
+
+/// Write starting rank numbers in 4-character slots separated by a space.
+///
+/// Players which were incorrectly formatted are left empty.
+fn write_players(players: &[Result<u16, TRFError>]) -> String {
+    players
+        .iter()
+        .map(|player| {
+            let player: String =
+                player.as_ref().map(ToString::to_string).unwrap_or_default();
+            format!(" {player:>4}")
+        })
+        .collect::<Vec<String>>()
+        .concat()
+}
+
+/// Players who announced their absence from a round (DIN 240).
+///
+/// Columns: bye type (0), round (2 to 4), then starting rank numbers (6 to 9, 11 to
+/// 14...). For example, `240 H   3    2    4` gives a half-point bye to players 2 and 4
+/// in round 3.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AbsentPlayers {
+    /// The bye given to the players.
+    ///
+    /// Only half-point, full-point and zero-point byes can be announced.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    bye: Result<Option<ByeRoundResult>, TRFError>,
+
+    /// The round, starting at 1.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    round: Result<Option<u16>, TRFError>,
+
+    /// Starting rank numbers of the absent players.
+    ///
+    /// Empty slots in the file are skipped.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::fields"))]
+    players: Vec<Result<u16, TRFError>>,
+}
+
+impl AbsentPlayers {
+    /// The data identification number of absent players.
+    pub const DATA_IDENTIFICATION_NUMBER: &str = "240";
+
+    /// Create a record of absent players.
+    #[must_use]
+    pub fn new(bye: ByeRoundResult, round: u16, players: &[u16]) -> Self {
+        Self {
+            bye: Ok(Some(bye)),
+            round: Ok(Some(round)),
+            players: players.iter().copied().map(Ok).collect(),
+        }
+    }
+
+    /// Get the bye given to the players.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a half-point, full-point or
+    /// zero-point bye.
+    pub const fn bye(&self) -> Result<&Option<ByeRoundResult>, &TRFError> {
+        self.bye.as_ref()
+    }
+
+    /// Get the round, starting at 1.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn round(&self) -> Result<&Option<u16>, &TRFError> {
+        self.round.as_ref()
+    }
+
+    /// Get the starting rank numbers of the absent players.
+    ///
+    /// Each player is [`Err`] if it isn't a valid number.
+    pub fn players(&self) -> &[Result<u16, TRFError>] {
+        &self.players
+    }
+
+    /// Whether a player is absent from a round by this record.
+    #[must_use]
+    pub fn includes(&self, round: u16, player: u16) -> bool {
+        self.round.as_ref().is_ok_and(|i| *i == Some(round))
+            && self
+                .players
+                .iter()
+                .any(|i| i.as_ref().is_ok_and(|i| *i == player))
+    }
+
+    /// Change the starting rank numbers of the absent players, see
+    /// [`crate::Situation::renumber_players`].
+    pub(crate) fn renumber(&mut self, renumbered: impl Fn(u16) -> u16) {
+        for player in self.players.iter_mut().flatten() {
+            *player = renumbered(*player);
+        }
+    }
+
+    /// Get the fields which were incorrectly formatted, along with their columns.
+    ///
+    /// `value` is the line the record was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn errors(&self, value: &str) -> Vec<(Range<usize>, TRFError)> {
+        [
+            field_error(self.bye.as_ref(), 0..1),
+            field_error(self.round.as_ref(), 2..5),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(player_errors(value, 6, &self.players))
+        .collect()
+    }
+}
+
+impl TryFrom<&str> for AbsentPlayers {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        let bye = parse_into(columns(value, 0..1)).and_then(|result| match result {
+            None => Ok(None),
+            Some(RoundResult::Bye(
+                bye @ (ByeRoundResult::HalfPointBye
+                | ByeRoundResult::FullPointBye
+                | ByeRoundResult::ZeroPointBye),
+            )) => Ok(Some(bye)),
+            Some(_) => Err(TRFError::InvalidRoundResultError(
+                columns(value, 0..1).to_string(),
+            )),
+        });
+
+        Ok(Self {
+            bye,
+            round: parse_number(columns(value, 2..5)),
+            players: read_players(value, 6),
+        })
+    }
+}
+
+impl From<&AbsentPlayers> for String {
+    /// Write the absent players as a TRF line, including the data identification number.
+    fn from(val: &AbsentPlayers) -> Self {
+        format!(
+            "{} {:1} {:>3}{}",
+            AbsentPlayers::DATA_IDENTIFICATION_NUMBER,
+            format_field(&val.bye, |bye| (*bye).into()),
+            format_field(&val.round, ToString::to_string),
+            write_players(&val.players),
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// Points added to the score of some players for pairing purposes (DIN 250).
+///
+/// Columns: points (0 to 3), first and last round (5 to 7, 9 to 11), first and last
+/// player (13 to 16, 18 to 21). For example, `250  1.0   1   2    1   20` adds 1 point to
+/// players 1 to 20 for the pairing of rounds 1 and 2.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Acceleration {
+    /// Points added to the score.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    points: Result<Option<f32>, TRFError>,
+
+    /// First round of the acceleration.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    first_round: Result<Option<u16>, TRFError>,
+
+    /// Last round of the acceleration.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    last_round: Result<Option<u16>, TRFError>,
+
+    /// Starting rank number of the first accelerated player.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    first_player: Result<Option<u16>, TRFError>,
+
+    /// Starting rank number of the last accelerated player.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    last_player: Result<Option<u16>, TRFError>,
+}
+
+impl Acceleration {
+    /// The data identification number of an acceleration.
+    pub const DATA_IDENTIFICATION_NUMBER: &str = "250";
+
+    /// Create an acceleration.
+    #[must_use]
+    pub const fn new(points: f32, rounds: (u16, u16), players: (u16, u16)) -> Self {
+        Self::with_bounds(
+            points,
+            (Some(rounds.0), Some(rounds.1)),
+            (Some(players.0), Some(players.1)),
+        )
+    }
+
+    /// Create an acceleration whose bounds may be open, see [`Acceleration::applies`].
+    pub(crate) const fn with_bounds(
+        points: f32,
+        rounds: (Option<u16>, Option<u16>),
+        players: (Option<u16>, Option<u16>),
+    ) -> Self {
+        Self {
+            points: Ok(Some(points)),
+            first_round: Ok(rounds.0),
+            last_round: Ok(rounds.1),
+            first_player: Ok(players.0),
+            last_player: Ok(players.1),
+        }
+    }
+
+    /// Get the points added to the score.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn points(&self) -> Result<&Option<f32>, &TRFError> {
+        self.points.as_ref()
+    }
+
+    /// Get the first round of the acceleration.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn first_round(&self) -> Result<&Option<u16>, &TRFError> {
+        self.first_round.as_ref()
+    }
+
+    /// Get the last round of the acceleration.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn last_round(&self) -> Result<&Option<u16>, &TRFError> {
+        self.last_round.as_ref()
+    }
+
+    /// Get the starting rank number of the first accelerated player.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn first_player(&self) -> Result<&Option<u16>, &TRFError> {
+        self.first_player.as_ref()
+    }
+
+    /// Get the starting rank number of the last accelerated player.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn last_player(&self) -> Result<&Option<u16>, &TRFError> {
+        self.last_player.as_ref()
+    }
+
+    /// Whether the acceleration applies to a player in a round.
+    ///
+    /// Empty bounds are open, an acceleration with an invalid field never applies.
+    #[must_use]
+    pub fn applies(&self, round: u16, player: u16) -> bool {
+        let within = |first: &Result<Option<u16>, TRFError>,
+                      last: &Result<Option<u16>, TRFError>,
+                      value: u16| {
+            match (first, last) {
+                (Ok(first), Ok(last)) => {
+                    first.is_none_or(|first| first <= value)
+                        && last.is_none_or(|last| value <= last)
+                }
+                _ => false,
+            }
+        };
+
+        within(&self.first_round, &self.last_round, round)
+            && within(&self.first_player, &self.last_player, player)
+    }
+
+    /// Get the fields which were incorrectly formatted, along with their columns.
+    pub(crate) fn errors(&self) -> Vec<(Range<usize>, TRFError)> {
+        [
+            field_error(self.points.as_ref(), 0..4),
+            field_error(self.first_round.as_ref(), 5..8),
+            field_error(self.last_round.as_ref(), 9..12),
+            field_error(self.first_player.as_ref(), 13..17),
+            field_error(self.last_player.as_ref(), 18..22),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+impl TryFrom<&str> for Acceleration {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        Ok(Self {
+            points: parse_number(columns(value, 0..4)),
+            first_round: parse_number(columns(value, 5..8)),
+            last_round: parse_number(columns(value, 9..12)),
+            first_player: parse_number(columns(value, 13..17)),
+            last_player: parse_number(columns(value, 18..22)),
+        })
+    }
+}
+
+impl From<&Acceleration> for String {
+    /// Write the acceleration as a TRF line, including the data identification number.
+    fn from(val: &Acceleration) -> Self {
+        format!(
+            "{} {:>4} {:>3} {:>3} {:>4} {:>4}",
+            Acceleration::DATA_IDENTIFICATION_NUMBER,
+            format_field(&val.points, |points| format!("{points:.1}")),
+            format_field(&val.first_round, ToString::to_string),
+            format_field(&val.last_round, ToString::to_string),
+            format_field(&val.first_player, ToString::to_string),
+            format_field(&val.last_player, ToString::to_string),
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// Players who must not be paired with each other (DIN 260).
+///
+/// Columns: starting rank numbers (0 to 3, 5 to 8...). For example, `260    1    3`
+/// forbids the pairing of players 1 and 3.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForbiddenPairs {
+    /// Starting rank numbers of the players.
+    ///
+    /// Empty slots in the file are skipped.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::fields"))]
+    players: Vec<Result<u16, TRFError>>,
+}
+
+impl ForbiddenPairs {
+    /// The data identification number of forbidden pairs.
+    pub const DATA_IDENTIFICATION_NUMBER: &str = "260";
+
+    /// Create a record of players who must not be paired with each other.
+    #[must_use]
+    pub fn new(players: &[u16]) -> Self {
+        Self {
+            players: players.iter().copied().map(Ok).collect(),
+        }
+    }
+
+    /// Get the starting rank numbers of the players.
+    ///
+    /// Each player is [`Err`] if it isn't a valid number.
+    pub fn players(&self) -> &[Result<u16, TRFError>] {
+        &self.players
+    }
+
+    /// Whether the pairing of two players is forbidden by this record.
+    #[must_use]
+    pub fn forbids(&self, a: u16, b: u16) -> bool {
+        let contains = |id: u16| {
+            self.players
+                .iter()
+                .any(|player| player.as_ref().is_ok_and(|player| *player == id))
+        };
+
+        a != b && contains(a) && contains(b)
+    }
+
+    /// Change the starting rank numbers of the players, see
+    /// [`crate::Situation::renumber_players`].
+    pub(crate) fn renumber(&mut self, renumbered: impl Fn(u16) -> u16) {
+        for player in self.players.iter_mut().flatten() {
+            *player = renumbered(*player);
+        }
+    }
+
+    /// Get the players which were incorrectly formatted, along with their columns.
+    ///
+    /// `value` is the line the record was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn errors(&self, value: &str) -> Vec<(Range<usize>, TRFError)> {
+        player_errors(value, 0, &self.players)
+    }
+
+    /// Write the forbidden pairs as a TRF line with the given data identification
+    /// number, also used by the `JaVaFo` extension (see [`crate::trf::extension`]).
+    pub(crate) fn write(&self, din: &str) -> String {
+        format!("{din}{}", write_players(&self.players))
+    }
+}
+
+impl TryFrom<&str> for ForbiddenPairs {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        Ok(Self {
+            players: read_players(value, 0),
+        })
+    }
+}
+
+impl From<&ForbiddenPairs> for String {
+    /// Write the forbidden pairs as a TRF line, including the data identification
+    /// number.
+    fn from(val: &ForbiddenPairs) -> Self {
+        val.write(ForbiddenPairs::DATA_IDENTIFICATION_NUMBER)
+    }
+}
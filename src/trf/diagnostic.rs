@@ -0,0 +1,210 @@
+//! # TRF Parse Diagnostics
+//!
+//! The parser doesn't stop on incorrectly formatted data: fields which can't be read are
+//! stored as [`Err`] (see [`crate::trf::player#Usage`]), and the problems found are
+//! collected as [`Diagnostic`]s. Each diagnostic locates the problem in the file (line,
+//! data identification number and columns), so it can be shown to the arbiter.
+//!
+//! ## Usage
+//!
+//! Use [`crate::Situation::read`] to get the diagnostics along with the situation:
+//!
+//! ```
+//! # use openswisspairings_lib::Situation;
+//! let file_contents = "\
+//! 012 Test Open\r\
+//! 062 ten\r\
+//! 001    1      Smith, John                       21OO                             0.0";
+//!
+//! let (situation, diagnostics) = Situation::read(file_contents);
+//! assert_eq!(situation.players().len(), 1);
+//!
+//! let errors: Vec<(Option<usize>, std::ops::Range<usize>)> = diagnostics
+//!     .errors()
+//!     .map(|diagnostic| (diagnostic.line(), diagnostic.columns()))
+//!     .collect();
+//! assert_eq!(errors, [(Some(2), 4..7), (Some(3), 48..52)]);
+//!
+//! for diagnostic in diagnostics.all() {
+//!     println!("{diagnostic}");
+//! }
+//! ```
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use super::TRFError;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The file doesn't follow the reference, but nothing was lost (e.g. a non-FIDE line
+    /// separator, or a record the parser doesn't read and keeps as is).
+    Warning,
+
+    /// Some data couldn't be read, the corresponding field is stored as [`Err`].
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found while reading a TRF file.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// Line number, starting at 1, [`None`] if the problem is about the whole file.
+    line: Option<usize>,
+
+    /// Data identification number of the line.
+    din: Option<String>,
+
+    /// Columns of the line where the problem is, counted in characters from 0.
+    columns: Range<usize>,
+
+    /// How serious the problem is.
+    severity: Severity,
+
+    /// The problem.
+    error: TRFError,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic about the whole file.
+    pub(crate) const fn whole_file(severity: Severity, error: TRFError) -> Self {
+        Self {
+            line: None,
+            din: None,
+            columns: 0..0,
+            severity,
+            error,
+        }
+    }
+
+    /// Create a diagnostic about some columns of a line.
+    pub(crate) fn new(
+        line: usize,
+        din: &str,
+        columns: Range<usize>,
+        severity: Severity,
+        error: TRFError,
+    ) -> Self {
+        Self {
+            line: Some(line),
+            din: Some(din.to_string()).filter(|din| !din.is_empty()),
+            columns,
+            severity,
+            error,
+        }
+    }
+
+    /// Get the line number, starting at 1.
+    ///
+    /// This is [`None`] if the problem is about the whole file.
+    #[must_use]
+    pub const fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Get the data identification number of the line.
+    #[must_use]
+    pub fn din(&self) -> Option<&str> {
+        self.din.as_deref()
+    }
+
+    /// Get the columns of the line where the problem is.
+    ///
+    /// Columns are counted in characters from 0, including the data identification
+    /// number, so `4..8` is the fifth to the eighth character of the line.
+    #[must_use]
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    /// Get how serious the problem is.
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Get the problem.
+    #[must_use]
+    pub const fn error(&self) -> &TRFError {
+        &self.error
+    }
+}
+
+impl Display for Diagnostic {
+    /// Write the diagnostic with columns counted from 1, like in the TRF reference.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}")?;
+            if let Some(din) = &self.din {
+                write!(f, " ({din})")?;
+            }
+            if !self.columns.is_empty() {
+                write!(
+                    f,
+                    ", columns {}-{}",
+                    self.columns.start + 1,
+                    self.columns.end
+                )?;
+            }
+            write!(f, ": ")?;
+        }
+
+        write!(f, "{}: {}", self.severity, self.error)
+    }
+}
+
+/// The diagnostics collected while reading a TRF file, in the order of the file.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// The diagnostics, in the order they were found.
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Add a diagnostic.
+    pub(crate) fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Get every diagnostic.
+    #[must_use]
+    pub fn all(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Get the diagnostics with the [`Severity::Error`] severity.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// Get the diagnostics with the [`Severity::Warning`] severity.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+
+    /// Whether some data couldn't be read.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Whether no problem was found.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
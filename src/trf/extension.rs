@@ -0,0 +1,728 @@
+//! # TRF(x) Extensions
+//!
+//! This module contains the extension records of the `JaVaFo` pairing engine, known as
+//! TRF(x), which are read by most pairing engines:
+//! - the number of rounds (XXR),
+//! - the configuration (XXC): the initial colour and whether players are ranked by
+//!   rating, see [`Configuration`],
+//! - the accelerations of a player in each round (XXA), see [`PlayerAcceleration`],
+//! - the forbidden pairs (XXP), see [`ForbiddenPairs`],
+//! - the custom score values (XXS), see [`ScoreValues`].
+//!
+//! The pairing engines follow the accelerations, the forbidden pairs and the score
+//! values, see [`crate::pairing`].
+//!
+//! ## Usage
+//!
+//! This struct is usually read with [`crate::Situation::try_from`], see
+//! [`crate::Situation::extensions`].
+//!
+//! ```
+//! # use openswisspairings_lib::{
+//! #     trf::{extension::ScoreCode, player::round::Color},
+//! #     Situation,
+//! # };
+//! let file_contents = "\
+//! 012 Test Open
+//! XXR 7
+//! XXC black1 rank
+//! XXA    1  1.0  1.0  0.0
+//! XXP    2    3
+//! XXS WW=1 BW=1 D=0.5 PAB=0.5
+//! 001    1      Alpha, Anna                       2000                             0.0
+//! 001    2      Bravo, Ben                        1900                             0.0
+//! 001    3      Charlie, Cleo                     1800                             0.0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let extensions = situation.extensions();
+//!
+//! assert_eq!(extensions.number_of_rounds().unwrap(), &Some(7));
+//! assert_eq!(extensions.initial_color(), Some(Color::Black));
+//! assert!(extensions.configuration().unwrap().unwrap().rank());
+//! assert_eq!(extensions.acceleration(1, 2), 1.0);
+//! assert_eq!(extensions.acceleration(1, 3), 0.0);
+//! assert!(extensions.forbidden_pairs()[0].forbids(3, 2));
+//!
+//! let score_values = extensions.score_values().unwrap().as_ref().unwrap();
+//! assert_eq!(score_values.value(ScoreCode::PairingAllocatedBye), Some(0.5));
+//! assert_eq!(score_values.value(ScoreCode::WhiteDraw), None);
+//!
+//! assert_eq!(String::from(&situation), file_contents);
+//! ```
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use super::{
+    constraint::ForbiddenPairs,
+    player::{
+        round::{
+            ByeRoundResult, Color, LessThanOneMoveRoundResult, NotPlayedRoundResult,
+            RegularRoundResult, Result as RoundResult,
+        },
+        utils::{columns, field_error, format_field, parse_into, parse_number},
+    },
+    TRFError,
+};
+
+/// The configuration of the pairing engine (XXC).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Configuration {
+    /// The colour of the first player in round 1 (keywords `white1` and `black1`).
+    initial_color: Option<Color>,
+
+    /// Whether the players are ranked by rating, title and name before pairing, instead
+    /// of using their starting rank numbers as is (keyword `rank`).
+    rank: bool,
+}
+
+impl Configuration {
+    /// Create a configuration.
+    #[must_use]
+    pub const fn new(initial_color: Option<Color>, rank: bool) -> Self {
+        Self {
+            initial_color,
+            rank,
+        }
+    }
+
+    /// Get the colour of the first player in round 1.
+    #[must_use]
+    pub const fn initial_color(&self) -> Option<Color> {
+        self.initial_color
+    }
+
+    /// Whether the players are ranked by rating, title and name before pairing.
+    #[must_use]
+    pub const fn rank(&self) -> bool {
+        self.rank
+    }
+}
+
+impl TryFrom<&str> for Configuration {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut configuration = Self::new(None, false);
+
+        for keyword in value.split_whitespace() {
+            match keyword {
+                "white1" => configuration.initial_color = Some(Color::White),
+                "black1" => configuration.initial_color = Some(Color::Black),
+                "rank" => configuration.rank = true,
+                other => return Err(TRFError::UnknownKeywordError(other.to_string())),
+            }
+        }
+
+        Ok(configuration)
+    }
+}
+
+impl From<&Configuration> for String {
+    fn from(val: &Configuration) -> Self {
+        let initial_color = match val.initial_color {
+            Some(Color::White) => Some("white1"),
+            Some(Color::Black) => Some("black1"),
+            Some(Color::None) | None => None,
+        };
+
+        initial_color
+            .into_iter()
+            .chain(val.rank.then_some("rank"))
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+/// The accelerations of a player in each round (XXA).
+///
+/// Columns: starting rank number (0 to 3), then the points added to the score of the
+/// player in each round (5 to 8, 10 to 13...). For example, `XXA    1  1.0  1.0` adds 1
+/// point to player 1 for the pairing of rounds 1 and 2.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerAcceleration {
+    /// Starting rank number of the player.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    player: Result<Option<u16>, TRFError>,
+
+    /// Points added to the score of the player in each round, starting with round 1.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::fields"))]
+    points: Vec<Result<Option<f32>, TRFError>>,
+}
+
+impl PlayerAcceleration {
+    /// Create the accelerations of a player, starting with round 1.
+    #[must_use]
+    pub fn new(player: u16, points: &[f32]) -> Self {
+        Self {
+            player: Ok(Some(player)),
+            points: points.iter().map(|points| Ok(Some(*points))).collect(),
+        }
+    }
+
+    /// Get the starting rank number of the player.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn player(&self) -> Result<&Option<u16>, &TRFError> {
+        self.player.as_ref()
+    }
+
+    /// Get the points added to the score of the player in each round, starting with
+    /// round 1.
+    ///
+    /// Each round is [`Err`] if it isn't a valid number.
+    pub fn points(&self) -> &[Result<Option<f32>, TRFError>] {
+        &self.points
+    }
+
+    /// Get the points added to the score of the player in a round, starting at 1.
+    ///
+    /// Rounds which are empty or incorrectly formatted don't add any points.
+    #[must_use]
+    pub fn round(&self, round: u16) -> f32 {
+        usize::from(round)
+            .checked_sub(1)
+            .and_then(|index| self.points.get(index))
+            .and_then(|points| points.as_ref().ok().copied().flatten())
+            .unwrap_or_default()
+    }
+
+    /// Get the fields which were incorrectly formatted, along with their columns.
+    pub(crate) fn errors(&self) -> Vec<(Range<usize>, TRFError)> {
+        field_error(self.player.as_ref(), 0..4)
+            .into_iter()
+            .chain(
+                (5..)
+                    .step_by(5)
+                    .zip(&self.points)
+                    .filter_map(|(n, points)| field_error(points.as_ref(), n..n + 4)),
+            )
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for PlayerAcceleration {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        Ok(Self {
+            player: parse_number(columns(value, 0..4)),
+            points: (5..value.chars().count())
+                .step_by(5)
+                .map(|n| parse_number(columns(value, n..n + 4)))
+                .collect(),
+        })
+    }
+}
+
+impl From<&PlayerAcceleration> for String {
+    /// Write the accelerations as a TRF line, including the data identification number.
+    fn from(val: &PlayerAcceleration) -> Self {
+        let points: Vec<Self> = val
+            .points
+            .iter()
+            .map(|points| {
+                format!(
+                    "{:>4}",
+                    format_field(points, |points| format!("{points:.1}"))
+                )
+            })
+            .collect();
+
+        format!(
+            "{} {:>4} {}",
+            Extensions::ACCELERATION,
+            format_field(&val.player, ToString::to_string),
+            points.join(" ")
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// A result which can be given a custom score value (XXS).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoreCode {
+    /// Win with white (`WW`).
+    WhiteWin,
+
+    /// Win with black (`BW`).
+    BlackWin,
+
+    /// Draw with white (`WD`).
+    WhiteDraw,
+
+    /// Draw with black (`BD`).
+    BlackDraw,
+
+    /// Loss with white (`WL`).
+    WhiteLoss,
+
+    /// Loss with black (`BL`).
+    BlackLoss,
+
+    /// Zero-point bye (`ZPB`).
+    ZeroPointBye,
+
+    /// Half-point bye (`HPB`).
+    HalfPointBye,
+
+    /// Full-point bye (`FPB`).
+    FullPointBye,
+
+    /// Pairing-allocated bye (`PAB`).
+    PairingAllocatedBye,
+
+    /// Forfeit win (`FW`).
+    ForfeitWin,
+
+    /// Forfeit loss (`FL`).
+    ForfeitLoss,
+
+    /// Win with either colour (`W`), used when the colour has no value of its own.
+    Win,
+
+    /// Draw with either colour (`D`), used when the colour has no value of its own.
+    Draw,
+}
+
+impl TryFrom<&str> for ScoreCode {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "WW" => Self::WhiteWin,
+            "BW" => Self::BlackWin,
+            "WD" => Self::WhiteDraw,
+            "BD" => Self::BlackDraw,
+            "WL" => Self::WhiteLoss,
+            "BL" => Self::BlackLoss,
+            "ZPB" => Self::ZeroPointBye,
+            "HPB" => Self::HalfPointBye,
+            "FPB" => Self::FullPointBye,
+            "PAB" => Self::PairingAllocatedBye,
+            "FW" => Self::ForfeitWin,
+            "FL" => Self::ForfeitLoss,
+            "W" => Self::Win,
+            "D" => Self::Draw,
+
+            other => return Err(TRFError::UnknownKeywordError(other.to_string())),
+        })
+    }
+}
+
+impl Display for ScoreCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Self::WhiteWin => "WW",
+            Self::BlackWin => "BW",
+            Self::WhiteDraw => "WD",
+            Self::BlackDraw => "BD",
+            Self::WhiteLoss => "WL",
+            Self::BlackLoss => "BL",
+            Self::ZeroPointBye => "ZPB",
+            Self::HalfPointBye => "HPB",
+            Self::FullPointBye => "FPB",
+            Self::PairingAllocatedBye => "PAB",
+            Self::ForfeitWin => "FW",
+            Self::ForfeitLoss => "FL",
+            Self::Win => "W",
+            Self::Draw => "D",
+        };
+
+        write!(f, "{code}")
+    }
+}
+
+/// The custom score values (XXS), e.g. `XXS WW=1 BW=1 D=0.5`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreValues {
+    /// The score values, in the order they were written.
+    values: Vec<(ScoreCode, f32)>,
+}
+
+impl ScoreValues {
+    /// Create custom score values.
+    #[must_use]
+    pub fn new(values: &[(ScoreCode, f32)]) -> Self {
+        Self {
+            values: values.to_vec(),
+        }
+    }
+
+    /// Get the score values, in the order they were written.
+    #[must_use]
+    pub fn values(&self) -> &[(ScoreCode, f32)] {
+        &self.values
+    }
+
+    /// Get the score value of a result, [`None`] if it has no custom value.
+    ///
+    /// If a value is given several times, the last one is used.
+    #[must_use]
+    pub fn value(&self, code: ScoreCode) -> Option<f32> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(i, _)| *i == code)
+            .map(|(_, value)| *value)
+    }
+
+    /// Get the points given for a round result, [`None`] if it has no custom value.
+    ///
+    /// Games use the value of their colour (e.g. `WW` for a win with white), then the
+    /// value of either colour (`W` or `D`). Forfeit wins and full-point byes fall back
+    /// to `W` as well, and half-point byes to `D`.
+    ///
+    /// ```
+    /// # use openswisspairings_lib::trf::{
+    /// #     extension::{ScoreCode, ScoreValues},
+    /// #     player::round::{Color, RegularRoundResult, Result as RoundResult},
+    /// # };
+    /// let values = ScoreValues::new(&[(ScoreCode::BlackWin, 1.5), (ScoreCode::Win, 1.0)]);
+    /// let win = RoundResult::Regular(RegularRoundResult::Win);
+    ///
+    /// assert_eq!(values.points(win, Some(Color::Black)), Some(1.5));
+    /// assert_eq!(values.points(win, Some(Color::White)), Some(1.0));
+    /// assert_eq!(values.points(RoundResult::Regular(RegularRoundResult::Draw), None), None);
+    /// ```
+    #[must_use]
+    pub fn points(&self, result: RoundResult, color: Option<Color>) -> Option<f32> {
+        let colored = |white: ScoreCode, black: ScoreCode| match color {
+            Some(Color::White) => self.value(white),
+            Some(Color::Black) => self.value(black),
+            Some(Color::None) | None => None,
+        };
+
+        match result {
+            RoundResult::Regular(RegularRoundResult::Win)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Win) => {
+                colored(ScoreCode::WhiteWin, ScoreCode::BlackWin)
+                    .or_else(|| self.value(ScoreCode::Win))
+            }
+            RoundResult::Regular(RegularRoundResult::Draw)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Draw) => {
+                colored(ScoreCode::WhiteDraw, ScoreCode::BlackDraw)
+                    .or_else(|| self.value(ScoreCode::Draw))
+            }
+            RoundResult::Regular(RegularRoundResult::Loss)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Loss) => {
+                colored(ScoreCode::WhiteLoss, ScoreCode::BlackLoss)
+            }
+            RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitWin) => self
+                .value(ScoreCode::ForfeitWin)
+                .or_else(|| self.value(ScoreCode::Win)),
+            RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitLoss) => {
+                self.value(ScoreCode::ForfeitLoss)
+            }
+            RoundResult::Bye(ByeRoundResult::FullPointBye) => self
+                .value(ScoreCode::FullPointBye)
+                .or_else(|| self.value(ScoreCode::Win)),
+            RoundResult::Bye(ByeRoundResult::HalfPointBye) => self
+                .value(ScoreCode::HalfPointBye)
+                .or_else(|| self.value(ScoreCode::Draw)),
+            RoundResult::Bye(ByeRoundResult::ZeroPointBye) => {
+                self.value(ScoreCode::ZeroPointBye)
+            }
+            RoundResult::Bye(ByeRoundResult::PairingAllocatedBye) => {
+                self.value(ScoreCode::PairingAllocatedBye)
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for ScoreValues {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let values = value
+            .split_whitespace()
+            .map(|pair| {
+                let (code, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| TRFError::UnknownKeywordError(pair.to_string()))?;
+
+                Ok((ScoreCode::try_from(code)?, value.parse::<f32>()?))
+            })
+            .collect::<Result<Vec<(ScoreCode, f32)>, TRFError>>()?;
+
+        Ok(Self { values })
+    }
+}
+
+impl From<&ScoreValues> for String {
+    fn from(val: &ScoreValues) -> Self {
+        val.values
+            .iter()
+            .map(|(code, value)| format!("{code}={value}"))
+            .collect::<Vec<Self>>()
+            .join(" ")
+    }
+}
+
+/// The `JaVaFo` extension records of a TRF file.
+///
+/// Every record is optional in the file: a record which is missing is stored as
+/// [`Ok`]\([`None`]), or as an empty list for records which can be repeated.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extensions {
+    /// Number of rounds (XXR).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    number_of_rounds: Result<Option<u16>, TRFError>,
+
+    /// Configuration of the pairing engine (XXC).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    configuration: Result<Option<Configuration>, TRFError>,
+
+    /// Accelerations of the players (XXA), one record per player.
+    accelerations: Vec<PlayerAcceleration>,
+
+    /// Forbidden pairs (XXP), one record per group of players.
+    forbidden_pairs: Vec<ForbiddenPairs>,
+
+    /// Custom score values (XXS).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    score_values: Result<Option<ScoreValues>, TRFError>,
+}
+
+impl Extensions {
+    /// The data identification number of the number of rounds.
+    pub const NUMBER_OF_ROUNDS: &str = "XXR";
+
+    /// The data identification number of the configuration.
+    pub const CONFIGURATION: &str = "XXC";
+
+    /// The data identification number of the accelerations of a player.
+    pub const ACCELERATION: &str = "XXA";
+
+    /// The data identification number of forbidden pairs.
+    pub const FORBIDDEN_PAIRS: &str = "XXP";
+
+    /// The data identification number of the custom score values.
+    pub const SCORE_VALUES: &str = "XXS";
+
+    /// Get the number of rounds.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn number_of_rounds(&self) -> Result<&Option<u16>, &TRFError> {
+        self.number_of_rounds.as_ref()
+    }
+
+    /// Set the number of rounds.
+    pub fn set_number_of_rounds(&mut self, number_of_rounds: u16) {
+        self.number_of_rounds = Ok(Some(number_of_rounds));
+    }
+
+    /// Get the configuration of the pairing engine.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if a keyword is unknown.
+    pub const fn configuration(&self) -> Result<&Option<Configuration>, &TRFError> {
+        self.configuration.as_ref()
+    }
+
+    /// Set the configuration of the pairing engine.
+    pub fn set_configuration(&mut self, configuration: Configuration) {
+        self.configuration = Ok(Some(configuration));
+    }
+
+    /// Get the colour of the first player in round 1, [`None`] if it isn't configured.
+    #[must_use]
+    pub fn initial_color(&self) -> Option<Color> {
+        self.configuration
+            .as_ref()
+            .ok()
+            .copied()
+            .flatten()
+            .and_then(|configuration| configuration.initial_color())
+    }
+
+    /// Get the accelerations of the players.
+    #[must_use]
+    pub fn accelerations(&self) -> &[PlayerAcceleration] {
+        &self.accelerations
+    }
+
+    /// Get the points added to the score of a player in a round, starting at 1.
+    ///
+    /// The points of every record of the player are added up.
+    #[must_use]
+    pub fn acceleration(&self, player: u16, round: u16) -> f32 {
+        self.accelerations
+            .iter()
+            .filter(|i| i.player().is_ok_and(|i| *i == Some(player)))
+            .map(|i| i.round(round))
+            .sum()
+    }
+
+    /// Add the accelerations of a player.
+    pub fn add_acceleration(&mut self, acceleration: PlayerAcceleration) {
+        self.accelerations.push(acceleration);
+    }
+
+    /// Get the forbidden pairs.
+    #[must_use]
+    pub fn forbidden_pairs(&self) -> &[ForbiddenPairs] {
+        &self.forbidden_pairs
+    }
+
+    /// Add players who must not be paired with each other.
+    pub fn add_forbidden_pairs(&mut self, forbidden_pairs: ForbiddenPairs) {
+        self.forbidden_pairs.push(forbidden_pairs);
+    }
+
+    /// Get the custom score values.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if a result code is unknown or a value isn't a valid number.
+    pub const fn score_values(&self) -> Result<&Option<ScoreValues>, &TRFError> {
+        self.score_values.as_ref()
+    }
+
+    /// Set the custom score values.
+    pub fn set_score_values(&mut self, score_values: ScoreValues) {
+        self.score_values = Ok(Some(score_values));
+    }
+
+    /// Change the starting rank numbers of the players in the accelerations and the
+    /// forbidden pairs, see [`crate::Situation::renumber_players`].
+    pub(crate) fn renumber(&mut self, renumbered: impl Fn(u16) -> u16) {
+        for acceleration in &mut self.accelerations {
+            if let Ok(Some(player)) = &mut acceleration.player {
+                *player = renumbered(*player);
+            }
+        }
+        for forbidden_pairs in &mut self.forbidden_pairs {
+            forbidden_pairs.renumber(&renumbered);
+        }
+    }
+
+    /// Read an extension record into the struct.
+    ///
+    /// `argument` is the line without its data identification number and the following
+    /// space. Returns the index of the record for records which can be repeated, 0
+    /// otherwise. Records which aren't extension records are ignored.
+    pub(crate) fn read_record(&mut self, din: &str, argument: &str) -> usize {
+        match din {
+            Self::NUMBER_OF_ROUNDS => self.number_of_rounds = parse_number(argument),
+            Self::CONFIGURATION => self.configuration = parse_into(argument),
+            Self::ACCELERATION => {
+                if let Ok(acceleration) = PlayerAcceleration::try_from(argument) {
+                    self.accelerations.push(acceleration);
+                }
+                return self.accelerations.len().saturating_sub(1);
+            }
+            Self::FORBIDDEN_PAIRS => {
+                if let Ok(forbidden_pairs) = ForbiddenPairs::try_from(argument) {
+                    self.forbidden_pairs.push(forbidden_pairs);
+                }
+                return self.forbidden_pairs.len().saturating_sub(1);
+            }
+            Self::SCORE_VALUES => self.score_values = parse_into(argument),
+            _ => {}
+        }
+
+        0
+    }
+
+    /// Get the fields of an extension record which were incorrectly formatted, along
+    /// with their columns.
+    ///
+    /// `argument` is the line the record was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn errors(
+        &self,
+        din: &str,
+        index: usize,
+        argument: &str,
+    ) -> Vec<(Range<usize>, TRFError)> {
+        let columns = 0..argument.chars().count();
+
+        match din {
+            Self::NUMBER_OF_ROUNDS => {
+                field_error(self.number_of_rounds.as_ref(), columns)
+                    .into_iter()
+                    .collect()
+            }
+            Self::CONFIGURATION => field_error(self.configuration.as_ref(), columns)
+                .into_iter()
+                .collect(),
+            Self::ACCELERATION => self
+                .accelerations
+                .get(index)
+                .map(PlayerAcceleration::errors)
+                .unwrap_or_default(),
+            Self::FORBIDDEN_PAIRS => self
+                .forbidden_pairs
+                .get(index)
+                .map(|i| i.errors(argument))
+                .unwrap_or_default(),
+            Self::SCORE_VALUES => field_error(self.score_values.as_ref(), columns)
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Get the records of the extensions, in the order they are written: data
+    /// identification number and index of the record for repeated records.
+    pub(crate) fn records(&self) -> Vec<(&'static str, usize)> {
+        [(Self::NUMBER_OF_ROUNDS, 0), (Self::CONFIGURATION, 0)]
+            .into_iter()
+            .chain((0..self.accelerations.len()).map(|index| (Self::ACCELERATION, index)))
+            .chain(
+                (0..self.forbidden_pairs.len())
+                    .map(|index| (Self::FORBIDDEN_PAIRS, index)),
+            )
+            .chain([(Self::SCORE_VALUES, 0)])
+            .collect()
+    }
+
+    /// Write an extension record as a TRF line, including the data identification
+    /// number.
+    ///
+    /// `index` is only used for records which can be repeated. Returns [`None`] if the
+    /// record is empty or isn't an extension record.
+    pub(crate) fn write_record(&self, din: &str, index: usize) -> Option<String> {
+        let argument: String = match din {
+            Self::NUMBER_OF_ROUNDS => {
+                format_field(&self.number_of_rounds, ToString::to_string)
+            }
+            Self::CONFIGURATION => format_field(&self.configuration, |i| i.into()),
+            Self::ACCELERATION => return self.accelerations.get(index).map(Into::into),
+            Self::FORBIDDEN_PAIRS => {
+                return Some(self.forbidden_pairs.get(index)?.write(din));
+            }
+            Self::SCORE_VALUES => format_field(&self.score_values, |i| i.into()),
+            _ => return None,
+        };
+
+        (!argument.is_empty()).then(|| format!("{din} {argument}"))
+    }
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Self {
+            number_of_rounds: Ok(None),
+            configuration: Ok(None),
+            accelerations: vec![],
+            forbidden_pairs: vec![],
+            score_values: Ok(None),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! # TRF Player Metadata
+//!
+//! This module contains the player metadata records written by Tornelo (DIN DAT). Each
+//! record stores a key and a value for the player with the given starting rank number,
+//! e.g. `DAT    1 GROUP      Team Penguin`. The well-known keys are
+//! [`PlayerMetadata::EMAIL`], [`PlayerMetadata::GROUP`] and
+//! [`PlayerMetadata::TORNELO_ID`], other keys (e.g. `ID:USCF`) are kept as is.
+//!
+//! The source of the file written by Tornelo (DIN XRS) is stored with the tournament
+//! header, see [`crate::trf::tournament::TournamentData::source`].
+//!
+//! ## Usage
+//!
+//! Metadata records are attached to the player section of the same starting rank number
+//! when the file is read, see [`Section::metadata`].
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::Situation;
+//!
+//! let file_contents = fs::read_to_string("test_file.trf").unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//! let player = &situation.players()[0];
+//!
+//! assert_eq!(situation.tournament().source(), Some("tornelo.com"));
+//! assert_eq!(player.group(), Some("Team Penguin"));
+//! assert_eq!(
+//!     player.tornelo_id(),
+//!     Some("39d162db-023a-4974-aa71-a1df73babc33")
+//! );
+//! assert_eq!(
+//!     situation.players()[1].metadata_value("ID:USCF"),
+//!     Some("12345678")
+//! );
+//! ```
+//!
+//! [`Section::metadata`]: crate::trf::player::Section::metadata
+use std::ops::Range;
+
+use super::{
+    player::utils::{columns, field_error},
+    TRFError,
+};
+
+/// A single player metadata record (DIN DAT).
+///
+/// Columns: starting rank number (0 to 3), key (5 to 14), value (from 16). Keys longer
+/// than 10 characters are separated from the value by a space.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerMetadata {
+    /// Starting rank number of the player.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    player: Result<u16, TRFError>,
+
+    /// The key, e.g. `GROUP`.
+    key: String,
+
+    /// The value.
+    value: String,
+}
+
+impl PlayerMetadata {
+    /// The data identification number of a player metadata record.
+    pub const DATA_IDENTIFICATION_NUMBER: &str = "DAT";
+
+    /// The key of the email address of the player.
+    pub const EMAIL: &str = "EMAIL";
+
+    /// The key of the group of the player (e.g. a school or a club).
+    pub const GROUP: &str = "GROUP";
+
+    /// The key of the Tornelo ID of the player.
+    pub const TORNELO_ID: &str = "ID:TORNELO";
+
+    /// Create a player metadata record.
+    #[must_use]
+    pub fn new(player: u16, key: &str, value: &str) -> Self {
+        Self {
+            player: Ok(player),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Get the starting rank number of the player.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn player(&self) -> Result<&u16, &TRFError> {
+        self.player.as_ref()
+    }
+
+    /// Get the key.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Get the value.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Get the fields which were incorrectly formatted, along with their columns.
+    pub(crate) fn errors(&self) -> Vec<(Range<usize>, TRFError)> {
+        field_error(self.player.as_ref(), 0..4)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for PlayerMetadata {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        let data = columns(value, 5..value.chars().count()).trim_start();
+        let (key, text) = data.split_once(' ').unwrap_or((data, ""));
+
+        Ok(Self {
+            player: columns(value, 0..4)
+                .trim()
+                .parse::<u16>()
+                .map_err(Into::into),
+            key: key.to_string(),
+            value: text.trim().to_string(),
+        })
+    }
+}
+
+impl From<&PlayerMetadata> for String {
+    /// Write the player metadata as a TRF line, including the data identification number.
+    fn from(val: &PlayerMetadata) -> Self {
+        format!(
+            "{} {:>4} {:<10} {}",
+            PlayerMetadata::DATA_IDENTIFICATION_NUMBER,
+            val.player
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            val.key,
+            val.value
+        )
+        .trim_end()
+        .to_string()
+    }
+}
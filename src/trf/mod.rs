@@ -1,13 +1,31 @@
-//! # TRF files reader
+//! # TRF files reader and writer
 //!
-//! This is a set of tools to read TRF - Tournament Report Files.
+//! This is a set of tools to read and write TRF - Tournament Report Files.
 //!
-//! It is based on the official reference: C.04.A.10 Annex-2 TRF16  
+//! It is based on the official reference: C.04.A.10 Annex-2 TRF16\
 //! <https://www.fide.com/FIDE/handbook/C04Annex2_TRF16.pdf>
 //!
+//! The records added by the TRF-2025 revision are also supported: the scoring system (see
+//! [`scoring`]), the new header records (see [`tournament`]) and the pairing constraints
+//! (see [`constraint`]). The revision of a file is detected from the records it
+//! contains, see [`FormatVersion`].
+//!
+//! The TRF(x) extension records of the `JaVaFo` pairing engine (XXR, XXC, XXA, XXP and
+//! XXS) are supported as well, see [`extension`], and so are the player metadata
+//! records of Tornelo (DAT and XRS), see [`metadata`].
+//!
 //! ## Status
 //!
-//! Currently only reading player data is supported.
+//! Currently reading player data (see [`player`]), team data (see [`team`]), the
+//! tournament header (see [`tournament`]) and the scoring system (see [`scoring`]) is
+//! supported. Those can be written back to the TRF format, other lines (records of other
+//! programs, comments and blank lines) are kept verbatim, see
+//! [`crate::Situation::unknown_records`].
+//!
+//! Data which can't be read doesn't stop the parser, the problems found are reported as
+//! diagnostics (see [`diagnostic`]). Files can be read strictly or leniently, see
+//! [`ParseMode`]. The round data of the players can be cross-checked,
+//! see [`validation`].
 //!
 //! ## Usage
 //!
@@ -17,7 +35,18 @@ use std::num::{ParseFloatError, ParseIntError};
 
 use thiserror::Error;
 
+pub mod constraint;
+pub mod diagnostic;
+pub mod extension;
+pub mod metadata;
 pub mod player;
+pub(crate) mod record;
+pub mod scoring;
+#[cfg(feature = "serde")]
+pub(crate) mod serialization;
+pub mod team;
+pub mod tournament;
+pub mod validation;
 
 /// An error in the TRF parser.
 ///
@@ -68,4 +97,98 @@ pub enum TRFError {
     /// enough
     #[error("Unexpectedly ran into the end of a string")]
     UnexpectedEndOfString(),
+
+    /// Error when the file uses a non-FIDE line separator (`\n` or `\r\n`) instead of
+    /// `\r`. The separator found is escaped.
+    #[error("Expected the FIDE \\r line separator, found {0}")]
+    NonFideLineSeparator(String),
+
+    /// Error when a record isn't supported by the parser, it is kept as is.
+    #[error("Unsupported record {0}, the line is kept as is")]
+    UnsupportedRecordError(String),
+
+    /// Error when a record added by TRF-2025 is read in [`ParseMode::Strict`].
+    #[error("Record {0} was added by TRF-2025, it isn't part of TRF16")]
+    Trf2025RecordError(String),
+
+    /// Error when a field isn't aligned on its columns (numbers are right-aligned, text
+    /// is left-aligned).
+    #[error("Expected a field aligned on its columns, found string '{0}'")]
+    MisalignedFieldError(String),
+
+    /// Error when two fields aren't separated by a space.
+    #[error("Expected a space between two fields, found character {0}")]
+    MissingSeparatorError(char),
+
+    /// Error when a URL is used where the reference expects text (e.g. the city).
+    #[error("Expected text, found URL {0}")]
+    UnexpectedUrlError(String),
+
+    /// Error when a record refers to a player who doesn't exist (e.g. a player metadata
+    /// record).
+    #[error("Expected the starting rank number of a player, found {0}")]
+    UnknownPlayerError(u16),
+
+    /// Error when a keyword of a `JaVaFo` extension record is unknown (e.g. in XXC or
+    /// XXS).
+    #[error("Expected a known keyword, found string {0}")]
+    UnknownKeywordError(String),
+
+    /// Error of a field which was incorrectly formatted, read back from a serialized
+    /// situation (requires the `serde` feature). Stores the original error message.
+    #[error("{0}")]
+    DeserializedError(String),
+}
+
+/// Revision of the TRF format, see [`crate::Situation::format_version`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FormatVersion {
+    /// C.04.A.10 Annex-2 TRF16.
+    Trf16,
+
+    /// The TRF-2025 revision, which adds the scoring system, the number of rounds, the
+    /// pairing system, the starting rank method, the tie-breaks and the pairing
+    /// constraints.
+    ///
+    /// Player sections keep the TRF16 layout in both revisions, their points follow the
+    /// scoring system of the file (see [`scoring`]).
+    Trf2025,
+}
+
+/// How strictly TRF files are read, see [`crate::Situation::read_with`].
+///
+/// ```
+/// use std::fs;
+/// # use openswisspairings_lib::{trf::ParseMode, Situation};
+///
+/// let file_contents =
+///     fs::read_to_string("lichess_swiss_2024.10.13_hId1KZmF_rapid.trf").unwrap();
+///
+/// // Lichess uses usernames and dates such as "Oct 13, 2024", which aren't TRF16:
+/// assert!(Situation::read_with(&file_contents, ParseMode::Strict).is_err());
+///
+/// let (situation, _) =
+///     Situation::read_with(&file_contents, ParseMode::Lenient).unwrap();
+/// let name = situation.players()[0].name().unwrap().as_ref().unwrap();
+/// assert_eq!(name.last_name(), "badindefense");
+/// assert_eq!(situation.tournament().start_date().unwrap().unwrap().month(), 10);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParseMode {
+    /// Reject any deviation from TRF16: every diagnostic is an error, including the
+    /// non-FIDE line separators, unsupported records, the records added by TRF-2025 (see
+    /// [`FormatVersion::Trf2025`]), misaligned fields and URLs in the city record
+    /// (DIN 022).
+    ///
+    /// Used for endorsement testing.
+    Strict,
+
+    /// Accept the formats found in real-world files: usernames instead of names, dates
+    /// such as "Oct 13, 2024", and titles in the short format of some programs (see
+    /// [`player::fields`]). Problems which don't lose data are only warnings.
+    ///
+    /// Used to import files from other programs and websites.
+    #[default]
+    Lenient,
 }
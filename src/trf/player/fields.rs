@@ -4,12 +4,13 @@
 //! contained inside of [`crate::trf::player::Section`].
 //!
 //! See also: [`crate::trf::player::round`].
-use crate::trf::TRFError;
+use crate::trf::{ParseMode, TRFError};
 
 use super::utils::parse_number;
 
 /// The player's gender.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sex {
     /// Man ("m")
     Man,
@@ -18,12 +19,36 @@ pub enum Sex {
     Woman,
 }
 
+impl TryFrom<&str> for Sex {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "m" => Ok(Self::Man),
+            "w" => Ok(Self::Woman),
+            other => Err(TRFError::InvalidGenderError(other.to_string())),
+        }
+    }
+}
+
+impl From<&Sex> for String {
+    fn from(val: &Sex) -> Self {
+        match val {
+            Sex::Man => "m",
+            Sex::Woman => "w",
+        }
+        .to_string()
+    }
+}
+
 /// The player's title.
 ///
 /// Corresponds to official FIDE titles.
 ///
-/// > Note: In most TRF files a different format seems to be used ("m", "g"???).
+/// > Note: In most TRF files a different format seems to be used ("m", "g"???). Those are
+/// > read in [`ParseMode::Lenient`].
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)] // F it
 pub enum Title {
     Grandmaster,
@@ -36,12 +61,36 @@ pub enum Title {
     WomanCandidateMaster,
 }
 
+impl Title {
+    /// Read a title.
+    ///
+    /// In [`ParseMode::Lenient`], titles are case insensitive and the short format of
+    /// some pairing programs is accepted ("g", "m", "f", "c", "wg", "wm", "wf", "wc").
+    pub(crate) fn read(value: &str, mode: ParseMode) -> Result<Self, TRFError> {
+        Self::try_from(value).or_else(|error| {
+            if mode == ParseMode::Strict {
+                return Err(error);
+            }
+
+            Ok(match value.to_lowercase().as_str() {
+                "gm" | "g" => Self::Grandmaster,
+                "im" | "m" => Self::InternationalMaster,
+                "wgm" | "wg" => Self::WomanGrandmaster,
+                "fm" | "f" => Self::FIDEMaster,
+                "wim" | "wm" => Self::WomanInternationalMaster,
+                "cm" | "c" => Self::CandidateMaster,
+                "wfm" | "wf" => Self::WomanFIDEMaster,
+                "wcm" | "wc" => Self::WomanCandidateMaster,
+                _ => return Err(error),
+            })
+        })
+    }
+}
+
 impl TryFrom<&str> for Title {
     type Error = TRFError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        // TODO: Parse weird format ('m', 'g')??
-
         Ok(match value {
             "GM" => Self::Grandmaster,
             "IM" => Self::InternationalMaster,
@@ -56,13 +105,31 @@ impl TryFrom<&str> for Title {
     }
 }
 
+impl From<&Title> for String {
+    fn from(val: &Title) -> Self {
+        match val {
+            Title::Grandmaster => "GM",
+            Title::InternationalMaster => "IM",
+            Title::WomanGrandmaster => "WGM",
+            Title::FIDEMaster => "FM",
+            Title::WomanInternationalMaster => "WIM",
+            Title::CandidateMaster => "CM",
+            Title::WomanFIDEMaster => "WFM",
+            Title::WomanCandidateMaster => "WCM",
+        }
+        .to_string()
+    }
+}
+
 /// The player's name.
 ///
 /// Is split into last name and first name according to the reference.
 ///
 /// > Note: In some TRF files from online chess websites, an username is used instead of a
-/// > full name. In that case **the player's username will not be saved**.
+/// > full name. In [`ParseMode::Lenient`] the username is saved as the last name, with an
+/// > empty first name.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Name {
     /// The player's last name.
     last_name: String,
@@ -72,6 +139,15 @@ pub struct Name {
 }
 
 impl Name {
+    /// Create a name.
+    #[must_use]
+    pub fn new(last_name: &str, first_name: &str) -> Self {
+        Self {
+            last_name: last_name.to_string(),
+            first_name: first_name.to_string(),
+        }
+    }
+
     /// Get the player's last name.
     #[must_use]
     pub fn last_name(&self) -> &str {
@@ -83,6 +159,17 @@ impl Name {
     pub fn first_name(&self) -> &str {
         &self.first_name
     }
+
+    /// Read a name.
+    ///
+    /// In [`ParseMode::Lenient`], a name which isn't in the 'Lastname, Firstname' format
+    /// (e.g. a username) is read as the last name.
+    pub(crate) fn read(value: &str, mode: ParseMode) -> Result<Self, TRFError> {
+        Self::try_from(value).or_else(|error| match mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => Ok(Self::new(value.trim(), "")),
+        })
+    }
 }
 
 impl TryFrom<&str> for Name {
@@ -101,14 +188,26 @@ impl TryFrom<&str> for Name {
     }
 }
 
+impl From<&Name> for String {
+    fn from(val: &Name) -> Self {
+        if val.first_name.is_empty() {
+            val.last_name.clone()
+        } else {
+            format!("{}, {}", val.last_name, val.first_name)
+        }
+    }
+}
+
 /// A date.
 ///
-/// This is only used in [`crate::trf::player::Section::birth_date`].
+/// This is used in [`crate::trf::player::Section::birth_date`] and in the tournament
+/// header, see [`crate::trf::tournament::TournamentData`].
 ///
 /// This doesn't rely on an external library because:
 /// 1. It would add yet another dependency
 /// 2. The documentation is unclear about this field
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Date {
     /// Year.
     ///
@@ -127,6 +226,12 @@ pub struct Date {
 }
 
 impl Date {
+    /// Create a date.
+    #[must_use]
+    pub const fn new(year: u16, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+
     /// Get [`Date.year`](Date#structfield.year).
     #[must_use]
     pub const fn year(&self) -> u16 {
@@ -144,6 +249,62 @@ impl Date {
     pub const fn day(&self) -> u8 {
         self.day
     }
+
+    /// Read a date.
+    ///
+    /// In [`ParseMode::Lenient`], the formats used by some websites and programs are also
+    /// accepted: "Oct 13, 2024", "October 13, 2024", "2024-10-13", "2024.10.13" and
+    /// "13.10.2024".
+    pub(crate) fn read(value: &str, mode: ParseMode) -> Result<Self, TRFError> {
+        Self::try_from(value).or_else(|error| match mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => Self::read_lenient(value).ok_or(error),
+        })
+    }
+
+    /// Read a date in one of the formats accepted by [`ParseMode::Lenient`].
+    fn read_lenient(value: &str) -> Option<Self> {
+        /// The first three letters of each month.
+        const MONTHS: [&str; 12] = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov",
+            "dec",
+        ];
+
+        let parts: Vec<&str> = value
+            .split(['-', '.', ' ', ','])
+            .filter(|part| !part.is_empty())
+            .collect();
+        let number = |part: &&str| part.parse::<u16>().ok();
+
+        let (year, month, day) = match parts.as_slice() {
+            [month, day, year] if month.starts_with(char::is_alphabetic) => {
+                let month = month.to_lowercase();
+                let month = MONTHS
+                    .iter()
+                    .zip(1..)
+                    .find(|(name, _)| month.starts_with(*name))?
+                    .1;
+                (number(year)?, month, number(day)?)
+            }
+            [year, month, day] if year.len() == 4 => {
+                (number(year)?, number(month)?, number(day)?)
+            }
+            [day, month, year] if year.len() == 4 => {
+                (number(year)?, number(month)?, number(day)?)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            year,
+            month: u8::try_from(month)
+                .ok()
+                .filter(|month| (1..=12).contains(month))?,
+            day: u8::try_from(day)
+                .ok()
+                .filter(|day| (1..=31).contains(day))?,
+        })
+    }
 }
 
 impl TryFrom<&str> for Date {
@@ -154,7 +315,7 @@ impl TryFrom<&str> for Date {
 
         if parts.len() != 3 {
             return Err(TRFError::InvalidDateError(value.to_string()));
-        };
+        }
 
         Ok(Self {
             year: parse_number(
@@ -184,3 +345,9 @@ impl TryFrom<&str> for Date {
         })
     }
 }
+
+impl From<&Date> for String {
+    fn from(val: &Date) -> Self {
+        format!("{:04}/{:02}/{:02}", val.year, val.month, val.day)
+    }
+}
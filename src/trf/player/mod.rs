@@ -10,21 +10,26 @@
 //! - [`Ok`]\([`None`]) means the field was empty
 //! - [`Ok`]\([`Some`]) contains data
 //!
-//! Currently no getters are available, however we do plan to add them.
+//! Getter methods are available to retrieve information. A section can be created with
+//! [`Section::new`] and filled with setter methods, see also [`crate::edit`] to edit the
+//! players of a [`crate::Situation`].
+use std::{collections::BTreeMap, ops::Range};
+
 use fields::{Date, Name, Sex, Title};
 use round::PlayerRoundSection;
 
-use super::TRFError;
+use super::{metadata::PlayerMetadata, ParseMode, TRFError};
 
 pub mod fields;
 pub mod round;
 pub(crate) mod utils;
 
-use utils::{parse_into, parse_number};
+use utils::{columns, field_error, format_field, parse_number, parse_with};
 
 /// Player section, stores all information about a player.
 ///
-/// You can load this struct from a TRF file using [`crate::Situation::try_from`].
+/// You can load this struct from a TRF file using [`crate::Situation::try_from`], and
+/// write it back as a TRF line using [`String::from`].
 ///
 /// > You might think this is ugly
 /// >
@@ -34,34 +39,39 @@ use utils::{parse_into, parse_number};
 ///
 /// ###### TODO
 ///
-/// - [ ] Getters
-/// - [ ] Setters
-/// - [ ] Export to TRF
+/// - [x] Getters
+/// - [x] Setters
+/// - [x] Export to TRF
 #[derive(Debug)]
-#[allow(dead_code)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Section {
     /// Player starting rank number.
     ///
     /// Serves as a unique ID assigned to each player for each [`crate::Situation`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     starting_rank_number: Result<u16, TRFError>,
 
     /// Player gender.
     ///
     /// See [`Sex`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     sex: Result<Option<Sex>, TRFError>,
 
     /// Player title.
     ///
     /// See [`Title`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     title: Result<Option<Title>, TRFError>,
 
     /// Player name.
     ///
     /// Split into last name and first name according to the TRF file reference, see
     /// [`Name`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     name: Result<Option<Name>, TRFError>,
 
     /// Player FIDE rating.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     fide_rating: Result<Option<u16>, TRFError>,
 
     /// FIDE Federation of player
@@ -75,9 +85,11 @@ pub struct Section {
     /// This is a unique ID identifiying the player in FIDE's database, however it isn't
     /// always present, rely on [`Section::starting_rank_number`] instead. You should only
     /// use this with FIDE services.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     fide_number: Result<Option<u64>, TRFError>,
 
     /// Player birth date.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     birth_date: Result<Option<Date>, TRFError>,
 
     /// Player total points.
@@ -87,6 +99,7 @@ pub struct Section {
     /// the same as a win). If, for instance, the 3/1/0 scoring point system is applied in
     /// a tournament and a player scored 5 wins, 2 draws and 2 losses, this field should
     /// contain "17.0".
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     points: Result<Option<f32>, TRFError>,
 
     /// Player final ranking.
@@ -94,57 +107,391 @@ pub struct Section {
     /// Exact definition, especially for Team.
     ///
     /// > I don't know what any of this means...
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     rank: Result<Option<u16>, TRFError>,
 
     /// Information about each round played by the player in the tournament.
     ///
     /// TODO: Use [`Result<Option>`]
     rounds: Vec<Option<PlayerRoundSection>>,
+
+    /// Metadata of the player, by key.
+    ///
+    /// Read from the metadata records of Tornelo (DIN DAT), see
+    /// [`crate::trf::metadata`].
+    metadata: BTreeMap<String, String>,
 }
 
 impl Section {
     /// The data identification number of a player section.
     pub const DATA_IDENTIFICATION_NUMBER: &str = "001";
-}
 
-impl TryFrom<String> for Section {
-    type Error = TRFError;
+    /// Create a player section, every field other than the starting rank number is empty.
+    #[must_use]
+    pub const fn new(starting_rank_number: u16) -> Self {
+        Self {
+            starting_rank_number: Ok(starting_rank_number),
+            sex: Ok(None),
+            title: Ok(None),
+            name: Ok(None),
+            fide_rating: Ok(None),
+            fide_federation: None,
+            fide_number: Ok(None),
+            birth_date: Ok(None),
+            points: Ok(None),
+            rank: Ok(None),
+            rounds: vec![],
+            metadata: BTreeMap::new(),
+        }
+    }
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        // The first four characters (Data Identification Number and space)
-        // shouldn't be included in 'value'
-        //
-        // Range: [a-5, b-4]
-        println!("> {}", &value[0..4]);
+    /// Get the player starting rank number.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn starting_rank_number(&self) -> Result<&u16, &TRFError> {
+        self.starting_rank_number.as_ref()
+    }
+
+    /// Get the player gender.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid gender. See [`Sex`].
+    pub const fn sex(&self) -> Result<&Option<Sex>, &TRFError> {
+        self.sex.as_ref()
+    }
+
+    /// Get the player title.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid title. See [`Title`].
+    pub const fn title(&self) -> Result<&Option<Title>, &TRFError> {
+        self.title.as_ref()
+    }
+
+    /// Get the player name.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid name. See [`Name`].
+    pub const fn name(&self) -> Result<&Option<Name>, &TRFError> {
+        self.name.as_ref()
+    }
+
+    /// Get the player FIDE rating.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn fide_rating(&self) -> Result<&Option<u16>, &TRFError> {
+        self.fide_rating.as_ref()
+    }
+
+    /// Get the player FIDE federation.
+    #[must_use]
+    pub fn fide_federation(&self) -> Option<&str> {
+        self.fide_federation.as_deref()
+    }
+
+    /// Get the player FIDE number.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn fide_number(&self) -> Result<&Option<u64>, &TRFError> {
+        self.fide_number.as_ref()
+    }
+
+    /// Get the player birth date.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid date. See [`Date`].
+    pub const fn birth_date(&self) -> Result<&Option<Date>, &TRFError> {
+        self.birth_date.as_ref()
+    }
+
+    /// Get the player total points.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn points(&self) -> Result<&Option<f32>, &TRFError> {
+        self.points.as_ref()
+    }
+
+    /// Get the player final ranking.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn rank(&self) -> Result<&Option<u16>, &TRFError> {
+        self.rank.as_ref()
+    }
+
+    /// Get information about each round played by the player.
+    ///
+    /// A round is [`None`] if it couldn't be read.
+    #[must_use]
+    pub fn rounds(&self) -> &[Option<PlayerRoundSection>] {
+        &self.rounds
+    }
+
+    /// Get the metadata of the player, by key.
+    ///
+    /// See [`crate::trf::metadata`].
+    #[must_use]
+    pub const fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Get the metadata value of a key, e.g. [`PlayerMetadata::EMAIL`].
+    #[must_use]
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Get the group of the player (e.g. a school or a club).
+    #[must_use]
+    pub fn group(&self) -> Option<&str> {
+        self.metadata_value(PlayerMetadata::GROUP)
+    }
+
+    /// Get the Tornelo ID of the player.
+    #[must_use]
+    pub fn tornelo_id(&self) -> Option<&str> {
+        self.metadata_value(PlayerMetadata::TORNELO_ID)
+    }
 
+    /// Get the fields which were incorrectly formatted, along with their columns.
+    ///
+    /// Columns don't include the data identification number and the following space.
+    pub(crate) fn errors(&self) -> Vec<(Range<usize>, TRFError)> {
+        let rounds = (87..).step_by(10).zip(&self.rounds).flat_map(|(n, round)| {
+            round.iter().flat_map(move |round| {
+                [
+                    field_error(round.id(), n..n + 4),
+                    field_error(round.color(), n + 5..n + 6),
+                    field_error(round.result(), n + 7..n + 8),
+                ]
+            })
+        });
+
+        [
+            field_error(self.starting_rank_number.as_ref(), 0..4),
+            field_error(self.sex.as_ref(), 5..6),
+            field_error(self.title.as_ref(), 6..9),
+            field_error(self.name.as_ref(), 10..43),
+            field_error(self.fide_rating.as_ref(), 44..48),
+            field_error(self.fide_number.as_ref(), 53..64),
+            field_error(self.birth_date.as_ref(), 65..75),
+            field_error(self.points.as_ref(), 76..80),
+            field_error(self.rank.as_ref(), 81..85),
+        ]
+        .into_iter()
+        .chain(rounds)
+        .flatten()
+        .collect()
+    }
+
+    /// Read a player section.
+    ///
+    /// The first four characters (data identification number and space) shouldn't be
+    /// included in `value`.
+    pub(crate) fn read(value: &str, mode: ParseMode) -> Self {
+        // Range: [a-5, b-4]
         let mut rounds: Vec<Option<PlayerRoundSection>> = vec![];
         let mut n: usize = 87;
-        while value.len() >= n + 7 {
-            rounds.push(PlayerRoundSection::try_from(&value[n..n + 8]).ok());
+        while value.chars().count() >= n + 7 {
+            rounds.push(PlayerRoundSection::try_from(columns(value, n..n + 8)).ok());
             n += 10;
         }
 
-        Ok(Self {
-            starting_rank_number: value[0..4].trim().parse::<u16>().map_err(Into::into),
-            sex: match &value[5..6] {
-                "m" => Ok(Some(Sex::Man)),
-                "w" => Ok(Some(Sex::Woman)),
-                " " => Ok(None),
-                other => Err(TRFError::InvalidGenderError(other.to_string())),
+        Self {
+            starting_rank_number: columns(value, 0..4)
+                .trim()
+                .parse::<u16>()
+                .map_err(Into::into),
+            sex: match columns(value, 5..6) {
+                " " | "" => Ok(None),
+                other => Sex::try_from(other).map(Some),
             },
-            title: parse_into(&value[6..9]),
-            name: parse_into(&value[10..43]),
-            fide_rating: parse_number(&value[44..48]),
-            fide_federation: Some(value[49..52].trim().to_string())
+            title: parse_with(columns(value, 6..9), |i| Title::read(i, mode)),
+            name: parse_with(columns(value, 10..43), |i| Name::read(i, mode)),
+            fide_rating: parse_number(columns(value, 44..48)),
+            fide_federation: Some(columns(value, 49..52).trim().to_string())
                 .filter(|s| !s.is_empty()),
-            fide_number: parse_number(&value[53..64]),
-            birth_date: match value[65..75].trim() {
-                "" => Ok(None),
-                other => Date::try_from(other).map(Some),
-            }, // [65..75]
-            points: parse_number(&value[76..80]),
-            rank: parse_number(&value[81..85]),
+            fide_number: parse_number(columns(value, 53..64)),
+            birth_date: parse_with(columns(value, 65..75), |i| Date::read(i, mode)),
+            points: parse_number(columns(value, 76..80)),
+            rank: parse_number(columns(value, 81..85)),
             rounds,
-        })
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Get the deviations from the column layout of TRF16, only reported in
+    /// [`ParseMode::Strict`].
+    ///
+    /// Fields must be separated by spaces, numbers must be right-aligned and text must be
+    /// left-aligned. Columns don't include the data identification number and the
+    /// following space.
+    pub(crate) fn strict_errors(value: &str) -> Vec<(Range<usize>, TRFError)> {
+        let length = value.chars().count();
+        let rounds = (87..length).step_by(10);
+
+        let separators = [4, 9, 43, 48, 52, 64, 75, 80, 85]
+            .into_iter()
+            .chain(rounds.clone().flat_map(|n| [n - 2, n - 1, n + 4, n + 6]))
+            .filter_map(|n| {
+                let character = columns(value, n..n + 1).chars().next()?;
+                (character != ' ')
+                    .then(|| (n..n + 1, TRFError::MissingSeparatorError(character)))
+            });
+
+        let right_aligned = [0..4, 44..48, 53..64, 76..80, 81..85]
+            .into_iter()
+            .chain(rounds.map(|n| n..n + 4))
+            .filter(|n| columns(value, n.clone()).ends_with(' '));
+        let left_aligned = [10..43, 49..52, 65..75]
+            .into_iter()
+            .filter(|n| columns(value, n.clone()).starts_with(' '));
+        let misaligned = right_aligned
+            .chain(left_aligned)
+            .filter(|n| !columns(value, n.clone()).trim().is_empty())
+            .map(|n| {
+                let field = columns(value, n.clone()).to_string();
+                (n, TRFError::MisalignedFieldError(field))
+            });
+
+        separators.chain(misaligned).collect()
+    }
+
+    /// Set the player starting rank number.
+    ///
+    /// The opponents of the player still refer to the previous starting rank number, use
+    /// [`crate::Situation::add_player`] to add a player to a situation.
+    pub fn set_starting_rank_number(&mut self, starting_rank_number: u16) {
+        self.starting_rank_number = Ok(starting_rank_number);
+    }
+
+    /// Set the player gender.
+    pub fn set_sex(&mut self, sex: Option<Sex>) {
+        self.sex = Ok(sex);
+    }
+
+    /// Set the player title.
+    pub fn set_title(&mut self, title: Option<Title>) {
+        self.title = Ok(title);
+    }
+
+    /// Set the player name.
+    pub fn set_name(&mut self, name: Option<Name>) {
+        self.name = Ok(name);
+    }
+
+    /// Set the player FIDE rating.
+    pub fn set_fide_rating(&mut self, fide_rating: Option<u16>) {
+        self.fide_rating = Ok(fide_rating);
+    }
+
+    /// Set the player FIDE federation.
+    pub fn set_fide_federation(&mut self, fide_federation: Option<String>) {
+        self.fide_federation = fide_federation;
+    }
+
+    /// Set the player FIDE number.
+    pub fn set_fide_number(&mut self, fide_number: Option<u64>) {
+        self.fide_number = Ok(fide_number);
+    }
+
+    /// Set the player birth date.
+    pub fn set_birth_date(&mut self, birth_date: Option<Date>) {
+        self.birth_date = Ok(birth_date);
+    }
+
+    /// Set the player total points.
+    pub fn set_points(&mut self, points: Option<f32>) {
+        self.points = Ok(points);
+    }
+
+    /// Set the player final ranking.
+    pub fn set_rank(&mut self, rank: Option<u16>) {
+        self.rank = Ok(rank);
+    }
+
+    /// Set the metadata value of a key.
+    ///
+    /// Returns the previous value of the key, if any.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Option<String> {
+        self.metadata.insert(key.to_string(), value.to_string())
+    }
+
+    /// Change the starting rank numbers of the opponents, see
+    /// [`crate::Situation::renumber_players`].
+    pub(crate) fn renumber_opponents(&mut self, renumbered: impl Fn(u16) -> u16) {
+        for section in self.rounds.iter_mut().flatten() {
+            if let Ok(Some(id)) = section.id() {
+                let id = renumbered(*id);
+                section.set_id(Some(id));
+            }
+        }
+    }
+
+    /// Set the section of a round (rounds start at 1).
+    ///
+    /// Empty rounds are added if the player has fewer rounds.
+    pub fn set_round(&mut self, round: u16, section: Option<PlayerRoundSection>) {
+        let index = usize::from(round.saturating_sub(1));
+        if self.rounds.len() <= index {
+            self.rounds.resize_with(index + 1, || None);
+        }
+        if let Some(i) = self.rounds.get_mut(index) {
+            *i = section;
+        }
+    }
+}
+
+impl TryFrom<String> for Section {
+    type Error = TRFError;
+
+    /// Read a player section in the default [`ParseMode`].
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(Self::read(&value, ParseMode::default()))
+    }
+}
+
+impl From<&Section> for String {
+    /// Write the player section as a TRF line, including the data identification number.
+    ///
+    /// Fields which were incorrectly formatted are left empty.
+    fn from(val: &Section) -> Self {
+        let mut line: Self = format!(
+            "{} {:>4.4} {:1.1}{:>3.3} {:<33.33} {:>4.4} {:<3.3} {:>11.11} {:<10.10} {:>4.4} {:>4.4}",
+            Section::DATA_IDENTIFICATION_NUMBER,
+            val.starting_rank_number
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            format_field(&val.sex, |value| value.into()),
+            format_field(&val.title, |value| value.into()),
+            format_field(&val.name, |value| value.into()),
+            format_field(&val.fide_rating, ToString::to_string),
+            val.fide_federation.as_deref().unwrap_or_default(),
+            format_field(&val.fide_number, ToString::to_string),
+            format_field(&val.birth_date, |value| value.into()),
+            format_field(&val.points, |points| format!("{points:.1}")),
+            format_field(&val.rank, ToString::to_string),
+        );
+
+        line.extend(val.rounds.iter().map(|round| {
+            let round: Self = round.as_ref().map(Into::into).unwrap_or_default();
+            format!("  {round:8}")
+        }));
+
+        line.trim_end().to_string()
     }
 }
@@ -5,17 +5,20 @@
 //!
 //! ## Usage
 //!
-//! This struct isn't meant to be instanced directly. See [`crate::Situation::try_from`]
-//! instead.
+//! Round sections are usually read with [`crate::Situation::try_from`], use
+//! [`PlayerRoundSection::new`] to create one (e.g. to enter a result).
 //!
 //! Getter methods are available to retrieve information. Currently no setter methods are
 //! available.
+//!
+//! A round section can be written back to the TRF format with [`String::from`].
 use crate::trf::TRFError;
 
-use super::utils::{parse_into, parse_number};
+use super::utils::{columns, format_field, parse_into, parse_number};
 
 /// Scheduled color or forfeit in round.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// White side.
     White,
@@ -41,8 +44,20 @@ impl TryFrom<&str> for Color {
     }
 }
 
+impl From<Color> for String {
+    fn from(val: Color) -> Self {
+        match val {
+            Color::White => "w",
+            Color::Black => "b",
+            Color::None => "-",
+        }
+        .to_string()
+    }
+}
+
 /// Result of round
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Result {
     /// The scheduled game was not played
     NotPlayed(NotPlayedRoundResult),
@@ -94,8 +109,46 @@ impl From<Result> for String {
     }
 }
 
+impl Result {
+    /// Get the result of the opponent of a player with this result.
+    ///
+    /// A forfeit loss gives a forfeit win to the opponent. Returns [`None`] for byes,
+    /// which don't have an opponent.
+    #[must_use]
+    pub const fn opponent_result(self) -> Option<Self> {
+        Some(match self {
+            Self::NotPlayed(NotPlayedRoundResult::ForfeitLoss) => {
+                Self::NotPlayed(NotPlayedRoundResult::ForfeitWin)
+            }
+            Self::NotPlayed(NotPlayedRoundResult::ForfeitWin) => {
+                Self::NotPlayed(NotPlayedRoundResult::ForfeitLoss)
+            }
+            Self::LessThanOneMove(LessThanOneMoveRoundResult::Win) => {
+                Self::LessThanOneMove(LessThanOneMoveRoundResult::Loss)
+            }
+            Self::LessThanOneMove(LessThanOneMoveRoundResult::Draw) => {
+                Self::LessThanOneMove(LessThanOneMoveRoundResult::Draw)
+            }
+            Self::LessThanOneMove(LessThanOneMoveRoundResult::Loss) => {
+                Self::LessThanOneMove(LessThanOneMoveRoundResult::Win)
+            }
+            Self::Regular(RegularRoundResult::Win) => {
+                Self::Regular(RegularRoundResult::Loss)
+            }
+            Self::Regular(RegularRoundResult::Draw) => {
+                Self::Regular(RegularRoundResult::Draw)
+            }
+            Self::Regular(RegularRoundResult::Loss) => {
+                Self::Regular(RegularRoundResult::Win)
+            }
+            Self::Bye(_) => return None,
+        })
+    }
+}
+
 /// The scheduled game was not played
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NotPlayedRoundResult {
     /// Forfeit loss
     ForfeitLoss,
@@ -117,7 +170,8 @@ impl From<NotPlayedRoundResult> for String {
 /// The scheduled game lasted less than one move
 ///
 /// Not rated
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LessThanOneMoveRoundResult {
     /// Win
     ///
@@ -147,7 +201,8 @@ impl From<LessThanOneMoveRoundResult> for String {
 }
 
 /// Regular game
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegularRoundResult {
     /// Win
     Win,
@@ -171,7 +226,8 @@ impl From<RegularRoundResult> for String {
 }
 
 /// Bye
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ByeRoundResult {
     /// Half-point-bye
     ///
@@ -214,18 +270,36 @@ impl From<ByeRoundResult> for String {
 ///
 /// > **TODO:** Use Result
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerRoundSection {
     /// Player or forfeit id.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     id: std::result::Result<Option<u16>, TRFError>,
 
     /// Scheduled color or forfeit.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     color: std::result::Result<Option<Color>, TRFError>,
 
     /// Result.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
     result: std::result::Result<Option<Result>, TRFError>,
 }
 
 impl PlayerRoundSection {
+    /// Create a round section.
+    #[must_use]
+    pub const fn new(
+        id: Option<u16>,
+        color: Option<Color>,
+        result: Option<Result>,
+    ) -> Self {
+        Self {
+            id: Ok(id),
+            color: Ok(color),
+            result: Ok(result),
+        }
+    }
+
     /// Get the player or forfeit id.
     ///
     /// # Errors
@@ -252,23 +326,39 @@ impl PlayerRoundSection {
     pub const fn result(&self) -> std::result::Result<&Option<Result>, &TRFError> {
         self.result.as_ref()
     }
+
+    /// Set the player or forfeit id.
+    pub fn set_id(&mut self, id: Option<u16>) {
+        self.id = Ok(id);
+    }
 }
 
 impl TryFrom<&str> for PlayerRoundSection {
     type Error = TRFError;
 
     fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        if value.len() < 7 {
+        if value.chars().count() < 7 {
             return Err(TRFError::PlayerRoundSectionTooShort(value.to_string()));
-        };
+        }
 
         Ok(Self {
-            id: match value[0..4].trim() {
+            id: match columns(value, 0..4).trim() {
                 "0000" | "" => Ok(None),
                 other => parse_number(other),
             },
-            color: parse_into(&value[5..6]),
-            result: parse_into(&value[7..8]),
+            color: parse_into(columns(value, 5..6)),
+            result: parse_into(columns(value, 7..8)),
         })
     }
 }
+
+impl From<&PlayerRoundSection> for String {
+    fn from(val: &PlayerRoundSection) -> Self {
+        format!(
+            "{:>4} {:1} {:1}",
+            format_field(&val.id, ToString::to_string),
+            format_field(&val.color, |color| (*color).into()),
+            format_field(&val.result, |result| (*result).into()),
+        )
+    }
+}
@@ -7,7 +7,9 @@
 //! parser.
 //!
 //! See [`crate::trf::player#Usage`].
-use std::str::FromStr;
+use std::{ops::Range, str::FromStr};
+
+use crate::trf::TRFError;
 
 /// Parse a number from a TRF file.
 pub fn parse_number<T: FromStr, E: From<<T as FromStr>::Err>>(
@@ -26,6 +28,62 @@ where
 {
     match value.trim() {
         "" => Ok(None),
-        other => other.try_into().map(Some).map_err(From::from),
+        other => other.try_into().map(Some),
+    }
+}
+
+/// Parse a value from a TRF file using a parser function, e.g. to choose the
+/// [`crate::trf::ParseMode`].
+pub fn parse_with<T, E>(
+    value: &str,
+    parser: impl Fn(&str) -> Result<T, E>,
+) -> Result<Option<T>, E> {
+    match value.trim() {
+        "" => Ok(None),
+        other => parser(other).map(Some),
     }
 }
+
+/// Get the characters in `range` of a TRF line.
+///
+/// TRF files are column based, so the range is counted in characters and not in bytes.
+/// Columns past the end of the line are ignored, which means this returns an empty
+/// string if the line is too short.
+pub fn columns(value: &str, range: Range<usize>) -> &str {
+    let byte_index = |n: usize| {
+        value
+            .char_indices()
+            .nth(n)
+            .map_or(value.len(), |(index, _)| index)
+    };
+
+    let start = byte_index(range.start);
+    let end = byte_index(range.end).max(start);
+
+    value.get(start..end).unwrap_or_default()
+}
+
+/// Format a field for a TRF file.
+///
+/// Fields which are empty or were incorrectly formatted are written as an empty string.
+pub fn format_field<T, E>(
+    field: &Result<Option<T>, E>,
+    format: impl Fn(&T) -> String,
+) -> String {
+    field
+        .as_ref()
+        .ok()
+        .and_then(Option::as_ref)
+        .map(format)
+        .unwrap_or_default()
+}
+
+/// Get the error of a field along with its columns, if it was incorrectly formatted.
+///
+/// Used to report the errors of a line, see [`crate::trf::diagnostic`].
+pub fn field_error<T>(
+    field: Result<T, &TRFError>,
+    columns: Range<usize>,
+) -> Option<(Range<usize>, TRFError)> {
+    field.err().map(|error| (columns, error.clone()))
+}
@@ -0,0 +1,98 @@
+//! # TRF Records
+//!
+//! A TRF file is a list of records, one per line. The parser stores the data of each
+//! record in the corresponding struct, and keeps track of the order of the lines here so
+//! that a [`crate::Situation`] can be written back in the same layout.
+//!
+//! Each record also keeps its original text. When writing, a record whose data hasn't
+//! changed is written back verbatim, which preserves formatting quirks (padding, `0000`
+//! instead of blank ids, etc.) that don't affect the data.
+
+use super::tournament::TournamentData;
+
+/// A single line of a TRF file.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Record {
+    /// What this line contains.
+    pub kind: RecordKind,
+
+    /// The original text of the line, without the line separator.
+    ///
+    /// This is [`None`] if the record wasn't read from a file.
+    pub source: Option<String>,
+}
+
+/// What a single line of a TRF file contains.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordKind {
+    /// A player section, stores the index of the player in [`crate::Situation::players`].
+    Player(usize),
+
+    /// A team section, stores the index of the team in [`crate::Situation::teams`].
+    Team(usize),
+
+    /// A tournament header record, stores the data identification number and the index
+    /// of the record for records which can be repeated (e.g. deputy chief arbiters).
+    Tournament(String, usize),
+
+    /// A point-value record of the scoring system, stores the data identification number.
+    Scoring(String),
+
+    /// An absent players record, stores the index of the record in
+    /// [`crate::Situation::absent_players`].
+    AbsentPlayers(usize),
+
+    /// An acceleration record, stores the index of the record in
+    /// [`crate::Situation::accelerations`].
+    Acceleration(usize),
+
+    /// A forbidden pairs record, stores the index of the record in
+    /// [`crate::Situation::forbidden_pairs`].
+    ForbiddenPairs(usize),
+
+    /// A player metadata record, stores the index of the player in
+    /// [`crate::Situation::players`] and the key.
+    Metadata(usize, String),
+
+    /// A `JaVaFo` extension record, stores the data identification number and the index
+    /// of the record for records which can be repeated (e.g. accelerations).
+    Extension(String, usize),
+
+    /// A line the parser doesn't read (blank lines, comments, unsupported records...).
+    ///
+    /// It is always written back verbatim from [`Record::source`].
+    Other,
+}
+
+impl RecordKind {
+    /// Whether the record was added by the TRF-2025 revision, see
+    /// [`crate::trf::FormatVersion`].
+    pub(crate) fn trf_2025(&self) -> bool {
+        match self {
+            Self::Tournament(din, _) => {
+                TournamentData::TRF_2025_RECORDS.contains(&din.as_str())
+            }
+            Self::Scoring(_)
+            | Self::AbsentPlayers(_)
+            | Self::Acceleration(_)
+            | Self::ForbiddenPairs(_) => true,
+            Self::Player(_)
+            | Self::Team(_)
+            | Self::Metadata(..)
+            | Self::Extension(..)
+            | Self::Other => false,
+        }
+    }
+}
+
+impl Record {
+    /// Create a record from a line of a file.
+    pub fn read(kind: RecordKind, source: &str) -> Self {
+        Self {
+            kind,
+            source: Some(source.to_string()),
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! # TRF Scoring System
+//!
+//! This module contains the scoring system of a tournament: the points given for each
+//! round result. It is read from the point-value records of TRF-2025 (DIN BBW, BBD, BBL,
+//! BBZ, BBF and BBU), and defaults to the standard 1 / ½ / 0 scoring system.
+//!
+//! Results which don't have their own record use the value of the closest result: a
+//! forfeit win or a full-point bye is worth a win, a half-point bye is worth a draw.
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::Situation;
+//! let file_contents = "\
+//! 012 Football League
+//! BBW 3.0
+//! BBD 1.0
+//! 001    1      Smith, John                       2100                             4.0          2 w 1     3 b =
+//! 001    2      Doe, Jane                         2000                             1.0          1 b 0     3 w =
+//! 001    3      Roe, Richard                      1900                             3.0          2 b =     1 w =";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let scoring_system = situation.scoring_system();
+//!
+//! assert_eq!(scoring_system.win(), 3.0);
+//! assert_eq!(scoring_system.score(&situation.players()[0]), 4.0);
+//!
+//! // The points column of the third player is wrong:
+//! let mismatches = situation.verify_points();
+//! assert_eq!(mismatches.len(), 1);
+//! assert_eq!(mismatches[0].player(), 3);
+//! assert_eq!(mismatches[0].computed(), 2.0);
+//! ```
+use std::ops::Range;
+
+use super::{
+    player::{
+        round::{
+            ByeRoundResult, LessThanOneMoveRoundResult, NotPlayedRoundResult,
+            RegularRoundResult, Result as RoundResult,
+        },
+        utils::{field_error, format_field, parse_number},
+        Section,
+    },
+    TRFError,
+};
+
+/// The points given for each round result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoringSystem {
+    /// Points for a win (BBW).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    win: Result<Option<f32>, TRFError>,
+
+    /// Points for a draw (BBD).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    draw: Result<Option<f32>, TRFError>,
+
+    /// Points for a loss (BBL).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    loss: Result<Option<f32>, TRFError>,
+
+    /// Points for a zero-point bye, i.e. a known absence (BBZ).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    zero_point_bye: Result<Option<f32>, TRFError>,
+
+    /// Points for a forfeit loss (BBF).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    forfeit_loss: Result<Option<f32>, TRFError>,
+
+    /// Points for a pairing-allocated bye (BBU).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    pairing_allocated_bye: Result<Option<f32>, TRFError>,
+}
+
+impl ScoringSystem {
+    /// The data identification number of the points for a win.
+    pub const WIN: &str = "BBW";
+
+    /// The data identification number of the points for a draw.
+    pub const DRAW: &str = "BBD";
+
+    /// The data identification number of the points for a loss.
+    pub const LOSS: &str = "BBL";
+
+    /// The data identification number of the points for a zero-point bye.
+    pub const ZERO_POINT_BYE: &str = "BBZ";
+
+    /// The data identification number of the points for a forfeit loss.
+    pub const FORFEIT_LOSS: &str = "BBF";
+
+    /// The data identification number of the points for a pairing-allocated bye.
+    pub const PAIRING_ALLOCATED_BYE: &str = "BBU";
+
+    /// The data identification numbers of every record, in the order they are written.
+    pub(crate) const RECORDS: [&str; 6] = [
+        Self::WIN,
+        Self::DRAW,
+        Self::LOSS,
+        Self::ZERO_POINT_BYE,
+        Self::FORFEIT_LOSS,
+        Self::PAIRING_ALLOCATED_BYE,
+    ];
+
+    /// Create a scoring system from the points for a win, a draw and a loss.
+    ///
+    /// Zero-point byes and forfeit losses are worth a loss, pairing-allocated byes are
+    /// worth a win.
+    #[must_use]
+    pub const fn new(win: f32, draw: f32, loss: f32) -> Self {
+        Self {
+            win: Ok(Some(win)),
+            draw: Ok(Some(draw)),
+            loss: Ok(Some(loss)),
+            zero_point_bye: Ok(Some(loss)),
+            forfeit_loss: Ok(Some(loss)),
+            pairing_allocated_bye: Ok(Some(win)),
+        }
+    }
+
+    /// Get a value, or `default` if it is empty or incorrectly formatted.
+    fn value(field: &Result<Option<f32>, TRFError>, default: f32) -> f32 {
+        field.as_ref().ok().copied().flatten().unwrap_or(default)
+    }
+
+    /// Get the points for a win, 1 by default.
+    #[must_use]
+    pub fn win(&self) -> f32 {
+        Self::value(&self.win, 1.0)
+    }
+
+    /// Get the points for a draw, 0.5 by default.
+    #[must_use]
+    pub fn draw(&self) -> f32 {
+        Self::value(&self.draw, 0.5)
+    }
+
+    /// Get the points for a loss, 0 by default.
+    #[must_use]
+    pub fn loss(&self) -> f32 {
+        Self::value(&self.loss, 0.0)
+    }
+
+    /// Get the points for a zero-point bye, the points for a loss by default.
+    #[must_use]
+    pub fn zero_point_bye(&self) -> f32 {
+        Self::value(&self.zero_point_bye, self.loss())
+    }
+
+    /// Get the points for a forfeit loss, the points for a loss by default.
+    #[must_use]
+    pub fn forfeit_loss(&self) -> f32 {
+        Self::value(&self.forfeit_loss, self.loss())
+    }
+
+    /// Get the points for a pairing-allocated bye, the points for a win by default.
+    #[must_use]
+    pub fn pairing_allocated_bye(&self) -> f32 {
+        Self::value(&self.pairing_allocated_bye, self.win())
+    }
+
+    /// Set the points for a zero-point bye.
+    pub fn set_zero_point_bye(&mut self, points: f32) {
+        self.zero_point_bye = Ok(Some(points));
+    }
+
+    /// Set the points for a forfeit loss.
+    pub fn set_forfeit_loss(&mut self, points: f32) {
+        self.forfeit_loss = Ok(Some(points));
+    }
+
+    /// Set the points for a pairing-allocated bye.
+    pub fn set_pairing_allocated_bye(&mut self, points: f32) {
+        self.pairing_allocated_bye = Ok(Some(points));
+    }
+
+    /// Get the points given for a round result.
+    #[must_use]
+    pub fn points(&self, result: RoundResult) -> f32 {
+        match result {
+            RoundResult::Regular(RegularRoundResult::Win)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Win)
+            | RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitWin)
+            | RoundResult::Bye(ByeRoundResult::FullPointBye) => self.win(),
+            RoundResult::Regular(RegularRoundResult::Draw)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Draw)
+            | RoundResult::Bye(ByeRoundResult::HalfPointBye) => self.draw(),
+            RoundResult::Regular(RegularRoundResult::Loss)
+            | RoundResult::LessThanOneMove(LessThanOneMoveRoundResult::Loss) => {
+                self.loss()
+            }
+            RoundResult::NotPlayed(NotPlayedRoundResult::ForfeitLoss) => {
+                self.forfeit_loss()
+            }
+            RoundResult::Bye(ByeRoundResult::ZeroPointBye) => self.zero_point_bye(),
+            RoundResult::Bye(ByeRoundResult::PairingAllocatedBye) => {
+                self.pairing_allocated_bye()
+            }
+        }
+    }
+
+    /// Compute the total points of a player from their rounds.
+    ///
+    /// Rounds which are empty or couldn't be read don't give any points.
+    #[must_use]
+    pub fn score(&self, player: &Section) -> f32 {
+        player
+            .rounds()
+            .iter()
+            .flatten()
+            .filter_map(|round| round.result().ok().copied().flatten())
+            .map(|result| self.points(result))
+            .sum()
+    }
+
+    /// Read a point-value record, records which aren't point-value records are ignored.
+    pub(crate) fn read_record(&mut self, din: &str, argument: &str) {
+        let value = parse_number(argument);
+
+        match din {
+            Self::WIN => self.win = value,
+            Self::DRAW => self.draw = value,
+            Self::LOSS => self.loss = value,
+            Self::ZERO_POINT_BYE => self.zero_point_bye = value,
+            Self::FORFEIT_LOSS => self.forfeit_loss = value,
+            Self::PAIRING_ALLOCATED_BYE => self.pairing_allocated_bye = value,
+            _ => {}
+        }
+    }
+
+    /// Write a point-value record as a TRF line, including the data identification
+    /// number.
+    ///
+    /// Returns [`None`] if the record is empty or isn't a point-value record.
+    pub(crate) fn write_record(&self, din: &str) -> Option<String> {
+        let argument = format_field(self.field(din)?, |points| format!("{points:.1}"));
+
+        (!argument.is_empty()).then(|| format!("{din} {argument}"))
+    }
+
+    /// Get the error of a point-value record along with its columns, if it was
+    /// incorrectly formatted.
+    ///
+    /// `argument` is the line the record was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn error(
+        &self,
+        din: &str,
+        argument: &str,
+    ) -> Option<(Range<usize>, TRFError)> {
+        field_error(self.field(din)?.as_ref(), 0..argument.chars().count())
+    }
+
+    /// Get the field of a point-value record.
+    fn field(&self, din: &str) -> Option<&Result<Option<f32>, TRFError>> {
+        Some(match din {
+            Self::WIN => &self.win,
+            Self::DRAW => &self.draw,
+            Self::LOSS => &self.loss,
+            Self::ZERO_POINT_BYE => &self.zero_point_bye,
+            Self::FORFEIT_LOSS => &self.forfeit_loss,
+            Self::PAIRING_ALLOCATED_BYE => &self.pairing_allocated_bye,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for ScoringSystem {
+    /// The standard 1 / ½ / 0 scoring system, without any point-value record.
+    fn default() -> Self {
+        Self {
+            win: Ok(None),
+            draw: Ok(None),
+            loss: Ok(None),
+            zero_point_bye: Ok(None),
+            forfeit_loss: Ok(None),
+            pairing_allocated_bye: Ok(None),
+        }
+    }
+}
+
+/// A player whose points column doesn't match the points computed from their rounds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointsMismatch {
+    /// Starting rank number of the player.
+    player: u16,
+
+    /// Points read from the file, [`None`] if they are invalid.
+    found: Option<f32>,
+
+    /// Points computed from the rounds.
+    computed: f32,
+}
+
+impl PointsMismatch {
+    /// Create a mismatch.
+    pub(crate) const fn new(player: u16, found: Option<f32>, computed: f32) -> Self {
+        Self {
+            player,
+            found,
+            computed,
+        }
+    }
+
+    /// Get the starting rank number of the player.
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the points read from the file, [`None`] if they are invalid.
+    #[must_use]
+    pub const fn found(&self) -> Option<f32> {
+        self.found
+    }
+
+    /// Get the points computed from the rounds.
+    #[must_use]
+    pub const fn computed(&self) -> f32 {
+        self.computed
+    }
+}
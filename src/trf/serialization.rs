@@ -0,0 +1,149 @@
+//! # Serde representation of the parsed fields
+//!
+//! This module is only available with the `serde` feature.
+//!
+//! Most fields are stored as `Result<Option<T>, TRFError>`, so that a file can be read
+//! even if some fields are incorrectly formatted. They are represented as:
+//! - the value itself if it was read correctly, e.g. `2150`
+//! - `null` if the field is empty
+//! - `{"error": "..."}` if the field is incorrectly formatted, with the error message
+//!
+//! Errors can't be read back as the original [`TRFError`], so they are read as
+//! [`TRFError::DeserializedError`] with the same message.
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::TRFError;
+
+/// A field, as it is written.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum FieldRef<'a, T> {
+    /// A field which was incorrectly formatted.
+    Error {
+        /// The error message.
+        error: String,
+    },
+
+    /// A field which was read correctly.
+    Value(&'a T),
+}
+
+impl<'a, T> From<&'a Result<T, TRFError>> for FieldRef<'a, T> {
+    fn from(val: &'a Result<T, TRFError>) -> Self {
+        match val {
+            Ok(value) => Self::Value(value),
+            Err(error) => Self::Error {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
+/// A field, as it is read.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Field<T> {
+    /// A field which was incorrectly formatted.
+    Error {
+        /// The error message.
+        error: String,
+    },
+
+    /// A field which was read correctly.
+    Value(T),
+}
+
+impl<T> From<Field<T>> for Result<T, TRFError> {
+    fn from(val: Field<T>) -> Self {
+        match val {
+            Field::Error { error } => Err(TRFError::DeserializedError(error)),
+            Field::Value(value) => Ok(value),
+        }
+    }
+}
+
+/// Serialize a single field, use with `#[serde(with = "...")]`.
+pub mod field {
+    use super::{
+        Deserialize, Deserializer, Field, FieldRef, Serialize, Serializer, TRFError,
+    };
+
+    /// Write a field.
+    pub fn serialize<S: Serializer, T: Serialize>(
+        field: &Result<T, TRFError>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        FieldRef::from(field).serialize(serializer)
+    }
+
+    /// Read a field.
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<Result<T, TRFError>, D::Error> {
+        Field::deserialize(deserializer).map(Into::into)
+    }
+}
+
+/// Serialize a list of fields, use with `#[serde(with = "...")]`.
+pub mod fields {
+    use serde::ser::SerializeSeq;
+
+    use super::{
+        Deserialize, Deserializer, Field, FieldRef, Serialize, Serializer, TRFError,
+    };
+
+    /// Write a list of fields.
+    pub fn serialize<S: Serializer, T: Serialize>(
+        fields: &[Result<T, TRFError>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut sequence = serializer.serialize_seq(Some(fields.len()))?;
+        for field in fields {
+            sequence.serialize_element(&FieldRef::from(field))?;
+        }
+        sequence.end()
+    }
+
+    /// Read a list of fields.
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Result<T, TRFError>>, D::Error> {
+        Vec::<Field<T>>::deserialize(deserializer)
+            .map(|fields| fields.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Serialize the line separator of a situation, use with `#[serde(with = "...")]`.
+///
+/// Only `\r`, `\n` and `\r\n` can be read.
+pub mod line_separator {
+    use serde::de::Error;
+
+    use super::{Deserialize, Deserializer, Serializer};
+
+    /// Write the line separator.
+    pub fn serialize<S: Serializer>(
+        separator: &crate::LineSeparator,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(separator)
+    }
+
+    /// Read the line separator.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the string isn't a line separator.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<crate::LineSeparator, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "\r" => Ok("\r"),
+            "\n" => Ok("\n"),
+            "\r\n" => Ok("\r\n"),
+            separator => Err(D::Error::custom(format!(
+                "Expected a line separator, found {separator:?}"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! # TRF Team
+//!
+//! This module contains the team model for TRF files (DIN 013). Teams only list their
+//! members by starting rank number, use [`TeamData::players`] to get the corresponding
+//! [`Section`]s.
+//!
+//! ## Usage
+//!
+//! This struct isn't meant to be instanced directly. See [`crate::Situation::try_from`]
+//! and [`crate::Situation::teams`] instead.
+//!
+//! ```
+//! # use openswisspairings_lib::Situation;
+//! let file_contents = "\
+//! 012 Team Swiss
+//! 001    1      Smith, John                       2100                             2.0
+//! 001    2      Doe, Jane                         2000                             1.5
+//! 001    3      Roe, Richard                      1900                             0.5
+//! 013 The Rooks                          1    3
+//! 013 The Knights                        2";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let rooks = &situation.teams()[0];
+//!
+//! assert_eq!(rooks.name(), Some("The Rooks"));
+//! assert_eq!(rooks.players(situation.players()).count(), 2);
+//! assert_eq!(rooks.points(situation.players()), 2.5);
+//! ```
+use std::ops::Range;
+
+use super::{
+    player::{
+        utils::{columns, field_error, parse_number},
+        Section,
+    },
+    TRFError,
+};
+
+/// Team section, stores the name and the members of a team.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamData {
+    /// Team name.
+    name: Option<String>,
+
+    /// Starting rank numbers of the team members.
+    ///
+    /// Empty slots in the file are skipped. See [`Section::starting_rank_number`].
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::fields"))]
+    members: Vec<Result<u16, TRFError>>,
+}
+
+impl TeamData {
+    /// The data identification number of a team section.
+    pub const DATA_IDENTIFICATION_NUMBER: &str = "013";
+
+    /// Create a team from its name and the starting rank numbers of its members.
+    #[must_use]
+    pub fn new(name: Option<String>, members: &[u16]) -> Self {
+        Self {
+            name,
+            members: members.iter().copied().map(Ok).collect(),
+        }
+    }
+
+    /// Get the team name.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the starting rank numbers of the team members.
+    ///
+    /// Each member is [`Err`] if it isn't a valid number.
+    pub fn members(&self) -> &[Result<u16, TRFError>] {
+        &self.members
+    }
+
+    /// Get the player sections of the team members.
+    ///
+    /// Members which can't be found in `players` are skipped.
+    pub fn players<'a>(
+        &'a self,
+        players: &'a [Section],
+    ) -> impl Iterator<Item = &'a Section> {
+        self.members.iter().filter_map(|member| {
+            let member = member.as_ref().ok()?;
+
+            players
+                .iter()
+                .find(|player| player.starting_rank_number().ok() == Some(member))
+        })
+    }
+
+    /// Get the team points, the sum of the points of the team members.
+    ///
+    /// Members whose points are missing or invalid count as zero.
+    #[must_use]
+    pub fn points(&self, players: &[Section]) -> f32 {
+        self.players(players)
+            .filter_map(|player| *player.points().ok()?)
+            .sum()
+    }
+
+    /// Change the starting rank numbers of the team members, see
+    /// [`crate::Situation::renumber_players`].
+    pub(crate) fn renumber(&mut self, renumbered: impl Fn(u16) -> u16) {
+        for member in self.members.iter_mut().flatten() {
+            *member = renumbered(*member);
+        }
+    }
+
+    /// Get the columns of the member slots of a team section.
+    fn member_columns(value: &str) -> impl Iterator<Item = Range<usize>> {
+        (32..value.chars().count()).step_by(5).map(|n| n..n + 4)
+    }
+
+    /// Get the members which were incorrectly formatted, along with their columns.
+    ///
+    /// `value` is the line the section was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn errors(&self, value: &str) -> Vec<(Range<usize>, TRFError)> {
+        Self::member_columns(value)
+            .filter(|n| !columns(value, n.clone()).trim().is_empty())
+            .zip(&self.members)
+            .filter_map(|(n, member)| field_error(member.as_ref(), n))
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for TeamData {
+    type Error = TRFError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // The first four characters (Data Identification Number and space)
+        // shouldn't be included in 'value'
+        let members: Vec<Result<u16, TRFError>> = Self::member_columns(value)
+            .filter_map(|n| parse_number(columns(value, n)).transpose())
+            .collect();
+
+        Ok(Self {
+            name: Some(columns(value, 0..32).trim().to_string())
+                .filter(|s| !s.is_empty()),
+            members,
+        })
+    }
+}
+
+impl From<&TeamData> for String {
+    /// Write the team section as a TRF line, including the data identification number.
+    ///
+    /// Members which were incorrectly formatted are left empty.
+    fn from(val: &TeamData) -> Self {
+        let members: Vec<Self> = val
+            .members
+            .iter()
+            .map(|member| {
+                let member: Self =
+                    member.as_ref().map(ToString::to_string).unwrap_or_default();
+                format!("{member:>4}")
+            })
+            .collect();
+        let line: Self = format!(
+            "{} {:<32.32}{}",
+            TeamData::DATA_IDENTIFICATION_NUMBER,
+            val.name.as_deref().unwrap_or_default(),
+            members.join(" ")
+        );
+
+        line.trim_end().to_string()
+    }
+}
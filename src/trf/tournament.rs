@@ -0,0 +1,637 @@
+//! # TRF Tournament Data
+//!
+//! This module contains the tournament header model for TRF files (DIN 012 to 132, and
+//! the header records added by TRF-2025: DIN 142, 162, 172 and 202), along with the
+//! source of the file written by Tornelo (DIN XRS).
+//!
+//! ## Usage
+//!
+//! This struct is usually read with [`crate::Situation::try_from`], see
+//! [`crate::Situation::tournament`]. It can also be created with [`Default::default`]
+//! and filled with setter methods.
+//!
+//! Like in [`crate::trf::player`], fields which are parsed are stored as
+//! [`core::result::Result<core::option::Option>`], while free text fields are kept as
+//! raw strings.
+//!
+//! ```
+//! use std::fs;
+//! # use openswisspairings_lib::Situation;
+//!
+//! let file_contents = fs::read_to_string("test_file.trf").unwrap();
+//! let situation = Situation::try_from(file_contents).unwrap();
+//! let tournament = situation.tournament();
+//!
+//! assert_eq!(tournament.name(), Some("Sample TRFx File"));
+//! assert_eq!(tournament.city(), Some("Rome"));
+//! assert_eq!(tournament.number_of_players().unwrap(), &Some(10));
+//! assert_eq!(tournament.start_date().unwrap().unwrap().year(), 2020);
+//! ```
+use std::ops::Range;
+
+use super::{
+    player::{
+        fields::Date,
+        utils::{columns, field_error, format_field, parse_number, parse_with},
+    },
+    ParseMode, TRFError,
+};
+
+/// Tournament section, stores all information from the header of a TRF file.
+///
+/// Every field is optional in the file: a record which is missing is stored as
+/// [`None`] (or [`Ok`]\([`None`]) for parsed fields).
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TournamentData {
+    /// Tournament name (DIN 012).
+    name: Option<String>,
+
+    /// City (DIN 022).
+    city: Option<String>,
+
+    /// Federation (DIN 032).
+    federation: Option<String>,
+
+    /// Date of start (DIN 042).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    start_date: Result<Option<Date>, TRFError>,
+
+    /// Date of end (DIN 052).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    end_date: Result<Option<Date>, TRFError>,
+
+    /// Number of players, as declared in the header (DIN 062).
+    ///
+    /// This isn't checked against the number of player sections.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    number_of_players: Result<Option<u16>, TRFError>,
+
+    /// Number of rated players, as declared in the header (DIN 072).
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    number_of_rated_players: Result<Option<u16>, TRFError>,
+
+    /// Number of teams, as declared in the header (DIN 082).
+    ///
+    /// Only used in team tournaments.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    number_of_teams: Result<Option<u16>, TRFError>,
+
+    /// Type of tournament (DIN 092).
+    ///
+    /// This is free text, e.g. "Individual: Swiss-System".
+    tournament_type: Option<String>,
+
+    /// Chief arbiter (DIN 102).
+    chief_arbiter: Option<String>,
+
+    /// Deputy chief arbiters (DIN 112).
+    ///
+    /// The record may be repeated, one for each deputy.
+    deputy_chief_arbiters: Vec<String>,
+
+    /// Allotted time per moves/game (DIN 122).
+    time_control: Option<String>,
+
+    /// Dates of the rounds (DIN 132).
+    ///
+    /// The dates are written in YY/MM/DD format, so the year is stored as two digits.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::fields"))]
+    round_dates: Vec<Result<Option<Date>, TRFError>>,
+
+    /// Number of rounds planned (DIN 142, TRF-2025).
+    ///
+    /// This may be more than the number of rounds already played.
+    #[cfg_attr(feature = "serde", serde(with = "crate::trf::serialization::field"))]
+    number_of_rounds: Result<Option<u16>, TRFError>,
+
+    /// Pairing system (DIN 162, TRF-2025).
+    ///
+    /// This is an encoded name, e.g. "`FIDE_DUTCH_2025`".
+    pairing_system: Option<String>,
+
+    /// Method used to allocate the starting ranks (DIN 172, TRF-2025).
+    starting_rank_method: Option<String>,
+
+    /// Tie-breaks, in order (DIN 202, TRF-2025).
+    ///
+    /// This is free text, usually the tie-break acronyms separated by commas, see
+    /// [`crate::tiebreak::Tiebreak`].
+    tiebreaks: Option<String>,
+
+    /// Source of the file (DIN XRS), written by Tornelo, e.g. "tornelo.com".
+    source: Option<String>,
+}
+
+impl TournamentData {
+    /// The data identification number of the tournament name.
+    pub const TOURNAMENT_NAME: &str = "012";
+
+    /// The data identification number of the city.
+    pub const CITY: &str = "022";
+
+    /// The data identification number of the federation.
+    pub const FEDERATION: &str = "032";
+
+    /// The data identification number of the date of start.
+    pub const DATE_OF_START: &str = "042";
+
+    /// The data identification number of the date of end.
+    pub const DATE_OF_END: &str = "052";
+
+    /// The data identification number of the number of players.
+    pub const NUMBER_OF_PLAYERS: &str = "062";
+
+    /// The data identification number of the number of rated players.
+    pub const NUMBER_OF_RATED_PLAYERS: &str = "072";
+
+    /// The data identification number of the number of teams.
+    pub const NUMBER_OF_TEAMS: &str = "082";
+
+    /// The data identification number of the type of tournament.
+    pub const TYPE_OF_TOURNAMENT: &str = "092";
+
+    /// The data identification number of the chief arbiter.
+    pub const CHIEF_ARBITER: &str = "102";
+
+    /// The data identification number of a deputy chief arbiter.
+    pub const DEPUTY_CHIEF_ARBITER: &str = "112";
+
+    /// The data identification number of the allotted time per moves/game.
+    pub const TIME_CONTROL: &str = "122";
+
+    /// The data identification number of the dates of the rounds.
+    pub const ROUND_DATES: &str = "132";
+
+    /// The data identification number of the number of rounds (TRF-2025).
+    pub const NUMBER_OF_ROUNDS: &str = "142";
+
+    /// The data identification number of the pairing system (TRF-2025).
+    pub const PAIRING_SYSTEM: &str = "162";
+
+    /// The data identification number of the starting rank method (TRF-2025).
+    pub const STARTING_RANK_METHOD: &str = "172";
+
+    /// The data identification number of the tie-breaks (TRF-2025).
+    pub const TIEBREAKS: &str = "202";
+
+    /// The data identification number of the source of the file (Tornelo).
+    pub const SOURCE: &str = "XRS";
+
+    /// The data identification numbers of the records added by TRF-2025.
+    pub(crate) const TRF_2025_RECORDS: [&str; 4] = [
+        Self::NUMBER_OF_ROUNDS,
+        Self::PAIRING_SYSTEM,
+        Self::STARTING_RANK_METHOD,
+        Self::TIEBREAKS,
+    ];
+
+    /// Get the tournament name.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the city.
+    #[must_use]
+    pub fn city(&self) -> Option<&str> {
+        self.city.as_deref()
+    }
+
+    /// Get the federation.
+    #[must_use]
+    pub fn federation(&self) -> Option<&str> {
+        self.federation.as_deref()
+    }
+
+    /// Get the date of start.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid date. See [`Date`].
+    pub const fn start_date(&self) -> Result<&Option<Date>, &TRFError> {
+        self.start_date.as_ref()
+    }
+
+    /// Get the date of end.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid date. See [`Date`].
+    pub const fn end_date(&self) -> Result<&Option<Date>, &TRFError> {
+        self.end_date.as_ref()
+    }
+
+    /// Get the declared number of players.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn number_of_players(&self) -> Result<&Option<u16>, &TRFError> {
+        self.number_of_players.as_ref()
+    }
+
+    /// Get the declared number of rated players.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn number_of_rated_players(&self) -> Result<&Option<u16>, &TRFError> {
+        self.number_of_rated_players.as_ref()
+    }
+
+    /// Get the declared number of teams.
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn number_of_teams(&self) -> Result<&Option<u16>, &TRFError> {
+        self.number_of_teams.as_ref()
+    }
+
+    /// Get the type of tournament.
+    #[must_use]
+    pub fn tournament_type(&self) -> Option<&str> {
+        self.tournament_type.as_deref()
+    }
+
+    /// Get the chief arbiter.
+    #[must_use]
+    pub fn chief_arbiter(&self) -> Option<&str> {
+        self.chief_arbiter.as_deref()
+    }
+
+    /// Get the deputy chief arbiters.
+    #[must_use]
+    pub fn deputy_chief_arbiters(&self) -> &[String] {
+        &self.deputy_chief_arbiters
+    }
+
+    /// Get the allotted time per moves/game.
+    #[must_use]
+    pub fn time_control(&self) -> Option<&str> {
+        self.time_control.as_deref()
+    }
+
+    /// Get the dates of the rounds.
+    ///
+    /// Each date is [`Err`] if it isn't a valid date, and [`Ok`]\([`None`]) if it was
+    /// left empty.
+    pub fn round_dates(&self) -> &[Result<Option<Date>, TRFError>] {
+        &self.round_dates
+    }
+
+    /// Get the number of rounds planned (TRF-2025).
+    ///
+    /// # Errors
+    ///
+    /// This field is [`Err`] if the parsed value isn't a valid number.
+    pub const fn number_of_rounds(&self) -> Result<&Option<u16>, &TRFError> {
+        self.number_of_rounds.as_ref()
+    }
+
+    /// Get the encoded pairing system (TRF-2025).
+    #[must_use]
+    pub fn pairing_system(&self) -> Option<&str> {
+        self.pairing_system.as_deref()
+    }
+
+    /// Get the method used to allocate the starting ranks (TRF-2025).
+    #[must_use]
+    pub fn starting_rank_method(&self) -> Option<&str> {
+        self.starting_rank_method.as_deref()
+    }
+
+    /// Get the tie-breaks, in order (TRF-2025).
+    #[must_use]
+    pub fn tiebreaks(&self) -> Option<&str> {
+        self.tiebreaks.as_deref()
+    }
+
+    /// Get the source of the file (Tornelo).
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Set the tournament name.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Set the city.
+    pub fn set_city(&mut self, city: Option<String>) {
+        self.city = city;
+    }
+
+    /// Set the federation.
+    pub fn set_federation(&mut self, federation: Option<String>) {
+        self.federation = federation;
+    }
+
+    /// Set the date of start.
+    pub fn set_start_date(&mut self, start_date: Option<Date>) {
+        self.start_date = Ok(start_date);
+    }
+
+    /// Set the date of end.
+    pub fn set_end_date(&mut self, end_date: Option<Date>) {
+        self.end_date = Ok(end_date);
+    }
+
+    /// Set the number of players.
+    pub fn set_number_of_players(&mut self, number_of_players: Option<u16>) {
+        self.number_of_players = Ok(number_of_players);
+    }
+
+    /// Set the number of rated players.
+    pub fn set_number_of_rated_players(&mut self, number_of_rated_players: Option<u16>) {
+        self.number_of_rated_players = Ok(number_of_rated_players);
+    }
+
+    /// Set the number of teams.
+    pub fn set_number_of_teams(&mut self, number_of_teams: Option<u16>) {
+        self.number_of_teams = Ok(number_of_teams);
+    }
+
+    /// Set the number of rounds planned.
+    pub fn set_number_of_rounds(&mut self, number_of_rounds: Option<u16>) {
+        self.number_of_rounds = Ok(number_of_rounds);
+    }
+
+    /// Set the type of tournament.
+    pub fn set_tournament_type(&mut self, tournament_type: Option<String>) {
+        self.tournament_type = tournament_type;
+    }
+
+    /// Set the chief arbiter.
+    pub fn set_chief_arbiter(&mut self, chief_arbiter: Option<String>) {
+        self.chief_arbiter = chief_arbiter;
+    }
+
+    /// Set the deputy chief arbiters.
+    pub fn set_deputy_chief_arbiters(&mut self, deputy_chief_arbiters: Vec<String>) {
+        self.deputy_chief_arbiters = deputy_chief_arbiters;
+    }
+
+    /// Set the allotted time per moves/game.
+    pub fn set_time_control(&mut self, time_control: Option<String>) {
+        self.time_control = time_control;
+    }
+
+    /// Set the dates of the rounds.
+    ///
+    /// Years of round dates are written with two digits (YY/MM/DD).
+    pub fn set_round_dates(&mut self, round_dates: Vec<Option<Date>>) {
+        self.round_dates = round_dates.into_iter().map(Ok).collect();
+    }
+
+    /// Set the pairing system.
+    pub fn set_pairing_system(&mut self, pairing_system: Option<String>) {
+        self.pairing_system = pairing_system;
+    }
+
+    /// Set the method used to allocate the starting ranks.
+    pub fn set_starting_rank_method(&mut self, starting_rank_method: Option<String>) {
+        self.starting_rank_method = starting_rank_method;
+    }
+
+    /// Set the tie-breaks.
+    pub fn set_tiebreaks(&mut self, tiebreaks: Option<String>) {
+        self.tiebreaks = tiebreaks;
+    }
+
+    /// Set the source of the file.
+    pub fn set_source(&mut self, source: Option<String>) {
+        self.source = source;
+    }
+
+    /// Read a header record into this struct.
+    ///
+    /// `argument` is the line without its data identification number and the following
+    /// space. Records which aren't tournament header records are ignored.
+    pub(crate) fn read_record(&mut self, din: &str, argument: &str, mode: ParseMode) {
+        /// Store a raw string, or [`None`] if it is empty.
+        fn text(argument: &str) -> Option<String> {
+            Some(argument.trim().to_string()).filter(|s| !s.is_empty())
+        }
+        let date = |argument: &str| parse_with(argument, |i| Date::read(i, mode));
+
+        match din {
+            Self::TOURNAMENT_NAME => self.name = text(argument),
+            Self::CITY => self.city = text(argument),
+            Self::FEDERATION => self.federation = text(argument),
+            Self::DATE_OF_START => self.start_date = date(argument),
+            Self::DATE_OF_END => self.end_date = date(argument),
+            Self::NUMBER_OF_PLAYERS => self.number_of_players = parse_number(argument),
+            Self::NUMBER_OF_RATED_PLAYERS => {
+                self.number_of_rated_players = parse_number(argument);
+            }
+            Self::NUMBER_OF_TEAMS => self.number_of_teams = parse_number(argument),
+            Self::TYPE_OF_TOURNAMENT => self.tournament_type = text(argument),
+            Self::CHIEF_ARBITER => self.chief_arbiter = text(argument),
+            Self::DEPUTY_CHIEF_ARBITER => {
+                self.deputy_chief_arbiters.extend(text(argument));
+            }
+            Self::TIME_CONTROL => self.time_control = text(argument),
+            Self::ROUND_DATES => {
+                // Round dates are aligned with the player round sections.
+                let mut n: usize = 87;
+                self.round_dates.clear();
+                while argument.chars().count() > n {
+                    self.round_dates.push(date(columns(argument, n..n + 8)));
+                    n += 10;
+                }
+            }
+            Self::NUMBER_OF_ROUNDS => self.number_of_rounds = parse_number(argument),
+            Self::PAIRING_SYSTEM => self.pairing_system = text(argument),
+            Self::STARTING_RANK_METHOD => self.starting_rank_method = text(argument),
+            Self::TIEBREAKS => self.tiebreaks = text(argument),
+            Self::SOURCE => self.source = text(argument),
+            _ => {}
+        }
+    }
+
+    /// Get the fields of a header record which were incorrectly formatted, along with
+    /// their columns.
+    ///
+    /// `argument` is the line the record was read from, without its data identification
+    /// number and the following space.
+    pub(crate) fn errors(
+        &self,
+        din: &str,
+        argument: &str,
+    ) -> Vec<(Range<usize>, TRFError)> {
+        let argument = 0..argument.chars().count();
+
+        match din {
+            Self::DATE_OF_START => field_error(self.start_date.as_ref(), argument),
+            Self::DATE_OF_END => field_error(self.end_date.as_ref(), argument),
+            Self::NUMBER_OF_PLAYERS => {
+                field_error(self.number_of_players.as_ref(), argument)
+            }
+            Self::NUMBER_OF_RATED_PLAYERS => {
+                field_error(self.number_of_rated_players.as_ref(), argument)
+            }
+            Self::NUMBER_OF_TEAMS => field_error(self.number_of_teams.as_ref(), argument),
+            Self::NUMBER_OF_ROUNDS => {
+                field_error(self.number_of_rounds.as_ref(), argument)
+            }
+            Self::ROUND_DATES => {
+                return (87..)
+                    .step_by(10)
+                    .zip(&self.round_dates)
+                    .filter_map(|(n, date)| field_error(date.as_ref(), n..n + 8))
+                    .collect();
+            }
+            _ => None,
+        }
+        .into_iter()
+        .collect()
+    }
+
+    /// Get the deviations from TRF16 in a header record, only reported in
+    /// [`ParseMode::Strict`]: the city (DIN 022) must not be a URL.
+    pub(crate) fn strict_errors(
+        din: &str,
+        argument: &str,
+    ) -> Vec<(Range<usize>, TRFError)> {
+        let city = argument.trim();
+
+        if din == Self::CITY && (city.contains("://") || city.starts_with("www.")) {
+            vec![(
+                0..argument.chars().count(),
+                TRFError::UnexpectedUrlError(city.to_string()),
+            )]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Get the records of the header, in the order they are written: data identification
+    /// number and index of the record for repeated records.
+    pub(crate) fn records(&self) -> Vec<(&'static str, usize)> {
+        [
+            Self::TOURNAMENT_NAME,
+            Self::CITY,
+            Self::FEDERATION,
+            Self::DATE_OF_START,
+            Self::DATE_OF_END,
+            Self::NUMBER_OF_PLAYERS,
+            Self::NUMBER_OF_RATED_PLAYERS,
+            Self::NUMBER_OF_TEAMS,
+            Self::TYPE_OF_TOURNAMENT,
+            Self::CHIEF_ARBITER,
+        ]
+        .into_iter()
+        .map(|din| (din, 0))
+        .chain(
+            (0..self.deputy_chief_arbiters.len())
+                .map(|index| (Self::DEPUTY_CHIEF_ARBITER, index)),
+        )
+        .chain([(Self::TIME_CONTROL, 0), (Self::ROUND_DATES, 0)])
+        .chain(Self::TRF_2025_RECORDS.into_iter().map(|din| (din, 0)))
+        .chain([(Self::SOURCE, 0)])
+        .collect()
+    }
+
+    /// Write a header record as a TRF line, including the data identification number.
+    ///
+    /// `index` is only used for records which can be repeated. Returns [`None`] if the
+    /// record is empty or isn't a tournament header record.
+    pub(crate) fn write_record(&self, din: &str, index: usize) -> Option<String> {
+        /// Format a parsed field, or [`None`] if it is empty or incorrectly formatted.
+        fn field<T: ToString>(field: &Result<Option<T>, TRFError>) -> Option<String> {
+            Some(format_field(field, ToString::to_string)).filter(|s| !s.is_empty())
+        }
+
+        /// Format a date field, or [`None`] if it is empty or incorrectly formatted.
+        fn date(field: &Result<Option<Date>, TRFError>) -> Option<String> {
+            Some(format_field(field, |date| date.into())).filter(|s| !s.is_empty())
+        }
+
+        let argument: String = match din {
+            Self::TOURNAMENT_NAME => self.name.clone()?,
+            Self::CITY => self.city.clone()?,
+            Self::FEDERATION => self.federation.clone()?,
+            Self::DATE_OF_START => date(&self.start_date)?,
+            Self::DATE_OF_END => date(&self.end_date)?,
+            Self::NUMBER_OF_PLAYERS => field(&self.number_of_players)?,
+            Self::NUMBER_OF_RATED_PLAYERS => field(&self.number_of_rated_players)?,
+            Self::NUMBER_OF_TEAMS => field(&self.number_of_teams)?,
+            Self::TYPE_OF_TOURNAMENT => self.tournament_type.clone()?,
+            Self::CHIEF_ARBITER => self.chief_arbiter.clone()?,
+            Self::DEPUTY_CHIEF_ARBITER => self.deputy_chief_arbiters.get(index)?.clone(),
+            Self::TIME_CONTROL => self.time_control.clone()?,
+            Self::ROUND_DATES => {
+                if self.round_dates.is_empty() {
+                    return None;
+                }
+
+                // Round dates are aligned with the player round sections.
+                let round_dates: Vec<String> = self
+                    .round_dates
+                    .iter()
+                    .map(|round_date| {
+                        let round_date = format_field(round_date, |date| {
+                            format!(
+                                "{:02}/{:02}/{:02}",
+                                date.year(),
+                                date.month(),
+                                date.day()
+                            )
+                        });
+                        format!("{round_date:8}")
+                    })
+                    .collect();
+
+                format!("{:87}{}", "", round_dates.join("  "))
+                    .trim_end()
+                    .to_string()
+            }
+            Self::NUMBER_OF_ROUNDS => field(&self.number_of_rounds)?,
+            Self::PAIRING_SYSTEM => self.pairing_system.clone()?,
+            Self::STARTING_RANK_METHOD => self.starting_rank_method.clone()?,
+            Self::TIEBREAKS => self.tiebreaks.clone()?,
+            Self::SOURCE => self.source.clone()?,
+            _ => return None,
+        };
+
+        Some(format!("{din} {argument}"))
+    }
+}
+
+impl From<&TournamentData> for String {
+    fn from(val: &TournamentData) -> Self {
+        val.records()
+            .into_iter()
+            .filter_map(|(din, index)| val.write_record(din, index))
+            .collect::<Vec<Self>>()
+            .join("\r")
+    }
+}
+
+impl Default for TournamentData {
+    fn default() -> Self {
+        Self {
+            name: None,
+            city: None,
+            federation: None,
+            start_date: Ok(None),
+            end_date: Ok(None),
+            number_of_players: Ok(None),
+            number_of_rated_players: Ok(None),
+            number_of_teams: Ok(None),
+            tournament_type: None,
+            chief_arbiter: None,
+            deputy_chief_arbiters: vec![],
+            time_control: None,
+            round_dates: vec![],
+            number_of_rounds: Ok(None),
+            pairing_system: None,
+            starting_rank_method: None,
+            tiebreaks: None,
+            source: None,
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! # TRF Round Data Validation
+//!
+//! Each round section of a player names an opponent, a colour and a result, which must
+//! be mirrored by the round section of the opponent. This module cross-checks the round
+//! sections of every player and reports the inconsistencies, see
+//! [`crate::Situation::validate`].
+//!
+//! Fields which couldn't be read are already reported by the parser, and are ignored
+//! here.
+//!
+//! ## Usage
+//!
+//! ```
+//! # use openswisspairings_lib::{trf::validation::InconsistencyKind, Situation};
+//! let file_contents = "\
+//! 001    1      Alpha, Anna                       2000                             2.0          2 w 1     3 b 1
+//! 001    2      Bravo, Ben                        1900                             0.0          1 b 1  0000 - U
+//! 001    3      Charlie, Cleo                     1800                             0.0          9 w 0     1 b 0";
+//!
+//! let situation = Situation::try_from(file_contents.to_string()).unwrap();
+//! let inconsistencies: Vec<(u16, u16, InconsistencyKind)> = situation
+//!     .validate()
+//!     .iter()
+//!     .map(|i| (i.round(), i.player(), i.kind()))
+//!     .collect();
+//!
+//! assert_eq!(
+//!     inconsistencies,
+//!     [
+//!         (1, 1, InconsistencyKind::ResultMismatch(2)),
+//!         (1, 3, InconsistencyKind::UnknownOpponent(9)),
+//!         (2, 1, InconsistencyKind::ColorMismatch(3)),
+//!     ]
+//! );
+//! ```
+use std::fmt::{self, Display, Formatter};
+
+use super::player::{
+    round::{
+        ByeRoundResult, Color, LessThanOneMoveRoundResult, NotPlayedRoundResult,
+        PlayerRoundSection, RegularRoundResult, Result as RoundResult,
+    },
+    Section,
+};
+
+/// The kind of an inconsistency in the round data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InconsistencyKind {
+    /// The opponent doesn't exist, stores the starting rank number found.
+    UnknownOpponent(u16),
+
+    /// The player is paired with themselves.
+    SelfPairing,
+
+    /// The opponent isn't paired with the player in this round, stores the starting rank
+    /// number of the opponent.
+    AsymmetricPairing(u16),
+
+    /// The player is named as the opponent of several players in this round.
+    PairedTwice,
+
+    /// The players don't have opposite colours, stores the starting rank number of the
+    /// opponent.
+    ColorMismatch(u16),
+
+    /// The results of the players don't match (e.g. both won), stores the starting rank
+    /// number of the opponent.
+    ResultMismatch(u16),
+
+    /// Another player already received the pairing-allocated bye in this round.
+    MultiplePairingAllocatedByes,
+
+    /// The colour or the result can't be used with (or without) an opponent, e.g. a bye
+    /// against an opponent or a game with a colour but no opponent.
+    ImpossibleCombination,
+}
+
+impl Display for InconsistencyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOpponent(opponent) => {
+                write!(f, "the opponent {opponent} doesn't exist")
+            }
+            Self::SelfPairing => write!(f, "the player is paired with themselves"),
+            Self::AsymmetricPairing(opponent) => {
+                write!(f, "the opponent {opponent} isn't paired with the player")
+            }
+            Self::PairedTwice => write!(f, "the player is paired with several opponents"),
+            Self::ColorMismatch(opponent) => {
+                write!(f, "the colours don't match with the opponent {opponent}")
+            }
+            Self::ResultMismatch(opponent) => {
+                write!(f, "the results don't match with the opponent {opponent}")
+            }
+            Self::MultiplePairingAllocatedByes => {
+                write!(f, "another player already has the pairing-allocated bye")
+            }
+            Self::ImpossibleCombination => {
+                write!(f, "the colour and result can't be used with this opponent")
+            }
+        }
+    }
+}
+
+/// An inconsistency in the round data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Inconsistency {
+    /// The round, starting at 1.
+    round: u16,
+
+    /// Starting rank number of the player the inconsistency is about.
+    player: u16,
+
+    /// The kind of inconsistency.
+    kind: InconsistencyKind,
+}
+
+impl Inconsistency {
+    /// Get the round, starting at 1.
+    #[must_use]
+    pub const fn round(&self) -> u16 {
+        self.round
+    }
+
+    /// Get the starting rank number of the player the inconsistency is about.
+    #[must_use]
+    pub const fn player(&self) -> u16 {
+        self.player
+    }
+
+    /// Get the kind of inconsistency.
+    #[must_use]
+    pub const fn kind(&self) -> InconsistencyKind {
+        self.kind
+    }
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Round {}, player {}: {}",
+            self.round, self.player, self.kind
+        )
+    }
+}
+
+/// Whether the colours of two opponents are opposite.
+///
+/// Games which weren't played may have no colour for both players.
+const fn opposite_colors(a: Option<Color>, b: Option<Color>) -> bool {
+    matches!(
+        (a, b),
+        (Some(Color::White), Some(Color::Black))
+            | (Some(Color::Black), Some(Color::White))
+            | (Some(Color::None) | None, Some(Color::None) | None)
+    )
+}
+
+/// Whether the results of two opponents match.
+///
+/// A game which wasn't played yet has no result for both players, a double forfeit is
+/// a forfeit loss for both players.
+const fn complementary_results(a: Option<RoundResult>, b: Option<RoundResult>) -> bool {
+    use LessThanOneMoveRoundResult as Short;
+    use NotPlayedRoundResult as NotPlayed;
+    use RegularRoundResult as Regular;
+
+    matches!(
+        (a, b),
+        (None, None)
+            | (
+                Some(RoundResult::Regular(Regular::Win)),
+                Some(RoundResult::Regular(Regular::Loss))
+            )
+            | (
+                Some(RoundResult::Regular(Regular::Loss)),
+                Some(RoundResult::Regular(Regular::Win))
+            )
+            | (
+                Some(RoundResult::Regular(Regular::Draw)),
+                Some(RoundResult::Regular(Regular::Draw))
+            )
+            | (
+                Some(RoundResult::LessThanOneMove(Short::Win)),
+                Some(RoundResult::LessThanOneMove(Short::Loss))
+            )
+            | (
+                Some(RoundResult::LessThanOneMove(Short::Loss)),
+                Some(RoundResult::LessThanOneMove(Short::Win))
+            )
+            | (
+                Some(RoundResult::LessThanOneMove(Short::Draw)),
+                Some(RoundResult::LessThanOneMove(Short::Draw))
+            )
+            | (
+                Some(RoundResult::NotPlayed(NotPlayed::ForfeitWin)),
+                Some(RoundResult::NotPlayed(NotPlayed::ForfeitLoss))
+            )
+            | (
+                Some(RoundResult::NotPlayed(NotPlayed::ForfeitLoss)),
+                Some(RoundResult::NotPlayed(_))
+            )
+    )
+}
+
+/// Whether the colour and the result of a round section can be used without an
+/// opponent.
+///
+/// Only byes and forfeits can be used without an opponent, and the player has no colour.
+const fn unpaired_combination(color: Option<Color>, result: Option<RoundResult>) -> bool {
+    matches!(color, Some(Color::None) | None)
+        && matches!(
+            result,
+            Some(RoundResult::Bye(_) | RoundResult::NotPlayed(_)) | None
+        )
+}
+
+/// Whether the colour and the result of a round section can be used with an opponent.
+///
+/// A bye can't be used with an opponent, and a game (even forfeited) needs a colour.
+/// Colours which couldn't be read are ignored.
+const fn paired_combination(section: &PlayerRoundSection) -> bool {
+    match section.result() {
+        Ok(Some(RoundResult::Bye(_))) => false,
+        Ok(Some(_)) => !matches!(section.color(), Ok(Some(Color::None) | None)),
+        _ => true,
+    }
+}
+
+/// Cross-check the round sections of the players.
+///
+/// Inconsistencies are sorted by round, then by player. Inconsistencies between two
+/// players (colours and results) are only reported for the player with the lower
+/// starting rank number. Players whose starting rank number is invalid are ignored.
+///
+/// ```
+/// # use openswisspairings_lib::{trf::validation::InconsistencyKind, Situation};
+/// // Games against an opponent need a colour, and byes can't have an opponent.
+/// let file_contents = "\
+/// 001    1      Alpha, Anna                       2000                             1.0          2 - 1     3 w 0
+/// 001    2      Bravo, Ben                        1900                             0.0          1 - 0     4 b 1
+/// 001    3      Charlie, Cleo                     1800                             2.0          4 w 1     1 b 1
+/// 001    4      Delta, Dan                        1700                             1.0          3 b 0     2 - U";
+///
+/// let situation = Situation::try_from(file_contents.to_string()).unwrap();
+/// let inconsistencies: Vec<(u16, u16, InconsistencyKind)> = situation
+///     .validate()
+///     .iter()
+///     .map(|i| (i.round(), i.player(), i.kind()))
+///     .collect();
+/// assert_eq!(
+///     inconsistencies,
+///     [
+///         (1, 1, InconsistencyKind::ImpossibleCombination),
+///         (1, 2, InconsistencyKind::ImpossibleCombination),
+///         (2, 2, InconsistencyKind::ColorMismatch(4)),
+///         (2, 2, InconsistencyKind::ResultMismatch(4)),
+///         (2, 4, InconsistencyKind::ImpossibleCombination),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn validate(players: &[Section]) -> Vec<Inconsistency> {
+    let players: Vec<(u16, &Section)> = players
+        .iter()
+        .filter_map(|player| Some((*player.starting_rank_number().ok()?, player)))
+        .collect();
+    let rounds = players
+        .iter()
+        .map(|(_, player)| player.rounds().len())
+        .max()
+        .unwrap_or_default();
+
+    let mut inconsistencies = vec![];
+
+    for (round, index) in (1..).zip(0..rounds) {
+        let sections: Vec<(u16, &PlayerRoundSection)> = players
+            .iter()
+            .filter_map(|(id, player)| Some((*id, player.rounds().get(index)?.as_ref()?)))
+            .collect();
+        let opponent =
+            |section: &PlayerRoundSection| section.id().ok().copied().flatten();
+        let section = |id: u16| sections.iter().find(|(i, _)| *i == id).map(|(_, i)| *i);
+
+        let mut report = |player: u16, kind: InconsistencyKind| {
+            inconsistencies.push(Inconsistency {
+                round,
+                player,
+                kind,
+            });
+        };
+        let mut bye_allocated = false;
+
+        for (id, current) in &sections {
+            let color = current.color().ok().copied().flatten();
+            let result = current.result().ok().copied().flatten();
+
+            if result == Some(RoundResult::Bye(ByeRoundResult::PairingAllocatedBye)) {
+                if bye_allocated {
+                    report(*id, InconsistencyKind::MultiplePairingAllocatedByes);
+                }
+                bye_allocated = true;
+            }
+
+            if sections
+                .iter()
+                .filter(|(_, other)| opponent(other) == Some(*id))
+                .count()
+                > 1
+            {
+                report(*id, InconsistencyKind::PairedTwice);
+            }
+
+            let Ok(opponent_id) = current.id() else {
+                continue;
+            };
+            let Some(opponent_id) = *opponent_id else {
+                if !unpaired_combination(color, result) {
+                    report(*id, InconsistencyKind::ImpossibleCombination);
+                }
+                continue;
+            };
+
+            if !paired_combination(current) {
+                report(*id, InconsistencyKind::ImpossibleCombination);
+            }
+
+            if opponent_id == *id {
+                report(*id, InconsistencyKind::SelfPairing);
+            } else if !players.iter().any(|(i, _)| *i == opponent_id) {
+                report(*id, InconsistencyKind::UnknownOpponent(opponent_id));
+            } else if let Some(mirror) =
+                section(opponent_id).filter(|i| opponent(i) == Some(*id))
+            {
+                if *id < opponent_id {
+                    if let (Ok(a), Ok(b)) = (current.color(), mirror.color()) {
+                        if !opposite_colors(*a, *b) {
+                            report(*id, InconsistencyKind::ColorMismatch(opponent_id));
+                        }
+                    }
+                    if let (Ok(a), Ok(b)) = (current.result(), mirror.result()) {
+                        if !complementary_results(*a, *b) {
+                            report(*id, InconsistencyKind::ResultMismatch(opponent_id));
+                        }
+                    }
+                }
+            } else {
+                report(*id, InconsistencyKind::AsymmetricPairing(opponent_id));
+            }
+        }
+    }
+
+    inconsistencies
+}